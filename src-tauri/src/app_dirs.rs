@@ -0,0 +1,30 @@
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_opener::OpenerExt;
+
+// Open a directory in the OS file manager, creating it first since the log dir only exists once something logs
+fn open_directory(app: &AppHandle, dir: PathBuf) -> Result<String, String> {
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let path = dir.to_string_lossy().to_string();
+    app.opener()
+        .open_path(path.clone(), None::<&str>)
+        .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+
+    Ok(path)
+}
+
+#[tauri::command]
+pub async fn open_app_data_dir(app: AppHandle) -> Result<String, String> {
+    let dir = app.path().app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    open_directory(&app, dir)
+}
+
+#[tauri::command]
+pub async fn open_app_log_dir(app: AppHandle) -> Result<String, String> {
+    let dir = app.path().app_log_dir()
+        .map_err(|e| format!("Failed to resolve app log directory: {}", e))?;
+    open_directory(&app, dir)
+}
@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::settings::{get_setting, set_setting};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioDeviceKind {
+    Input,
+    Output,
+}
+
+impl AudioDeviceKind {
+    fn setting_key(self) -> &'static str {
+        match self {
+            AudioDeviceKind::Input => "audioInputDevice",
+            AudioDeviceKind::Output => "audioOutputDevice",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AudioDevice {
+    // cpal has no stable device ids, so the device name doubles as the id
+    pub id: String,
+    pub name: String,
+    pub is_default: bool,
+    pub is_selected: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AudioDevices {
+    pub inputs: Vec<AudioDevice>,
+    pub outputs: Vec<AudioDevice>,
+    // Output devices are still listed for text-to-speech when there is no microphone
+    pub warning: Option<String>,
+}
+
+const NO_INPUT_DEVICE_WARNING: &str = "No audio input devices were found. Connect a microphone to use dictation.";
+
+#[cfg(desktop)]
+fn enumerate_device_names(kind: AudioDeviceKind) -> Result<(Vec<String>, Option<String>), String> {
+    use cpal::traits::{DeviceTrait, HostTrait};
+
+    let host = cpal::default_host();
+    let (devices, default_device) = match kind {
+        AudioDeviceKind::Input => (
+            host.input_devices().map(|devices| devices.collect::<Vec<_>>()),
+            host.default_input_device(),
+        ),
+        AudioDeviceKind::Output => (
+            host.output_devices().map(|devices| devices.collect::<Vec<_>>()),
+            host.default_output_device(),
+        ),
+    };
+
+    let devices = devices.map_err(|e| format!("Failed to list audio devices: {}", e))?;
+    let mut names: Vec<String> = devices.iter().filter_map(|device| device.name().ok()).collect();
+    // Some hosts list a device once per channel configuration; dedup only drops adjacent repeats
+    names.sort();
+    names.dedup();
+
+    Ok((names, default_device.and_then(|device| device.name().ok())))
+}
+
+#[cfg(mobile)]
+fn enumerate_device_names(_kind: AudioDeviceKind) -> Result<(Vec<String>, Option<String>), String> {
+    Err("Audio device selection is not supported on this platform".to_string())
+}
+
+fn list_devices(app: &AppHandle, kind: AudioDeviceKind) -> Result<Vec<AudioDevice>, String> {
+    let (names, default_name) = enumerate_device_names(kind)?;
+    let selected = get_setting::<String>(app, kind.setting_key())?;
+
+    Ok(names
+        .into_iter()
+        .map(|name| AudioDevice {
+            id: name.clone(),
+            is_default: default_name.as_deref() == Some(name.as_str()),
+            is_selected: selected.as_deref() == Some(name.as_str()),
+            name,
+        })
+        .collect())
+}
+
+pub fn list_all_devices(app: &AppHandle) -> Result<AudioDevices, String> {
+    let inputs = list_devices(app, AudioDeviceKind::Input)?;
+    let outputs = list_devices(app, AudioDeviceKind::Output)?;
+    let warning = inputs.is_empty().then(|| NO_INPUT_DEVICE_WARNING.to_string());
+
+    Ok(AudioDevices { inputs, outputs, warning })
+}
+
+// cpal has no hot-plug notifications, so poll the device lists and emit when they change
+#[cfg(desktop)]
+pub fn start_device_watcher(app: &AppHandle) {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tauri::Emitter;
+
+    static WATCHER_STARTED: AtomicBool = AtomicBool::new(false);
+    if WATCHER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut last = list_all_devices(&app).ok();
+        loop {
+            std::thread::sleep(std::time::Duration::from_secs(3));
+            let current = list_all_devices(&app).ok();
+            if current.is_some() && current != last {
+                if let Err(e) = app.emit("audio-devices-changed", &current) {
+                    eprintln!("Warning: Failed to emit audio device change: {}", e);
+                }
+                last = current;
+            }
+        }
+    });
+}
+
+#[cfg(mobile)]
+pub fn start_device_watcher(_app: &AppHandle) {}
+
+#[tauri::command]
+pub async fn list_audio_devices(app: AppHandle) -> Result<AudioDevices, String> {
+    start_device_watcher(&app);
+
+    let handle = app.clone();
+    tokio::task::spawn_blocking(move || list_all_devices(&handle))
+        .await
+        .map_err(|e| format!("Failed to list audio devices: {}", e))?
+}
+
+#[tauri::command]
+pub async fn set_audio_device(app: AppHandle, kind: AudioDeviceKind, device_id: String) -> Result<(), String> {
+    let (names, _) = tokio::task::spawn_blocking(move || enumerate_device_names(kind))
+        .await
+        .map_err(|e| format!("Failed to list audio devices: {}", e))??;
+
+    if names.is_empty() {
+        return Err(match kind {
+            AudioDeviceKind::Input => NO_INPUT_DEVICE_WARNING.to_string(),
+            AudioDeviceKind::Output => "No audio output devices were found.".to_string(),
+        });
+    }
+    if !names.contains(&device_id) {
+        return Err(format!("Audio device '{}' is not connected", device_id));
+    }
+
+    set_setting(&app, kind.setting_key(), &device_id)
+}
@@ -0,0 +1,263 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::ollama::{list_running_models, OLLAMA_BASE_URL};
+use crate::operations::{self, OperationKind};
+use crate::param_presets::{merge_preset_options, preset_options};
+use crate::thermal::{read_thermal_status, ThrottleState};
+
+const BENCHMARK_TIMEOUT: Duration = Duration::from_secs(600);
+const MAX_BENCHMARK_TOKENS: usize = 4096;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchResult {
+    pub model: String,
+    // Time Ollama spent loading the model before it could start
+    pub load_ms: Option<u64>,
+    // Request start to first generated token, including the load
+    pub first_token_ms: Option<u64>,
+    pub total_ms: u64,
+    pub prompt_tokens: Option<u64>,
+    pub completion_tokens: Option<u64>,
+    pub prompt_tokens_per_second: Option<f64>,
+    // Generation speed from Ollama's own eval timing, so load and prompt processing don't skew it
+    pub tokens_per_second: Option<f64>,
+    // Set when the machine was thermally throttling during the run, which makes the numbers read low
+    pub throttled: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkProgress {
+    pub index: usize,
+    pub total: usize,
+    pub model: String,
+    // None when the model is about to start, set once it has finished
+    pub result: Option<BenchResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BenchmarkChunk {
+    #[serde(default)]
+    response: String,
+    #[serde(default)]
+    done: bool,
+    error: Option<String>,
+    // Durations are reported in nanoseconds
+    load_duration: Option<u64>,
+    prompt_eval_count: Option<u64>,
+    prompt_eval_duration: Option<u64>,
+    eval_count: Option<u64>,
+    eval_duration: Option<u64>,
+}
+
+fn tokens_per_second(count: Option<u64>, duration_ns: Option<u64>) -> Option<f64> {
+    match (count, duration_ns) {
+        (Some(count), Some(duration_ns)) if duration_ns > 0 => Some(count as f64 / (duration_ns as f64 / 1e9)),
+        _ => None,
+    }
+}
+
+async fn stream_benchmark(
+    client: &reqwest::Client,
+    model: &str,
+    prompt: &str,
+    num_tokens: usize,
+    preset: &serde_json::Value,
+    started: Instant,
+    result: &mut BenchResult,
+) -> Result<(), String> {
+    // Fixed seed and zero temperature so every model answers the same way each run, unless a preset says otherwise
+    let mut options = merge_preset_options(serde_json::json!({ "temperature": 0, "seed": 42 }), preset);
+    options["num_predict"] = num_tokens.into();
+    let mut response = client
+        .post(format!("{}/api/generate", OLLAMA_BASE_URL))
+        .json(&serde_json::json!({
+            "model": model,
+            "prompt": prompt,
+            "stream": true,
+            "options": options
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Ollama returned {}: {}", status, body.trim()));
+    }
+
+    let mut buffer: Vec<u8> = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to read Ollama response: {}", e))?
+    {
+        buffer.extend_from_slice(&chunk);
+
+        while let Some(newline) = buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let parsed: BenchmarkChunk = serde_json::from_str(line)
+                .map_err(|e| format!("Failed to parse Ollama response: {}", e))?;
+            if let Some(error) = parsed.error {
+                return Err(error);
+            }
+
+            if !parsed.response.is_empty() && result.first_token_ms.is_none() {
+                result.first_token_ms = Some(started.elapsed().as_millis() as u64);
+            }
+
+            if parsed.done {
+                result.load_ms = parsed.load_duration.map(|duration| duration / 1_000_000);
+                result.prompt_tokens = parsed.prompt_eval_count;
+                result.completion_tokens = parsed.eval_count;
+                result.prompt_tokens_per_second = tokens_per_second(parsed.prompt_eval_count, parsed.prompt_eval_duration);
+                result.tokens_per_second = tokens_per_second(parsed.eval_count, parsed.eval_duration);
+                return Ok(());
+            }
+        }
+    }
+
+    Err("Ollama closed the connection before the response finished".to_string())
+}
+
+// keep_alive 0 tells Ollama to evict the model right away, freeing its memory for the next one
+async fn unload_model(client: &reqwest::Client, model: &str) {
+    let unloaded = client
+        .post(format!("{}/api/generate", OLLAMA_BASE_URL))
+        .json(&serde_json::json!({ "model": model, "keep_alive": 0 }))
+        .send()
+        .await;
+    if let Err(e) = unloaded {
+        eprintln!("Warning: Failed to unload {}: {}", model, e);
+    }
+}
+
+pub async fn benchmark_model(
+    client: &reqwest::Client,
+    model: &str,
+    prompt: &str,
+    num_tokens: usize,
+    preset: &serde_json::Value,
+) -> BenchResult {
+    let started = Instant::now();
+    let mut result = BenchResult {
+        model: model.to_string(),
+        load_ms: None,
+        first_token_ms: None,
+        total_ms: 0,
+        prompt_tokens: None,
+        completion_tokens: None,
+        prompt_tokens_per_second: None,
+        tokens_per_second: None,
+        throttled: false,
+        error: None,
+    };
+
+    let run = tokio::time::timeout(
+        BENCHMARK_TIMEOUT,
+        stream_benchmark(client, model, prompt, num_tokens, preset, started, &mut result),
+    )
+    .await;
+    match run {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => result.error = Some(e),
+        Err(_) => result.error = Some(format!("Benchmark timed out after {} seconds", BENCHMARK_TIMEOUT.as_secs())),
+    }
+    result.total_ms = started.elapsed().as_millis() as u64;
+
+    result.throttled = tokio::task::spawn_blocking(read_thermal_status)
+        .await
+        .map(|status| status.throttling == ThrottleState::Throttling)
+        .unwrap_or(false);
+    result
+}
+
+#[tauri::command]
+pub async fn benchmark_models(
+    app: AppHandle,
+    models: Vec<String>,
+    prompt: String,
+    num_tokens: usize,
+    preset: Option<String>,
+) -> Result<Vec<BenchResult>, String> {
+    if models.is_empty() {
+        return Err("Select at least one model to benchmark".to_string());
+    }
+    if num_tokens == 0 || num_tokens > MAX_BENCHMARK_TOKENS {
+        return Err(format!("num_tokens must be between 1 and {}", MAX_BENCHMARK_TOKENS));
+    }
+    let preset = preset_options(&app, preset.as_deref())?;
+
+    let client = reqwest::Client::builder()
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    // Anything already resident would compete with the benchmarked model for memory, so start from an empty server
+    match list_running_models(&client).await {
+        Ok(running) => {
+            for model in running {
+                unload_model(&client, &model).await;
+            }
+        }
+        Err(e) => eprintln!("Warning: Failed to list loaded models before benchmarking: {}", e),
+    }
+
+    // The model being measured right now, so it can still be unloaded if the run is cancelled mid-model
+    let current_model: Mutex<Option<String>> = Mutex::new(None);
+
+    let label = format!("Benchmarking {} models", models.len());
+    let (app_ref, client_ref, models_ref, prompt_ref, current_ref) = (&app, &client, &models, &prompt, &current_model);
+    let preset_ref = &preset;
+    let outcome = operations::run(OperationKind::Benchmark, label, |operation_id| async move {
+        let total = models_ref.len();
+        let mut results = Vec::with_capacity(total);
+
+        // One model at a time, unloading each before the next, so no run competes for VRAM
+        for (index, model) in models_ref.iter().enumerate() {
+            let mut progress = BenchmarkProgress {
+                index,
+                total,
+                model: model.clone(),
+                result: None,
+            };
+            if let Err(e) = app_ref.emit("benchmark-progress", &progress) {
+                eprintln!("Warning: Failed to emit benchmark progress: {}", e);
+            }
+
+            if let Ok(mut current) = current_ref.lock() {
+                *current = Some(model.clone());
+            }
+            let result = benchmark_model(client_ref, model, prompt_ref, num_tokens, preset_ref).await;
+            unload_model(client_ref, model).await;
+            if let Ok(mut current) = current_ref.lock() {
+                *current = None;
+            }
+            operations::set_progress(operation_id, (index + 1) as f64 / total as f64);
+
+            progress.result = Some(result.clone());
+            if let Err(e) = app_ref.emit("benchmark-progress", &progress) {
+                eprintln!("Warning: Failed to emit benchmark progress: {}", e);
+            }
+            results.push(result);
+        }
+        Ok(results)
+    })
+    .await;
+
+    // A cancelled run is dropped at its next await, which can leave the current model loaded
+    let interrupted = current_model.lock().ok().and_then(|mut current| current.take());
+    if let Some(model) = interrupted {
+        unload_model(&client, &model).await;
+    }
+    outcome
+}
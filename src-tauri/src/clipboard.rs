@@ -0,0 +1,84 @@
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+// Image in the same shape the frontend stores for message attachments,
+// so it can be dropped straight into a vision request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatImage {
+    pub url: String,
+    pub mime_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ClipboardImageResult {
+    Image {
+        image: ChatImage,
+        width: u32,
+        height: u32,
+    },
+    NoImage,
+}
+
+pub fn encode_image_for_chat(bytes: &[u8], mime_type: &str) -> ChatImage {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+
+    ChatImage {
+        url: format!("data:{};base64,{}", mime_type, encoded),
+        mime_type: mime_type.to_string(),
+    }
+}
+
+#[cfg(desktop)]
+fn encode_rgba_as_png(width: u32, height: u32, rgba: &[u8]) -> Result<Vec<u8>, String> {
+    let mut png_bytes = Vec::new();
+
+    {
+        let mut encoder = png::Encoder::new(&mut png_bytes, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()
+            .map_err(|e| format!("Failed to write PNG header: {}", e))?;
+        writer.write_image_data(rgba)
+            .map_err(|e| format!("Failed to encode clipboard image: {}", e))?;
+    }
+
+    Ok(png_bytes)
+}
+
+#[cfg(desktop)]
+pub fn read_clipboard_image() -> Result<ClipboardImageResult, String> {
+    let mut clipboard = arboard::Clipboard::new()
+        .map_err(|e| format!("Failed to access clipboard: {}", e))?;
+
+    // arboard normalizes the platform formats (TIFF/PNG on macOS, DIB on Windows,
+    // PNG on X11/Wayland) into raw RGBA pixels
+    let image = match clipboard.get_image() {
+        Ok(image) => image,
+        Err(arboard::Error::ContentNotAvailable) => return Ok(ClipboardImageResult::NoImage),
+        Err(e) => return Err(format!("Failed to read image from clipboard: {}", e)),
+    };
+
+    let width = image.width as u32;
+    let height = image.height as u32;
+    let png_bytes = encode_rgba_as_png(width, height, &image.bytes)?;
+
+    Ok(ClipboardImageResult::Image {
+        image: encode_image_for_chat(&png_bytes, "image/png"),
+        width,
+        height,
+    })
+}
+
+#[cfg(mobile)]
+pub fn read_clipboard_image() -> Result<ClipboardImageResult, String> {
+    Err("Reading images from the clipboard is not supported on this platform".to_string())
+}
+
+#[tauri::command]
+pub async fn get_clipboard_image() -> Result<ClipboardImageResult, String> {
+    tokio::task::spawn_blocking(read_clipboard_image)
+        .await
+        .map_err(|e| format!("Clipboard task failed: {}", e))?
+}
@@ -0,0 +1,322 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::ollama::{embed_texts, list_installed_tags, with_keep_alive, InstalledTag, OLLAMA_BASE_URL};
+use crate::operations::{self, OperationKind};
+use crate::param_presets::preset_options;
+use crate::system_info::{estimate_model_memory_requirements, get_system_resources};
+
+const COMPARE_TIMEOUT: Duration = Duration::from_secs(300);
+// Cap on the distinctive words reported for each side of the lexical diff
+const MAX_DIFF_WORDS: usize = 25;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompareSlot {
+    A,
+    B,
+}
+
+impl CompareSlot {
+    // Each side streams on its own event so the UI can route chunks without inspecting them
+    fn chunk_event(self) -> &'static str {
+        match self {
+            CompareSlot::A => "model-compare-chunk-a",
+            CompareSlot::B => "model-compare-chunk-b",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompareChunk {
+    // Lets the UI tell apart comparisons that run at the same time
+    pub operation_id: u64,
+    pub slot: CompareSlot,
+    pub model: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelOutput {
+    pub model: String,
+    pub output: String,
+    pub latency_ms: u64,
+    pub first_token_ms: Option<u64>,
+    pub prompt_tokens: Option<u64>,
+    pub completion_tokens: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelComparison {
+    pub a: ModelOutput,
+    pub b: ModelOutput,
+    pub ran_in_parallel: bool,
+    // Cosine similarity of the two outputs' embeddings; None when no embedding model is installed
+    pub semantic_similarity: Option<f64>,
+    pub embedding_model: Option<String>,
+    // Jaccard similarity of the two outputs' word sets
+    pub lexical_similarity: f64,
+    pub only_in_a: Vec<String>,
+    pub only_in_b: Vec<String>,
+}
+
+// What both models are asked: the prompt plus sampling options from an optional preset, tagged
+// with the comparison's operation id
+struct CompareRequest<'a> {
+    operation_id: u64,
+    prompt: &'a str,
+    options: &'a serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenerateChunk {
+    #[serde(default)]
+    response: String,
+    #[serde(default)]
+    done: bool,
+    error: Option<String>,
+    prompt_eval_count: Option<u64>,
+    eval_count: Option<u64>,
+}
+
+fn words(text: &str) -> BTreeSet<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+fn cosine_similarity(a: &[f64], b: &[f64]) -> Option<f64> {
+    if a.len() != b.len() || a.is_empty() {
+        return None;
+    }
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let norm_b = b.iter().map(|y| y * y).sum::<f64>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return None;
+    }
+    Some(dot / (norm_a * norm_b))
+}
+
+async fn stream_model_output(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    slot: CompareSlot,
+    model: &str,
+    request: &CompareRequest<'_>,
+    started: Instant,
+    output: &mut ModelOutput,
+) -> Result<(), String> {
+    let mut response = client
+        .post(format!("{}/api/generate", OLLAMA_BASE_URL))
+        .json(&with_keep_alive(app, serde_json::json!({
+            "model": model,
+            "prompt": request.prompt,
+            "options": request.options,
+            "stream": true
+        })))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Ollama returned {}: {}", status, body.trim()));
+    }
+
+    // Buffer raw bytes so multi-byte characters split across chunks stay intact
+    let mut buffer: Vec<u8> = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to read Ollama response: {}", e))?
+    {
+        buffer.extend_from_slice(&chunk);
+
+        while let Some(newline) = buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let parsed: GenerateChunk = serde_json::from_str(line)
+                .map_err(|e| format!("Failed to parse Ollama response: {}", e))?;
+            if let Some(error) = parsed.error {
+                return Err(error);
+            }
+
+            if !parsed.response.is_empty() {
+                if output.first_token_ms.is_none() {
+                    output.first_token_ms = Some(started.elapsed().as_millis() as u64);
+                }
+                output.output.push_str(&parsed.response);
+
+                let chunk = CompareChunk {
+                    operation_id: request.operation_id,
+                    slot,
+                    model: model.to_string(),
+                    content: parsed.response,
+                };
+                if let Err(e) = app.emit(slot.chunk_event(), chunk) {
+                    eprintln!("Warning: Failed to emit comparison chunk: {}", e);
+                }
+            }
+
+            if parsed.done {
+                output.prompt_tokens = parsed.prompt_eval_count;
+                output.completion_tokens = parsed.eval_count;
+                return Ok(());
+            }
+        }
+    }
+
+    Err("Ollama closed the connection before the response finished".to_string())
+}
+
+async fn run_model(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    slot: CompareSlot,
+    model: &str,
+    request: &CompareRequest<'_>,
+) -> ModelOutput {
+    let started = Instant::now();
+    let mut output = ModelOutput {
+        model: model.to_string(),
+        output: String::new(),
+        latency_ms: 0,
+        first_token_ms: None,
+        prompt_tokens: None,
+        completion_tokens: None,
+        error: None,
+    };
+
+    let result = tokio::time::timeout(
+        COMPARE_TIMEOUT,
+        stream_model_output(app, client, slot, model, request, started, &mut output),
+    )
+    .await;
+
+    output.latency_ms = started.elapsed().as_millis() as u64;
+    output.error = match result {
+        Err(_) => Some(format!("No complete response within {} seconds", COMPARE_TIMEOUT.as_secs())),
+        Ok(Err(error)) => Some(error),
+        Ok(Ok(())) => None,
+    };
+    output
+}
+
+fn find_tag<'a>(installed: &'a [InstalledTag], model: &str) -> Option<&'a InstalledTag> {
+    installed
+        .iter()
+        .find(|tag| tag.name == model || tag.name.strip_suffix(":latest") == Some(model))
+}
+
+// Two models only run side by side when both fit in memory at once; otherwise Ollama would swap them
+async fn can_run_in_parallel(installed: &[InstalledTag], model_a: &str, model_b: &str) -> bool {
+    let (Some(tag_a), Some(tag_b)) = (find_tag(installed, model_a), find_tag(installed, model_b)) else {
+        return false;
+    };
+    let Ok(resources) = get_system_resources().await else {
+        return false;
+    };
+
+    let required_gb = estimate_model_memory_requirements(tag_a.size, &tag_a.name)
+        + estimate_model_memory_requirements(tag_b.size, &tag_b.name);
+    required_gb <= resources.available_memory_gb - 2.0
+}
+
+async fn embedding_similarity(client: &reqwest::Client, installed: &[InstalledTag], a: &str, b: &str) -> Option<(f64, String)> {
+    let model = installed.iter().find(|tag| tag.name.to_lowercase().contains("embed"))?.name.clone();
+
+    let embeddings = match embed_texts(client, &model, &[a, b]).await {
+        Ok(embeddings) => embeddings,
+        Err(e) => {
+            eprintln!("Warning: Failed to embed comparison outputs: {}", e);
+            return None;
+        }
+    };
+
+    match embeddings.as_slice() {
+        [embedding_a, embedding_b] => cosine_similarity(embedding_a, embedding_b).map(|similarity| (similarity, model)),
+        _ => None,
+    }
+}
+
+#[tauri::command]
+pub async fn compare_model_outputs(
+    app: AppHandle,
+    prompt: String,
+    model_a: String,
+    model_b: String,
+    preset: Option<String>,
+) -> Result<ModelComparison, String> {
+    if prompt.trim().is_empty() {
+        return Err("Prompt cannot be empty".to_string());
+    }
+    let options = preset_options(&app, preset.as_deref())?;
+
+    let client = reqwest::Client::new();
+    let installed = list_installed_tags(&client).await?;
+    for model in [&model_a, &model_b] {
+        if find_tag(&installed, model).is_none() {
+            return Err(format!("{} is not installed in Ollama", model));
+        }
+    }
+
+    let ran_in_parallel = can_run_in_parallel(&installed, &model_a, &model_b).await;
+    let label = format!("Comparing {} and {}", model_a, model_b);
+    let (app_ref, client_ref) = (&app, &client);
+    let (model_a_ref, model_b_ref) = (model_a.as_str(), model_b.as_str());
+    let (prompt_ref, options_ref) = (prompt.as_str(), &options);
+    let (a, b) = operations::run(OperationKind::Comparison, label, |operation_id| async move {
+        let request = CompareRequest { operation_id, prompt: prompt_ref, options: options_ref };
+        if ran_in_parallel {
+            Ok(tokio::join!(
+                run_model(app_ref, client_ref, CompareSlot::A, model_a_ref, &request),
+                run_model(app_ref, client_ref, CompareSlot::B, model_b_ref, &request),
+            ))
+        } else {
+            let a = run_model(app_ref, client_ref, CompareSlot::A, model_a_ref, &request).await;
+            operations::set_progress(operation_id, 0.5);
+            let b = run_model(app_ref, client_ref, CompareSlot::B, model_b_ref, &request).await;
+            Ok((a, b))
+        }
+    })
+    .await?;
+
+    let words_a = words(&a.output);
+    let words_b = words(&b.output);
+    let union = words_a.union(&words_b).count();
+    let lexical_similarity = if union == 0 {
+        1.0
+    } else {
+        words_a.intersection(&words_b).count() as f64 / union as f64
+    };
+    let only_in_a = words_a.difference(&words_b).take(MAX_DIFF_WORDS).cloned().collect();
+    let only_in_b = words_b.difference(&words_a).take(MAX_DIFF_WORDS).cloned().collect();
+
+    let embedding = if a.error.is_none() && b.error.is_none() {
+        embedding_similarity(&client, &installed, &a.output, &b.output).await
+    } else {
+        None
+    };
+
+    Ok(ModelComparison {
+        a,
+        b,
+        ran_in_parallel,
+        semantic_similarity: embedding.as_ref().map(|(similarity, _)| *similarity),
+        embedding_model: embedding.map(|(_, model)| model),
+        lexical_similarity,
+        only_in_a,
+        only_in_b,
+    })
+}
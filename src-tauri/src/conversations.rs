@@ -0,0 +1,574 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Row, Sqlite};
+use tauri::AppHandle;
+
+use crate::database::{ensure_column, get_database_pool};
+use crate::messages::{normalize_conversation, ChatMessage, MessageRole, NormalizedConversation, ProviderKind};
+use crate::models::ModelRef;
+use crate::param_presets::{find_param_preset, preset_options};
+use crate::ollama::{effective_num_ctx, list_installed_tags};
+use crate::secrets::get_api_key;
+use crate::settings::get_setting;
+use crate::summarize::{complete_with_ollama, extractive_summary, truncate_chars, MAX_INPUT_CHARS};
+
+// Bumped whenever the normalization below changes, so old and new fingerprints never collide
+const FINGERPRINT_VERSION: &str = "v1";
+
+// Re-summarize once a conversation has grown by this many messages or by half since the last summary
+const SUMMARY_MIN_NEW_MESSAGES: i64 = 6;
+const SUMMARY_GROWTH_FACTOR: f64 = 1.5;
+const SUMMARY_MAX_TOKENS: usize = 80;
+
+// Local models are served through the frontend's built-in "local" (Ollama) provider
+const LOCAL_PROVIDER_ID: &str = "local";
+
+// Left free for the model's reply when fitting history into the context window
+const RESPONSE_TOKEN_RESERVE: u64 = 1024;
+// Rough cost of one attached image; providers bill a few hundred to ~1.5k tokens depending on size
+const IMAGE_TOKEN_ESTIMATE: u64 = 768;
+
+// Same format the frontend's new Date().toISOString() produces
+const SQL_NOW: &str = "strftime('%Y-%m-%dT%H:%M:%fZ', 'now')";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelSwitch {
+    pub conversation_id: i64,
+    pub previous_provider: String,
+    pub previous_model: String,
+    pub provider: String,
+    pub model: String,
+    // Last message produced before the switch, so the transcript can mark where it happened
+    pub after_message_id: Option<i64>,
+    // History re-normalized for the new provider, ready to send
+    pub context: NormalizedConversation,
+    pub context_window: Option<u64>,
+    pub estimated_tokens: u64,
+    pub trimmed_messages: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationSummary {
+    pub summary: String,
+    pub message_count: i64,
+    // False when the stored summary was still current and returned as-is
+    pub regenerated: bool,
+}
+
+// The frontend owns the schema, but the fingerprint column is only used from here
+async fn ensure_fingerprint_column(pool: &Pool<Sqlite>) -> Result<(), String> {
+    ensure_column(pool, "conversations", "fingerprint", "TEXT").await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_conversations_fingerprint ON conversations(fingerprint)")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to create fingerprint index: {}", e))?;
+
+    // Messages are written by the frontend, so clear the fingerprint whenever they change and recompute it on lookup
+    for (event, row) in [("INSERT", "NEW"), ("UPDATE", "NEW"), ("DELETE", "OLD")] {
+        sqlx::query(&format!(
+            "CREATE TRIGGER IF NOT EXISTS clear_fingerprint_after_message_{} AFTER {} ON messages \
+             BEGIN UPDATE conversations SET fingerprint = NULL WHERE id = {}.conversation_id; END",
+            event.to_lowercase(),
+            event,
+            row
+        ))
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to create fingerprint trigger: {}", e))?;
+    }
+
+    Ok(())
+}
+
+// Line endings and surrounding whitespace vary between exporters, so they don't count as content
+fn normalize_text(text: &str) -> String {
+    text.replace("\r\n", "\n").trim().to_string()
+}
+
+pub fn compute_fingerprint(messages: &[(String, String)]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(FINGERPRINT_VERSION.as_bytes());
+
+    // Length-prefix each field so ("ab", "c") and ("a", "bc") can't hash the same
+    for (role, text) in messages {
+        let role = role.trim().to_lowercase();
+        let text = normalize_text(text);
+        hasher.update((role.len() as u64).to_le_bytes());
+        hasher.update(role.as_bytes());
+        hasher.update((text.len() as u64).to_le_bytes());
+        hasher.update(text.as_bytes());
+    }
+
+    format!("{:x}", hasher.finalize())
+}
+
+async fn load_message_texts(pool: &Pool<Sqlite>, conversation_id: i64) -> Result<Vec<(String, String)>, String> {
+    let rows = sqlx::query("SELECT role, text FROM messages WHERE conversation_id = ? ORDER BY created_at ASC, id ASC")
+        .bind(conversation_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to load messages for conversation {}: {}", conversation_id, e))?;
+
+    rows.iter()
+        .map(|row| {
+            let role: String = row.try_get("role")
+                .map_err(|e| format!("Failed to read message role: {}", e))?;
+            let text: Option<String> = row.try_get("text")
+                .map_err(|e| format!("Failed to read message text: {}", e))?;
+            Ok((role, text.unwrap_or_default()))
+        })
+        .collect()
+}
+
+async fn store_fingerprint(pool: &Pool<Sqlite>, id: i64) -> Result<String, String> {
+    let messages = load_message_texts(pool, id).await?;
+    let fingerprint = compute_fingerprint(&messages);
+
+    sqlx::query("UPDATE conversations SET fingerprint = ? WHERE id = ?")
+        .bind(&fingerprint)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to store fingerprint for conversation {}: {}", id, e))?;
+
+    Ok(fingerprint)
+}
+
+#[tauri::command]
+pub async fn conversation_fingerprint(app: AppHandle, id: i64) -> Result<String, String> {
+    let pool = get_database_pool(&app).await?;
+    ensure_fingerprint_column(&pool).await?;
+    store_fingerprint(&pool, id).await
+}
+
+// Lets an importer skip conversations that are already present
+#[tauri::command]
+pub async fn find_conversation_by_fingerprint(app: AppHandle, fingerprint: String) -> Result<Option<i64>, String> {
+    let pool = get_database_pool(&app).await?;
+    ensure_fingerprint_column(&pool).await?;
+    // Conversations in the retention trash count as deleted
+    ensure_column(&pool, "conversations", "deleted_at", "DATETIME").await?;
+
+    // Conversations that were never fingerprinted or whose messages changed since have no fingerprint stored
+    let stale: Vec<i64> = sqlx::query_scalar("SELECT id FROM conversations WHERE fingerprint IS NULL AND deleted_at IS NULL")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| format!("Failed to load conversations to fingerprint: {}", e))?;
+    for id in stale {
+        store_fingerprint(&pool, id).await?;
+    }
+
+    let row = sqlx::query("SELECT id FROM conversations WHERE fingerprint = ? AND deleted_at IS NULL LIMIT 1")
+        .bind(&fingerprint)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| format!("Failed to look up fingerprint: {}", e))?;
+
+    row.map(|row| row.try_get("id").map_err(|e| format!("Failed to read conversation id: {}", e)))
+        .transpose()
+}
+
+fn summary_is_stale(message_count: i64, watermark: Option<i64>) -> bool {
+    match watermark {
+        None => true,
+        Some(watermark) => {
+            message_count - watermark >= SUMMARY_MIN_NEW_MESSAGES
+                || message_count as f64 >= watermark as f64 * SUMMARY_GROWTH_FACTOR
+        }
+    }
+}
+
+fn build_transcript(messages: &[(String, String)]) -> String {
+    messages
+        .iter()
+        .filter(|(role, text)| role != "system" && !text.trim().is_empty())
+        .map(|(role, text)| format!("{}: {}", role, text.trim()))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[tauri::command]
+pub async fn summarize_conversation(
+    app: AppHandle,
+    id: i64,
+    model: Option<String>,
+    preset: Option<String>,
+) -> Result<ConversationSummary, String> {
+    let preset = preset_options(&app, preset.as_deref())?;
+    let pool = get_database_pool(&app).await?;
+    ensure_column(&pool, "conversations", "summary", "TEXT").await?;
+    ensure_column(&pool, "conversations", "summary_message_count", "INTEGER").await?;
+
+    let row = sqlx::query("SELECT summary, summary_message_count FROM conversations WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| format!("Failed to load conversation {}: {}", id, e))?
+        .ok_or_else(|| format!("Conversation {} not found", id))?;
+    let existing_summary: Option<String> = row.try_get("summary").unwrap_or(None);
+    let watermark: Option<i64> = row.try_get("summary_message_count").unwrap_or(None);
+
+    let messages = load_message_texts(&pool, id).await?;
+    let message_count = messages.len() as i64;
+
+    if let Some(summary) = existing_summary.filter(|_| !summary_is_stale(message_count, watermark)) {
+        return Ok(ConversationSummary {
+            summary,
+            message_count: watermark.unwrap_or(message_count),
+            regenerated: false,
+        });
+    }
+
+    let transcript = build_transcript(&messages);
+    if transcript.is_empty() {
+        return Err(format!("Conversation {} has no messages to summarize", id));
+    }
+
+    // Long conversations keep their most recent part, which best reflects where they ended up
+    let transcript_chars = transcript.chars().count();
+    let recent = if transcript_chars > MAX_INPUT_CHARS {
+        transcript.chars().skip(transcript_chars - MAX_INPUT_CHARS).collect::<String>()
+    } else {
+        transcript.clone()
+    };
+
+    let prompt = format!(
+        "Summarize what this conversation is about in one or two short sentences for a sidebar. \
+         Reply with the summary only.\n\n---\n{}",
+        recent
+    );
+
+    let summary = match complete_with_ollama(&app, &prompt, SUMMARY_MAX_TOKENS, model, &preset).await {
+        Ok(summary) => summary,
+        Err(e) => {
+            eprintln!("Warning: Falling back to extractive conversation summary: {}", e);
+            extractive_summary(truncate_chars(&transcript, MAX_INPUT_CHARS), SUMMARY_MAX_TOKENS / 2)
+        }
+    };
+
+    sqlx::query("UPDATE conversations SET summary = ?, summary_message_count = ? WHERE id = ?")
+        .bind(&summary)
+        .bind(message_count)
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to store summary for conversation {}: {}", id, e))?;
+
+    Ok(ConversationSummary {
+        summary,
+        message_count,
+        regenerated: true,
+    })
+}
+
+async fn ensure_model_switches_table(pool: &Pool<Sqlite>) -> Result<(), String> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS conversation_model_switches (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            conversation_id INTEGER NOT NULL,
+            from_provider TEXT,
+            from_model TEXT,
+            to_provider TEXT NOT NULL,
+            to_model TEXT NOT NULL,
+            after_message_id INTEGER,
+            created_at DATETIME,
+            FOREIGN KEY (conversation_id) REFERENCES conversations (id) ON DELETE CASCADE
+        )",
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to create model switch table: {}", e))?;
+
+    Ok(())
+}
+
+// Rough count for fitting history into a context window; real tokenizers average ~4 chars per token
+fn estimate_tokens(message: &ChatMessage) -> u64 {
+    let (chars, images) = match &message.content {
+        serde_json::Value::String(text) => (text.chars().count(), 0),
+        // Counting base64 attachment data as text would make any image look like it fills the window
+        serde_json::Value::Array(parts) => parts.iter().fold((0, 0), |(chars, images), part| {
+            match part.get("text").and_then(|text| text.as_str()) {
+                Some(text) => (chars + text.chars().count(), images),
+                None => (chars, images + 1),
+            }
+        }),
+        other => (other.to_string().chars().count(), 0),
+    };
+    (chars as u64).div_ceil(4) + images * IMAGE_TOKEN_ESTIMATE + 4
+}
+
+// The shapes the frontend's message store keeps in the images and files columns
+#[derive(Debug, Deserialize)]
+struct StoredImage {
+    url: Option<String>,
+    // Holds the base64 data for attached images
+    file_path: Option<String>,
+    mime_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StoredFile {
+    name: String,
+    #[serde(rename = "type", default)]
+    mime_type: String,
+    // Base64 file content
+    content: Option<String>,
+}
+
+fn parse_attachments<T: serde::de::DeserializeOwned>(json: Option<String>, column: &str) -> Vec<T> {
+    let Some(json) = json.filter(|json| !json.trim().is_empty()) else {
+        return Vec::new();
+    };
+    serde_json::from_str::<Option<Vec<T>>>(&json)
+        .unwrap_or_else(|e| {
+            eprintln!("Warning: Skipping unreadable {} attachments: {}", column, e);
+            None
+        })
+        .unwrap_or_default()
+}
+
+// Same content parts the frontend's chat service builds, so attachments survive a switch to another provider
+fn message_content(text: String, images: Vec<StoredImage>, files: Vec<StoredFile>, kind: ProviderKind) -> serde_json::Value {
+    if images.is_empty() && files.is_empty() {
+        return serde_json::Value::String(text);
+    }
+
+    let mut text = text;
+    let mut parts = Vec::new();
+    for image in images {
+        let mime_type = image.mime_type.unwrap_or_else(|| "image/png".to_string());
+        let data = image
+            .file_path
+            .or_else(|| image.url.as_deref().and_then(|url| url.split_once(',')).map(|(_, data)| data.to_string()))
+            .unwrap_or_default();
+        parts.push(match kind {
+            ProviderKind::Anthropic => serde_json::json!({
+                "type": "image",
+                "source": { "type": "base64", "media_type": mime_type, "data": data }
+            }),
+            ProviderKind::OpenAiCompatible => serde_json::json!({
+                "type": "image_url",
+                "image_url": { "url": format!("data:{};base64,{}", mime_type, data) }
+            }),
+        });
+    }
+    for file in files {
+        match (kind, file.content) {
+            (ProviderKind::Anthropic, Some(content)) => parts.push(serde_json::json!({
+                "type": "document",
+                "source": {
+                    "type": "base64",
+                    "media_type": if file.mime_type.is_empty() { "application/octet-stream" } else { file.mime_type.as_str() },
+                    "data": content
+                }
+            })),
+            (_, content) => {
+                // Other providers only get text files inline; anything else is referenced by name
+                let is_text = file.mime_type.starts_with("text/") || ["application/json", "application/xml"].contains(&file.mime_type.as_str());
+                let decoded = content
+                    .filter(|_| is_text)
+                    .and_then(|content| BASE64.decode(content).ok())
+                    .and_then(|bytes| String::from_utf8(bytes).ok());
+                match decoded {
+                    Some(contents) => text.push_str(&format!(
+                        "\n\n--- File: {} ({}) ---\n{}\n--- End of file ---\n",
+                        file.name, file.mime_type, contents
+                    )),
+                    None => text.push_str(&format!("\n\n[File attachment: {} ({})]\n", file.name, file.mime_type)),
+                }
+            }
+        }
+    }
+
+    if !text.trim().is_empty() {
+        parts.insert(0, serde_json::json!({ "type": "text", "text": text }));
+    }
+    if parts.len() == 1 && parts[0].get("type").and_then(|kind| kind.as_str()) == Some("text") {
+        return serde_json::Value::String(text);
+    }
+    serde_json::Value::Array(parts)
+}
+
+async fn load_message_contents(pool: &Pool<Sqlite>, conversation_id: i64, kind: ProviderKind) -> Result<Vec<(String, serde_json::Value)>, String> {
+    let rows = sqlx::query("SELECT role, text, images, files FROM messages WHERE conversation_id = ? ORDER BY created_at ASC, id ASC")
+        .bind(conversation_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to load messages for conversation {}: {}", conversation_id, e))?;
+
+    rows.iter()
+        .map(|row| {
+            let role: String = row.try_get("role")
+                .map_err(|e| format!("Failed to read message role: {}", e))?;
+            let text: Option<String> = row.try_get("text")
+                .map_err(|e| format!("Failed to read message text: {}", e))?;
+            let images = parse_attachments(row.try_get("images").unwrap_or(None), "image");
+            let files = parse_attachments(row.try_get("files").unwrap_or(None), "file");
+            Ok((role, message_content(text.unwrap_or_default(), images, files, kind)))
+        })
+        .collect()
+}
+
+// Check the target model can actually be used and work out which provider API it speaks
+async fn resolve_switch_target(app: &AppHandle, model_ref: &ModelRef) -> Result<(String, String, ProviderKind, Option<u64>), String> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    match model_ref {
+        ModelRef::Local { name, .. } => {
+            let installed = list_installed_tags(&client).await?;
+            let found = installed.iter().any(|tag| {
+                tag.name == *name || tag.name.strip_suffix(":latest") == Some(name.as_str())
+            });
+            if !found {
+                return Err(format!("{} is not installed in Ollama", name));
+            }
+
+            // Ollama truncates to num_ctx, not the trained length, so that's the window the history has to fit
+            let context_window = effective_num_ctx(&client, name).await;
+            Ok((LOCAL_PROVIDER_ID.to_string(), name.clone(), ProviderKind::OpenAiCompatible, context_window))
+        }
+        ModelRef::Cloud { provider, id } => {
+            let providers = get_setting::<std::collections::BTreeMap<String, serde_json::Value>>(app, "providers")?
+                .unwrap_or_default();
+            let settings = providers
+                .get(provider)
+                .ok_or_else(|| format!("Provider {} is not configured", provider))?;
+
+            let is_local = settings.get("isLocal").and_then(|v| v.as_bool()).unwrap_or(false);
+            if !is_local && get_api_key(app, provider)?.is_none() {
+                return Err(format!("No API key is stored for {}", provider));
+            }
+
+            let name = settings.get("name").and_then(|v| v.as_str()).unwrap_or_default();
+            let endpoint = settings.get("endpoint").and_then(|v| v.as_str()).unwrap_or_default();
+            let kind = ProviderKind::from_provider(&format!("{} {} {}", provider, name, endpoint));
+
+            // Cloud providers don't report context windows, so their history isn't trimmed here
+            Ok((provider.clone(), id.clone(), kind, None))
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn switch_conversation_model(
+    app: AppHandle,
+    conversation_id: i64,
+    new_model_ref: ModelRef,
+    preset: Option<String>,
+) -> Result<ModelSwitch, String> {
+    let preset = preset.map(|name| find_param_preset(&app, &name)).transpose()?;
+    let pool = get_database_pool(&app).await?;
+    ensure_model_switches_table(&pool).await?;
+
+    let row = sqlx::query("SELECT provider, model, system_prompt FROM conversations WHERE id = ?")
+        .bind(conversation_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| format!("Failed to load conversation {}: {}", conversation_id, e))?
+        .ok_or_else(|| format!("Conversation {} not found", conversation_id))?;
+    let previous_provider: String = row.try_get("provider")
+        .map_err(|e| format!("Failed to read conversation provider: {}", e))?;
+    let previous_model: String = row.try_get("model")
+        .map_err(|e| format!("Failed to read conversation model: {}", e))?;
+    let system_prompt: Option<String> = row.try_get("system_prompt").unwrap_or(None);
+
+    let (provider, model, kind, context_window) = resolve_switch_target(&app, &new_model_ref).await?;
+    // A preset's num_ctx is sent with every request, so it replaces the model's own window
+    let context_window = match (&new_model_ref, preset.and_then(|preset| preset.num_ctx)) {
+        (ModelRef::Local { .. }, Some(num_ctx)) => Some(num_ctx),
+        _ => context_window,
+    };
+
+    // The whole history goes to the new model, including replies other models wrote
+    let mut messages: Vec<ChatMessage> = system_prompt
+        .filter(|prompt| !prompt.trim().is_empty())
+        .map(|prompt| ChatMessage::new(MessageRole::System, serde_json::Value::String(prompt)))
+        .into_iter()
+        .collect();
+    for (role, content) in load_message_contents(&pool, conversation_id, kind).await? {
+        match serde_json::from_value::<MessageRole>(serde_json::Value::String(role.clone())) {
+            Ok(role) => messages.push(ChatMessage::new(role, content)),
+            Err(_) => eprintln!("Warning: Skipping message with unknown role '{}'", role),
+        }
+    }
+
+    // Drop the oldest turns until the history fits, always keeping the system prompt and latest message
+    let mut trimmed_messages = 0;
+    if let Some(context_window) = context_window {
+        let budget = context_window.saturating_sub(RESPONSE_TOKEN_RESERVE);
+        let mut total: u64 = messages.iter().map(estimate_tokens).sum();
+        while total > budget {
+            let non_system = messages.iter().filter(|message| message.role != MessageRole::System).count();
+            if non_system <= 1 {
+                break;
+            }
+            let Some(oldest) = messages.iter().position(|message| message.role != MessageRole::System) else {
+                break;
+            };
+            total -= estimate_tokens(&messages.remove(oldest));
+            trimmed_messages += 1;
+        }
+    }
+
+    let mut context = normalize_conversation(messages, kind)?;
+    if trimmed_messages > 0 {
+        context.transformations.push(format!(
+            "Dropped {} oldest message(s) to fit the {}-token context window",
+            trimmed_messages,
+            context_window.unwrap_or_default()
+        ));
+    }
+    let estimated_tokens = context.messages.iter().map(estimate_tokens).sum::<u64>()
+        + context.system.as_deref().map(|system| (system.chars().count() as u64).div_ceil(4)).unwrap_or(0);
+
+    let after_message_id: Option<i64> = sqlx::query_scalar(
+        "SELECT id FROM messages WHERE conversation_id = ? ORDER BY created_at DESC, id DESC LIMIT 1",
+    )
+    .bind(conversation_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| format!("Failed to load latest message: {}", e))?;
+
+    sqlx::query(&format!(
+        "INSERT INTO conversation_model_switches \
+         (conversation_id, from_provider, from_model, to_provider, to_model, after_message_id, created_at) \
+         VALUES (?, ?, ?, ?, ?, ?, {})",
+        SQL_NOW
+    ))
+    .bind(conversation_id)
+    .bind(&previous_provider)
+    .bind(&previous_model)
+    .bind(&provider)
+    .bind(&model)
+    .bind(after_message_id)
+    .execute(&pool)
+    .await
+    .map_err(|e| format!("Failed to record model switch: {}", e))?;
+
+    sqlx::query(&format!("UPDATE conversations SET provider = ?, model = ?, updated_at = {} WHERE id = ?", SQL_NOW))
+        .bind(&provider)
+        .bind(&model)
+        .bind(conversation_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to update conversation {}: {}", conversation_id, e))?;
+
+    Ok(ModelSwitch {
+        conversation_id,
+        previous_provider,
+        previous_model,
+        provider,
+        model,
+        after_message_id,
+        context,
+        context_window,
+        estimated_tokens,
+        trimmed_messages,
+    })
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuTopology {
+    pub logical_cores: usize,
+    pub physical_cores: usize,
+    // Only reported on hybrid CPUs (Apple Silicon, Intel 12th gen and later)
+    pub performance_cores: Option<usize>,
+    pub efficiency_cores: Option<usize>,
+    pub recommended_thread_count: usize,
+}
+
+fn logical_cores() -> usize {
+    std::thread::available_parallelism()
+        .map(|p| p.get())
+        .unwrap_or(1)
+}
+
+#[cfg(target_os = "macos")]
+fn sysctl_count(name: &str) -> Option<usize> {
+    let output = std::process::Command::new("sysctl").args(["-n", name]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+// Returns (physical cores, performance cores, efficiency cores)
+#[cfg(target_os = "macos")]
+fn detect_physical_cores() -> Option<(usize, Option<usize>, Option<usize>)> {
+    let physical = sysctl_count("hw.physicalcpu")?;
+
+    // Apple Silicon reports perflevel0 as the performance cluster and perflevel1 as efficiency
+    let hybrid = sysctl_count("hw.nperflevels").is_some_and(|levels| levels > 1);
+    if hybrid {
+        Some((
+            physical,
+            sysctl_count("hw.perflevel0.physicalcpu"),
+            sysctl_count("hw.perflevel1.physicalcpu"),
+        ))
+    } else {
+        Some((physical, None, None))
+    }
+}
+
+// Expand a sysfs cpu list like "0-7,16-23" into cpu numbers
+#[cfg(target_os = "linux")]
+fn parse_cpu_list(list: &str) -> Vec<usize> {
+    list.trim()
+        .split(',')
+        .filter(|part| !part.is_empty())
+        .flat_map(|part| match part.split_once('-') {
+            Some((start, end)) => match (start.parse::<usize>(), end.parse::<usize>()) {
+                (Ok(start), Ok(end)) => (start..=end).collect(),
+                _ => Vec::new(),
+            },
+            None => part.parse().map(|cpu| vec![cpu]).unwrap_or_default(),
+        })
+        .collect()
+}
+
+// Physical cores are the distinct (package, core) pairs among the given logical cpus
+#[cfg(target_os = "linux")]
+fn count_physical_cores(cpus: &[usize]) -> usize {
+    let read_id = |cpu: usize, file: &str| {
+        std::fs::read_to_string(format!("/sys/devices/system/cpu/cpu{}/topology/{}", cpu, file))
+            .ok()
+            .and_then(|id| id.trim().parse::<i64>().ok())
+    };
+
+    cpus.iter()
+        .filter_map(|cpu| Some((read_id(*cpu, "physical_package_id")?, read_id(*cpu, "core_id")?)))
+        .collect::<std::collections::BTreeSet<_>>()
+        .len()
+}
+
+#[cfg(target_os = "linux")]
+fn detect_physical_cores() -> Option<(usize, Option<usize>, Option<usize>)> {
+    let online = std::fs::read_to_string("/sys/devices/system/cpu/online").ok()?;
+    let physical = count_physical_cores(&parse_cpu_list(&online));
+    if physical == 0 {
+        return None;
+    }
+
+    // Intel hybrid CPUs expose separate PMUs for the P-cores and E-cores
+    let core_cpus = std::fs::read_to_string("/sys/devices/cpu_core/cpus").ok();
+    let atom_cpus = std::fs::read_to_string("/sys/devices/cpu_atom/cpus").ok();
+    match (core_cpus, atom_cpus) {
+        (Some(core_cpus), Some(atom_cpus)) => Some((
+            physical,
+            Some(count_physical_cores(&parse_cpu_list(&core_cpus))),
+            Some(count_physical_cores(&parse_cpu_list(&atom_cpus))),
+        )),
+        _ => Some((physical, None, None)),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn detect_physical_cores() -> Option<(usize, Option<usize>, Option<usize>)> {
+    let output = std::process::Command::new("wmic")
+        .args(["cpu", "get", "NumberOfCores", "/value"])
+        .output()
+        .ok()?;
+
+    // One line per socket on multi-CPU machines
+    let physical: usize = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("NumberOfCores="))
+        .filter_map(|cores| cores.trim().parse::<usize>().ok())
+        .sum();
+    (physical > 0).then_some((physical, None, None))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn detect_physical_cores() -> Option<(usize, Option<usize>, Option<usize>)> {
+    None
+}
+
+// Inference threads should sit on performance cores only, leaving one free for the OS and UI
+fn recommend_thread_count(physical_cores: usize, performance_cores: Option<usize>) -> usize {
+    let cores = performance_cores.filter(|cores| *cores > 0).unwrap_or(physical_cores);
+    if cores > 2 {
+        cores - 1
+    } else {
+        cores.max(1)
+    }
+}
+
+pub fn detect_cpu_topology() -> CpuTopology {
+    let logical_cores = logical_cores();
+
+    // Without topology information, assume SMT and halve the logical count rather than oversubscribe
+    let (physical_cores, performance_cores, efficiency_cores) = detect_physical_cores()
+        .unwrap_or(((logical_cores / 2).max(1), None, None));
+
+    CpuTopology {
+        logical_cores,
+        physical_cores,
+        performance_cores,
+        efficiency_cores,
+        recommended_thread_count: recommend_thread_count(physical_cores, performance_cores),
+    }
+}
+
+#[tauri::command]
+pub async fn get_cpu_topology() -> Result<CpuTopology, String> {
+    tokio::task::spawn_blocking(detect_cpu_topology)
+        .await
+        .map_err(|e| format!("CPU detection failed: {}", e))
+}
@@ -0,0 +1,48 @@
+use std::path::PathBuf;
+use sqlx::{Pool, Row, Sqlite};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+// Same connection string the frontend stores use (and the sql plugin preloads)
+pub const DATABASE_URL: &str = "sqlite:open_chat.db";
+
+// Share the sql plugin's connection pool instead of opening a second connection
+pub async fn get_database_pool(app: &AppHandle) -> Result<Pool<Sqlite>, String> {
+    let instances = app.try_state::<DbInstances>()
+        .ok_or_else(|| "SQL plugin is not initialized".to_string())?;
+    let instances = instances.0.read().await;
+
+    match instances.get(DATABASE_URL) {
+        Some(DbPool::Sqlite(pool)) => Ok(pool.clone()),
+        None => Err("Database has not been loaded yet".to_string()),
+    }
+}
+
+// The sql plugin resolves sqlite URLs relative to the app config directory
+pub fn get_database_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let config_dir = app.path().app_config_dir()
+        .map_err(|e| format!("Failed to resolve app config directory: {}", e))?;
+    let file_name = DATABASE_URL.split_once(':').map(|(_, path)| path).unwrap_or(DATABASE_URL);
+    Ok(config_dir.join(file_name))
+}
+
+// Add a column the frontend schema doesn't know about, if it isn't there yet
+pub async fn ensure_column(pool: &Pool<Sqlite>, table: &str, column: &str, definition: &str) -> Result<(), String> {
+    let columns = sqlx::query(&format!("PRAGMA table_info({})", table))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to read {} schema: {}", table, e))?;
+
+    let has_column = columns
+        .iter()
+        .any(|row| row.try_get::<String, _>("name").map(|name| name == column).unwrap_or(false));
+
+    if !has_column {
+        sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Failed to add {}.{} column: {}", table, column, e))?;
+    }
+
+    Ok(())
+}
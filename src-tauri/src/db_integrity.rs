@@ -0,0 +1,271 @@
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection, SqliteJournalMode};
+use sqlx::{Connection, Row};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+use crate::database::{get_database_file_path, get_database_pool};
+
+// integrity_check stops after this many problems; more wouldn't change the advice
+const MAX_REPORTED_PROBLEMS: u32 = 100;
+// Rows copied per statement when walking a damaged table by rowid
+const RECOVERY_CHUNK_ROWS: i64 = 256;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseIntegrity {
+    pub path: String,
+    pub ok: bool,
+    pub problems: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableRecovery {
+    pub table: String,
+    pub recovered_rows: u64,
+    // None when the damaged table couldn't even be counted
+    pub original_rows: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseRepair {
+    pub backup_path: String,
+    pub tables: Vec<TableRecovery>,
+    pub recovered_rows: u64,
+    pub warnings: Vec<String>,
+    // The sql plugin's connections are closed during repair, so the app must relaunch to reopen them
+    pub restart_required: bool,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// SQLite keeps its WAL and shared-memory files next to the database with these suffixes
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path: OsString = path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+async fn open_database(path: &Path, read_only: bool) -> Result<SqliteConnection, String> {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .read_only(read_only)
+        .create_if_missing(!read_only)
+        .journal_mode(SqliteJournalMode::Delete);
+    SqliteConnection::connect_with(&options)
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+}
+
+pub async fn check_database_file(path: &Path, quick: bool) -> DatabaseIntegrity {
+    let mut integrity = DatabaseIntegrity {
+        path: path.to_string_lossy().to_string(),
+        ok: true,
+        problems: Vec::new(),
+    };
+    // A missing database is created fresh by the frontend, so there's nothing to check yet
+    if !path.exists() {
+        return integrity;
+    }
+
+    let pragma = if quick { "quick_check" } else { "integrity_check" };
+    let result = async {
+        let mut connection = open_database(path, true).await?;
+        let rows = sqlx::query(&format!("PRAGMA {}({})", pragma, MAX_REPORTED_PROBLEMS))
+            .fetch_all(&mut connection)
+            .await
+            .map_err(|e| format!("Integrity check failed: {}", e))?;
+        let _ = connection.close().await;
+        Ok::<_, String>(rows.iter().filter_map(|row| row.try_get::<String, _>(0).ok()).collect::<Vec<_>>())
+    }
+    .await;
+
+    match result {
+        Ok(messages) if messages.len() == 1 && messages[0] == "ok" => {}
+        Ok(messages) => {
+            integrity.ok = false;
+            integrity.problems = messages;
+        }
+        // "file is not a database" and similar mean the header itself is damaged
+        Err(e) => {
+            integrity.ok = false;
+            integrity.problems.push(e);
+        }
+    }
+    integrity
+}
+
+async fn count_rows(connection: &mut SqliteConnection, table: &str) -> Option<u64> {
+    sqlx::query_scalar::<_, i64>(&format!("SELECT count(*) FROM old.{}", table))
+        .fetch_one(connection)
+        .await
+        .ok()
+        .map(|count| count as u64)
+}
+
+// Copy what can be read from a table: everything at once if possible, otherwise by rowid ranges,
+// falling back to single rows inside ranges that hit a damaged page
+async fn copy_table(connection: &mut SqliteConnection, name: &str, warnings: &mut Vec<String>) -> TableRecovery {
+    let table = quote_identifier(name);
+    let original_rows = count_rows(connection, &table).await;
+    let mut recovery = TableRecovery {
+        table: name.to_string(),
+        recovered_rows: 0,
+        original_rows,
+    };
+
+    let bulk = sqlx::query(&format!("INSERT INTO main.{0} SELECT * FROM old.{0}", table))
+        .execute(&mut *connection)
+        .await;
+    if let Ok(result) = bulk {
+        recovery.recovered_rows = result.rows_affected();
+        return recovery;
+    }
+
+    let bounds = sqlx::query(&format!("SELECT min(rowid), max(rowid) FROM old.{}", table))
+        .fetch_one(&mut *connection)
+        .await
+        .ok()
+        .and_then(|row| Some((row.try_get::<Option<i64>, _>(0).ok()??, row.try_get::<Option<i64>, _>(1).ok()??)));
+    let Some((first, last)) = bounds else {
+        warnings.push(format!("Table {} is too damaged to read; none of its rows could be recovered", name));
+        return recovery;
+    };
+
+    let copy_range = format!("INSERT OR IGNORE INTO main.{0} SELECT * FROM old.{0} WHERE rowid BETWEEN ? AND ?", table);
+    let mut start = first;
+    while start <= last {
+        let end = start.saturating_add(RECOVERY_CHUNK_ROWS - 1).min(last);
+        match sqlx::query(&copy_range).bind(start).bind(end).execute(&mut *connection).await {
+            Ok(result) => recovery.recovered_rows += result.rows_affected(),
+            Err(_) => {
+                for rowid in start..=end {
+                    if let Ok(result) = sqlx::query(&copy_range).bind(rowid).bind(rowid).execute(&mut *connection).await {
+                        recovery.recovered_rows += result.rows_affected();
+                    }
+                }
+            }
+        }
+        if end == i64::MAX {
+            break;
+        }
+        start = end + 1;
+    }
+
+    recovery
+}
+
+async fn recover_into(source: &Path, destination: &Path) -> Result<(Vec<TableRecovery>, Vec<String>), String> {
+    let mut connection = open_database(destination, false).await?;
+    sqlx::query("ATTACH DATABASE ? AS old")
+        .bind(source.to_string_lossy().to_string())
+        .execute(&mut connection)
+        .await
+        .map_err(|e| format!("The database is too damaged to open: {}", e))?;
+
+    let schema = sqlx::query("SELECT type, name, sql FROM old.sqlite_master WHERE sql IS NOT NULL ORDER BY rowid")
+        .fetch_all(&mut connection)
+        .await
+        .map_err(|e| format!("The database schema is unreadable: {}", e))?;
+    let schema: Vec<(String, String, String)> = schema
+        .iter()
+        .filter_map(|row| Some((row.try_get(0).ok()?, row.try_get(1).ok()?, row.try_get(2).ok()?)))
+        .collect();
+
+    let mut warnings = Vec::new();
+    let mut tables = Vec::new();
+    for (kind, name, sql) in schema.iter().filter(|(kind, name, _)| kind == "table" && !name.starts_with("sqlite_")) {
+        if let Err(e) = sqlx::query(sql).execute(&mut connection).await {
+            warnings.push(format!("Failed to recreate {} {}: {}", kind, name, e));
+            continue;
+        }
+        tables.push(copy_table(&mut connection, name, &mut warnings).await);
+    }
+
+    // Keep AUTOINCREMENT counters so recovered ids aren't reused
+    let _ = sqlx::query("INSERT INTO main.sqlite_sequence SELECT * FROM old.sqlite_sequence")
+        .execute(&mut connection)
+        .await;
+
+    // Indexes are built after the data so a damaged row can't block the whole table
+    for (kind, name, sql) in schema.iter().filter(|(kind, name, _)| kind != "table" && !name.starts_with("sqlite_")) {
+        if let Err(e) = sqlx::query(sql).execute(&mut connection).await {
+            warnings.push(format!("Failed to recreate {} {}: {}", kind, name, e));
+        }
+    }
+
+    let _ = sqlx::query("DETACH DATABASE old").execute(&mut connection).await;
+    let _ = connection.close().await;
+    Ok((tables, warnings))
+}
+
+#[tauri::command]
+pub async fn check_database_integrity(app: AppHandle, quick: Option<bool>) -> Result<DatabaseIntegrity, String> {
+    let path = get_database_file_path(&app)?;
+    Ok(check_database_file(&path, quick.unwrap_or(false)).await)
+}
+
+#[tauri::command]
+pub async fn repair_database(app: AppHandle) -> Result<DatabaseRepair, String> {
+    let path = get_database_file_path(&app)?;
+    if !path.exists() {
+        return Err("There is no database file to repair".to_string());
+    }
+
+    // Release the sql plugin's connections so the file can be swapped out
+    if let Ok(pool) = get_database_pool(&app).await {
+        pool.close().await;
+    }
+
+    // Keep the damaged file and its WAL intact so nothing is lost if recovery falls short
+    let backup_path = with_suffix(&path, &format!(".corrupt-{}", now_ms()));
+    for suffix in ["", "-wal", "-shm"] {
+        let source = with_suffix(&path, suffix);
+        if source.exists() {
+            std::fs::copy(&source, with_suffix(&backup_path, suffix))
+                .map_err(|e| format!("Failed to back up {}: {}", source.display(), e))?;
+        }
+    }
+
+    let recovered_path = with_suffix(&path, ".recovered");
+    if recovered_path.exists() {
+        std::fs::remove_file(&recovered_path)
+            .map_err(|e| format!("Failed to remove leftover {}: {}", recovered_path.display(), e))?;
+    }
+    let (tables, warnings) = match recover_into(&backup_path, &recovered_path).await {
+        Ok(recovery) => recovery,
+        Err(e) => {
+            let _ = std::fs::remove_file(&recovered_path);
+            return Err(e);
+        }
+    };
+
+    for suffix in ["", "-wal", "-shm"] {
+        let stale = with_suffix(&path, suffix);
+        if let Err(e) = std::fs::remove_file(&stale) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                return Err(format!("Failed to remove {}: {}", stale.display(), e));
+            }
+        }
+    }
+    std::fs::rename(&recovered_path, &path)
+        .map_err(|e| format!("Failed to move the recovered database into place: {}", e))?;
+
+    Ok(DatabaseRepair {
+        backup_path: backup_path.to_string_lossy().to_string(),
+        recovered_rows: tables.iter().map(|table| table.recovered_rows).sum(),
+        tables,
+        warnings,
+        restart_required: true,
+    })
+}
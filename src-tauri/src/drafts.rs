@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+// Separate from settings.json so frequent autosaves don't rewrite the user's settings file
+const DRAFTS_STORE: &str = "drafts.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Draft {
+    pub window_label: String,
+    pub text: String,
+    pub updated_at_ms: u64,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn open_drafts_store(app: &AppHandle) -> Result<std::sync::Arc<tauri_plugin_store::Store<tauri::Wry>>, String> {
+    app.store(DRAFTS_STORE)
+        .map_err(|e| format!("Failed to open drafts store: {}", e))
+}
+
+// Drafts are keyed by window label, so a recreated mini window finds its draft again
+#[tauri::command]
+pub async fn save_draft(app: AppHandle, window_label: String, text: String) -> Result<(), String> {
+    let store = open_drafts_store(&app)?;
+
+    if text.trim().is_empty() {
+        store.delete(&window_label);
+    } else {
+        let draft = Draft {
+            window_label: window_label.clone(),
+            text,
+            updated_at_ms: now_ms(),
+        };
+        let value = serde_json::to_value(&draft)
+            .map_err(|e| format!("Failed to serialize draft: {}", e))?;
+        store.set(window_label, value);
+    }
+    store.save().map_err(|e| format!("Failed to save draft: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_draft(app: AppHandle, window_label: String) -> Result<Option<Draft>, String> {
+    let store = open_drafts_store(&app)?;
+
+    match store.get(&window_label) {
+        Some(value) => serde_json::from_value(value)
+            .map(Some)
+            .map_err(|e| format!("Invalid draft for window '{}': {}", window_label, e)),
+        None => Ok(None),
+    }
+}
+
+#[tauri::command]
+pub async fn clear_draft(app: AppHandle, window_label: String) -> Result<(), String> {
+    let store = open_drafts_store(&app)?;
+    store.delete(&window_label);
+    store.save().map_err(|e| format!("Failed to save drafts: {}", e))
+}
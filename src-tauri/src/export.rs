@@ -0,0 +1,327 @@
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+
+use crate::database::get_database_pool;
+use crate::ollama::{discover_local_models, LocalModel, ModelSource};
+use crate::ollama_registry::parse_quantization;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeBlock {
+    pub language: Option<String>,
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExportedModel {
+    name: String,
+    source: ModelSource,
+    size_bytes: u64,
+    format: Option<String>,
+    path: String,
+    quantization: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExportedModelList {
+    models: Vec<ExportedModel>,
+    total_count: usize,
+    total_size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExportedMessage {
+    id: i64,
+    role: String,
+    text: String,
+    model: Option<String>,
+    created_at: Option<String>,
+}
+
+// Parse fenced (``` or ~~~) code blocks out of markdown message content
+pub fn parse_code_blocks(content: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(char, usize, Option<String>, Vec<&str>)> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+
+        match current.take() {
+            None => {
+                if let Some((fence_char, fence_len)) = parse_fence(trimmed) {
+                    let info = trimmed[fence_len..].trim();
+                    // The language is the first word of the info string, e.g. "rust" in "```rust title=x"
+                    let language = info
+                        .split_whitespace()
+                        .next()
+                        .map(|lang| lang.trim_matches(|c| c == '{' || c == '}' || c == '.').to_lowercase())
+                        .filter(|lang| !lang.is_empty());
+                    current = Some((fence_char, fence_len, language, Vec::new()));
+                }
+            }
+            Some((fence_char, fence_len, language, mut lines)) => {
+                let is_closing = parse_fence(trimmed)
+                    .map(|(c, len)| c == fence_char && len >= fence_len && trimmed[len..].trim().is_empty())
+                    .unwrap_or(false);
+
+                if is_closing {
+                    blocks.push(CodeBlock {
+                        language,
+                        code: lines.join("\n"),
+                    });
+                } else {
+                    lines.push(line);
+                    current = Some((fence_char, fence_len, language, lines));
+                }
+            }
+        }
+    }
+
+    // An unclosed fence runs to the end of the message (common when a response was cut off)
+    if let Some((_, _, language, lines)) = current {
+        blocks.push(CodeBlock {
+            language,
+            code: lines.join("\n"),
+        });
+    }
+
+    blocks
+}
+
+fn parse_fence(line: &str) -> Option<(char, usize)> {
+    let fence_char = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let fence_len = line.chars().take_while(|c| *c == fence_char).count();
+
+    if fence_len >= 3 {
+        Some((fence_char, fence_len))
+    } else {
+        None
+    }
+}
+
+pub fn extension_for_language(language: &str) -> &'static str {
+    match language.to_lowercase().as_str() {
+        "rust" | "rs" => "rs",
+        "python" | "py" => "py",
+        "javascript" | "js" | "node" => "js",
+        "typescript" | "ts" => "ts",
+        "tsx" => "tsx",
+        "jsx" => "jsx",
+        "json" => "json",
+        "html" => "html",
+        "css" => "css",
+        "scss" => "scss",
+        "bash" | "sh" | "shell" | "zsh" => "sh",
+        "powershell" | "ps1" => "ps1",
+        "go" | "golang" => "go",
+        "java" => "java",
+        "kotlin" | "kt" => "kt",
+        "swift" => "swift",
+        "c" => "c",
+        "cpp" | "c++" | "cxx" => "cpp",
+        "csharp" | "cs" | "c#" => "cs",
+        "ruby" | "rb" => "rb",
+        "php" => "php",
+        "sql" => "sql",
+        "yaml" | "yml" => "yml",
+        "toml" => "toml",
+        "xml" => "xml",
+        "markdown" | "md" => "md",
+        "lua" => "lua",
+        "r" => "r",
+        "dart" => "dart",
+        "dockerfile" | "docker" => "dockerfile",
+        _ => "txt",
+    }
+}
+
+async fn load_message(app: &AppHandle, message_id: i64) -> Result<ExportedMessage, String> {
+    let pool = get_database_pool(app).await?;
+
+    let row = sqlx::query("SELECT id, role, text, model, created_at FROM messages WHERE id = ?")
+        .bind(message_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| format!("Failed to load message {}: {}", message_id, e))?
+        .ok_or_else(|| format!("Message {} not found", message_id))?;
+
+    Ok(ExportedMessage {
+        id: row.try_get("id").map_err(|e| format!("Failed to read message id: {}", e))?,
+        role: row.try_get("role").map_err(|e| format!("Failed to read message role: {}", e))?,
+        text: row.try_get::<Option<String>, _>("text")
+            .map_err(|e| format!("Failed to read message text: {}", e))?
+            .unwrap_or_default(),
+        model: row.try_get("model").unwrap_or(None),
+        created_at: row.try_get("created_at").unwrap_or(None),
+    })
+}
+
+// Ask the user where to save, returning None if they cancel the dialog
+async fn choose_save_path(
+    app: &AppHandle,
+    file_name: &str,
+    extension: &str,
+) -> Result<Option<std::path::PathBuf>, String> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+
+    app.dialog()
+        .file()
+        .set_file_name(file_name)
+        .add_filter(extension.to_uppercase(), &[extension])
+        .save_file(move |path| {
+            let _ = tx.send(path);
+        });
+
+    let Some(path) = rx.await.map_err(|_| "Save dialog was closed unexpectedly".to_string())? else {
+        return Ok(None);
+    };
+
+    path.into_path()
+        .map(Some)
+        .map_err(|e| format!("Invalid save location: {}", e))
+}
+
+impl From<&LocalModel> for ExportedModel {
+    fn from(model: &LocalModel) -> Self {
+        // Ollama names end in the tag ("llama3:8b-q4_K_M"); files in the quantization ("model.Q4_K_M.gguf")
+        let stem = ["gguf", "safetensors", "bin"]
+            .iter()
+            .find_map(|extension| model.name.strip_suffix(&format!(".{}", extension)))
+            .unwrap_or(&model.name);
+
+        ExportedModel {
+            name: model.name.clone(),
+            source: model.source.clone(),
+            size_bytes: model.size_bytes,
+            format: model.format.clone(),
+            path: model.file_path.display().to_string(),
+            quantization: parse_quantization(stem),
+        }
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn model_list_csv(list: &ExportedModelList) -> String {
+    let mut csv = String::from("name,source,size_bytes,format,path,quantization\n");
+    for model in &list.models {
+        let source = match model.source {
+            ModelSource::Ollama => "ollama",
+            ModelSource::LmStudio => "lm_studio",
+            ModelSource::Other => "other",
+        };
+        let row = [
+            csv_field(&model.name),
+            source.to_string(),
+            model.size_bytes.to_string(),
+            csv_field(model.format.as_deref().unwrap_or_default()),
+            csv_field(&model.path),
+            csv_field(model.quantization.as_deref().unwrap_or_default()),
+        ];
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    // Summary row so totals survive a round trip through a spreadsheet
+    csv.push_str(&format!("Total ({} models),,{},,,\n", list.total_count, list.total_size_bytes));
+    csv
+}
+
+fn write_export(path: &std::path::Path, contents: &str) -> Result<String, String> {
+    std::fs::write(path, contents)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub async fn export_message(
+    app: AppHandle,
+    message_id: i64,
+    format: String,
+) -> Result<Option<String>, String> {
+    let message = load_message(&app, message_id).await?;
+
+    let (contents, extension) = match format.as_str() {
+        "markdown" | "md" => (message.text.clone(), "md"),
+        "text" | "txt" => (message.text.clone(), "txt"),
+        "json" => (
+            serde_json::to_string_pretty(&message)
+                .map_err(|e| format!("Failed to serialize message: {}", e))?,
+            "json",
+        ),
+        other => return Err(format!("Unsupported export format '{}'", other)),
+    };
+
+    let file_name = format!("message-{}.{}", message.id, extension);
+    match choose_save_path(&app, &file_name, extension).await? {
+        Some(path) => write_export(&path, &contents).map(Some),
+        None => Ok(None),
+    }
+}
+
+#[tauri::command]
+pub async fn export_code_block(
+    app: AppHandle,
+    message_id: i64,
+    block_index: usize,
+    suggested_filename: Option<String>,
+) -> Result<Option<String>, String> {
+    let message = load_message(&app, message_id).await?;
+    let blocks = parse_code_blocks(&message.text);
+
+    let block = blocks.get(block_index).ok_or_else(|| {
+        format!(
+            "Code block {} not found (message {} has {} code blocks)",
+            block_index, message_id, blocks.len()
+        )
+    })?;
+
+    let extension = block
+        .language
+        .as_deref()
+        .map(extension_for_language)
+        .unwrap_or("txt");
+
+    // Keep the caller's filename but make sure it carries an extension
+    let file_name = match suggested_filename.filter(|name| !name.trim().is_empty()) {
+        Some(name) if std::path::Path::new(&name).extension().is_some() => name,
+        Some(name) => format!("{}.{}", name, extension),
+        None => format!("snippet-{}-{}.{}", message_id, block_index + 1, extension),
+    };
+
+    let mut contents = block.code.clone();
+    if !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+
+    match choose_save_path(&app, &file_name, extension).await? {
+        Some(path) => write_export(&path, &contents).map(Some),
+        None => Ok(None),
+    }
+}
+
+#[tauri::command]
+pub async fn export_model_list(format: String, dest: String) -> Result<usize, String> {
+    let discovery = discover_local_models().await?;
+    let list = ExportedModelList {
+        models: discovery.models.iter().map(ExportedModel::from).collect(),
+        total_count: discovery.total_count,
+        total_size_bytes: discovery.total_size_bytes,
+    };
+
+    let contents = match format.as_str() {
+        "json" => serde_json::to_string_pretty(&list)
+            .map_err(|e| format!("Failed to serialize model list: {}", e))?,
+        "csv" => model_list_csv(&list),
+        other => return Err(format!("Unsupported export format '{}'", other)),
+    };
+
+    write_export(std::path::Path::new(&dest), &contents)?;
+    Ok(contents.len())
+}
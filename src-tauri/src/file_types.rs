@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+// Enough to see past headers and judge whether the content reads as text
+const SAMPLE_BYTES: usize = 8 * 1024;
+// Share of printable characters a sample needs to count as text
+const MIN_PRINTABLE_RATIO: f64 = 0.95;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FileKind {
+    PlainText,
+    Pdf,
+    Markdown,
+    Code { language: String },
+    Image,
+    Binary,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileClassification {
+    pub path: String,
+    pub kind: FileKind,
+    pub mime_type: String,
+    // Whether text can be extracted from the file for a knowledge base
+    pub ingestible: bool,
+    pub size_bytes: u64,
+}
+
+// (magic bytes at offset 0, kind, MIME type) for formats recognizable from their first bytes
+const MAGIC_SIGNATURES: &[(&[u8], FileKind, &str)] = &[
+    (b"%PDF-", FileKind::Pdf, "application/pdf"),
+    (b"\x89PNG\r\n\x1a\n", FileKind::Image, "image/png"),
+    (b"\xff\xd8\xff", FileKind::Image, "image/jpeg"),
+    (b"GIF87a", FileKind::Image, "image/gif"),
+    (b"GIF89a", FileKind::Image, "image/gif"),
+    (b"II*\0", FileKind::Image, "image/tiff"),
+    (b"MM\0*", FileKind::Image, "image/tiff"),
+    (b"PK\x03\x04", FileKind::Binary, "application/zip"),
+    (b"\x1f\x8b", FileKind::Binary, "application/gzip"),
+    (b"7z\xbc\xaf\x27\x1c", FileKind::Binary, "application/x-7z-compressed"),
+    (b"\x7fELF", FileKind::Binary, "application/x-executable"),
+    (b"MZ", FileKind::Binary, "application/vnd.microsoft.portable-executable"),
+    (b"\xcf\xfa\xed\xfe", FileKind::Binary, "application/x-mach-binary"),
+    (b"\xca\xfe\xba\xbe", FileKind::Binary, "application/x-mach-binary"),
+    (b"SQLite format 3\0", FileKind::Binary, "application/vnd.sqlite3"),
+    (b"GGUF", FileKind::Binary, "application/octet-stream"),
+];
+
+// (extension, language, MIME type) for source files
+const CODE_EXTENSIONS: &[(&str, &str, &str)] = &[
+    ("rs", "rust", "text/x-rust"),
+    ("py", "python", "text/x-python"),
+    ("js", "javascript", "text/javascript"),
+    ("mjs", "javascript", "text/javascript"),
+    ("jsx", "javascript", "text/javascript"),
+    ("ts", "typescript", "text/x-typescript"),
+    ("tsx", "typescript", "text/x-typescript"),
+    ("go", "go", "text/x-go"),
+    ("java", "java", "text/x-java"),
+    ("kt", "kotlin", "text/x-kotlin"),
+    ("swift", "swift", "text/x-swift"),
+    ("c", "c", "text/x-c"),
+    ("h", "c", "text/x-c"),
+    ("cpp", "cpp", "text/x-c++"),
+    ("cc", "cpp", "text/x-c++"),
+    ("hpp", "cpp", "text/x-c++"),
+    ("cs", "csharp", "text/x-csharp"),
+    ("rb", "ruby", "text/x-ruby"),
+    ("php", "php", "text/x-php"),
+    ("sh", "shell", "text/x-shellscript"),
+    ("bash", "shell", "text/x-shellscript"),
+    ("zsh", "shell", "text/x-shellscript"),
+    ("ps1", "powershell", "text/plain"),
+    ("sql", "sql", "application/sql"),
+    ("html", "html", "text/html"),
+    ("htm", "html", "text/html"),
+    ("css", "css", "text/css"),
+    ("scss", "scss", "text/x-scss"),
+    ("json", "json", "application/json"),
+    ("yaml", "yaml", "application/yaml"),
+    ("yml", "yaml", "application/yaml"),
+    ("toml", "toml", "application/toml"),
+    ("xml", "xml", "application/xml"),
+    ("lua", "lua", "text/x-lua"),
+    ("r", "r", "text/x-r"),
+    ("dart", "dart", "text/x-dart"),
+    ("scala", "scala", "text/x-scala"),
+];
+
+fn magic_match(sample: &[u8]) -> Option<(FileKind, &'static str)> {
+    // WebP and HEIC have their identifying bytes after a size field
+    if sample.len() >= 12 && &sample[..4] == b"RIFF" && &sample[8..12] == b"WEBP" {
+        return Some((FileKind::Image, "image/webp"));
+    }
+    if sample.len() >= 12 && &sample[4..8] == b"ftyp" && matches!(&sample[8..12], b"heic" | b"heix" | b"mif1") {
+        return Some((FileKind::Image, "image/heic"));
+    }
+
+    MAGIC_SIGNATURES
+        .iter()
+        .find(|(magic, _, _)| sample.starts_with(magic))
+        .map(|(_, kind, mime_type)| (kind.clone(), *mime_type))
+}
+
+fn looks_like_text(sample: &[u8]) -> bool {
+    if sample.starts_with(&[0xff, 0xfe]) || sample.starts_with(&[0xfe, 0xff]) {
+        // UTF-16 byte order mark; its NUL bytes would otherwise read as binary
+        return true;
+    }
+    if sample.contains(&0) {
+        return false;
+    }
+
+    // The sample can cut a multi-byte character in half, so a broken tail is fine
+    let text = match std::str::from_utf8(sample) {
+        Ok(text) => text,
+        Err(e) if e.error_len().is_none() => std::str::from_utf8(&sample[..e.valid_up_to()]).unwrap_or_default(),
+        // Not UTF-8; legacy single-byte encodings still mostly land in the printable ASCII range
+        Err(_) => {
+            let printable = sample
+                .iter()
+                .filter(|byte| byte.is_ascii_graphic() || matches!(byte, b' ' | b'\n' | b'\r' | b'\t'))
+                .count();
+            return printable as f64 / sample.len() as f64 >= MIN_PRINTABLE_RATIO;
+        }
+    };
+
+    let total = text.chars().count();
+    if total == 0 {
+        return false;
+    }
+    let printable = text
+        .chars()
+        .filter(|c| !c.is_control() || matches!(c, '\n' | '\r' | '\t' | '\x0c'))
+        .count();
+    printable as f64 / total as f64 >= MIN_PRINTABLE_RATIO
+}
+
+// Scripts without an extension still announce their language on the first line
+fn shebang_language(sample: &[u8]) -> Option<&'static str> {
+    let first_line = sample.strip_prefix(b"#!")?.split(|byte| *byte == b'\n').next()?;
+    let first_line = String::from_utf8_lossy(first_line);
+    ["python", "node", "bash", "sh", "zsh", "ruby", "perl"]
+        .into_iter()
+        .find(|interpreter| first_line.split(['/', ' ']).any(|part| part.starts_with(interpreter)))
+        .map(|interpreter| match interpreter {
+            "node" => "javascript",
+            "bash" | "sh" | "zsh" => "shell",
+            other => other,
+        })
+}
+
+fn classify_text(path: &Path, sample: &[u8]) -> (FileKind, &'static str) {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    if matches!(extension.as_str(), "md" | "markdown" | "mdx") {
+        return (FileKind::Markdown, "text/markdown");
+    }
+    if let Some((_, language, mime_type)) = CODE_EXTENSIONS.iter().find(|(code_extension, _, _)| *code_extension == extension) {
+        return (FileKind::Code { language: language.to_string() }, mime_type);
+    }
+    if let Some(language) = shebang_language(sample) {
+        return (FileKind::Code { language: language.to_string() }, "text/plain");
+    }
+    if extension == "csv" {
+        return (FileKind::PlainText, "text/csv");
+    }
+    (FileKind::PlainText, "text/plain")
+}
+
+pub fn classify_path(path: &Path) -> Result<FileClassification, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let size_bytes = file
+        .metadata()
+        .map_err(|e| format!("Failed to read metadata for {}: {}", path.display(), e))?
+        .len();
+
+    let mut sample = Vec::with_capacity(SAMPLE_BYTES);
+    file.by_ref()
+        .take(SAMPLE_BYTES as u64)
+        .read_to_end(&mut sample)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    // The content decides the type; the extension only refines what kind of text it is
+    let (kind, mime_type) = if sample.is_empty() {
+        (FileKind::Unknown, "application/octet-stream")
+    } else if let Some(matched) = magic_match(&sample) {
+        matched
+    } else if looks_like_text(&sample) {
+        classify_text(path, &sample)
+    } else {
+        (FileKind::Binary, "application/octet-stream")
+    };
+
+    let ingestible = matches!(
+        kind,
+        FileKind::PlainText | FileKind::Markdown | FileKind::Code { .. } | FileKind::Pdf
+    );
+
+    Ok(FileClassification {
+        path: path.to_string_lossy().to_string(),
+        kind,
+        mime_type: mime_type.to_string(),
+        ingestible,
+        size_bytes,
+    })
+}
+
+#[tauri::command]
+pub async fn classify_file(path: String) -> Result<FileClassification, String> {
+    tokio::task::spawn_blocking(move || classify_path(Path::new(&path)))
+        .await
+        .map_err(|e| format!("File classification failed: {}", e))?
+}
@@ -0,0 +1,592 @@
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+use crate::system_info::is_running_under_rosetta;
+
+// Each nvidia-smi call costs tens of milliseconds of CPU, so polling is never faster than this
+const MIN_GPU_MEMORY_INTERVAL: Duration = Duration::from_millis(500);
+const DEFAULT_GPU_MEMORY_INTERVAL: Duration = Duration::from_secs(1);
+// Deadline for each compute backend tool (nvidia-smi, rocminfo, vulkaninfo)
+const BACKEND_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+// Id of the live gpu-memory stream; the polling loop stops once it no longer matches
+static GPU_MEMORY_STREAM: Mutex<Option<u64>> = Mutex::new(None);
+static NEXT_GPU_MEMORY_STREAM: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GpuKind {
+    Integrated,
+    Discrete,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuInfo {
+    pub name: String,
+    pub vendor: String,
+    pub kind: GpuKind,
+    pub vram_gb: Option<f64>,
+    // True when the GPU borrows system RAM instead of having dedicated VRAM
+    pub shared_memory: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuMemorySample {
+    pub index: usize,
+    pub name: String,
+    pub used_mb: u64,
+    pub free_mb: u64,
+    pub total_mb: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuMemoryEvent {
+    pub timestamp_ms: u64,
+    pub gpus: Vec<GpuMemorySample>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GpuMemoryStreamStatus {
+    Started,
+    // No GPU whose memory use can be read, e.g. Apple silicon's unified memory
+    Unsupported,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ComputeBackends {
+    pub metal: bool,
+    // NVIDIA driver version
+    pub cuda: Option<String>,
+    // ROCm release, or "unknown" when rocminfo works but no version file was found
+    pub rocm: Option<String>,
+    pub vulkan: bool,
+}
+
+impl ComputeBackends {
+    // Vulkan is left out because Ollama doesn't use it out of the box
+    pub fn has_gpu_acceleration(&self) -> bool {
+        self.metal || self.cuda.is_some() || self.rocm.is_some()
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        if self.metal {
+            names.push("Metal".to_string());
+        }
+        if let Some(driver) = &self.cuda {
+            names.push(format!("CUDA (driver {})", driver));
+        }
+        if let Some(version) = &self.rocm {
+            names.push(format!("ROCm {}", version));
+        }
+        if self.vulkan {
+            names.push("Vulkan".to_string());
+        }
+        names
+    }
+}
+
+// AMD APU codenames as they appear in lspci output
+const AMD_APU_CODENAMES: &[&str] = &[
+    "renoir", "cezanne", "lucienne", "barcelo", "rembrandt", "phoenix", "hawk point",
+    "raphael", "van gogh", "picasso", "raven", "mendocino", "strix", "krackan",
+];
+
+fn bytes_to_gb(bytes: u64) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0 * 1024.0)
+}
+
+fn run_command(program: &str, args: &[&str]) -> Option<String> {
+    match Command::new(program).args(args).output() {
+        Ok(output) if output.status.success() => Some(String::from_utf8_lossy(&output.stdout).to_string()),
+        _ => None,
+    }
+}
+
+// Vendor tools can hang on a wedged driver, so kill them once the deadline passes
+fn run_command_with_timeout(program: &str, args: &[&str], timeout: Duration) -> Option<String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    // Drain stdout on another thread so a chatty tool can't stall on a full pipe
+    let mut stdout = child.stdout.take()?;
+    let reader = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = stdout.read_to_end(&mut output);
+        output
+    });
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                let output = reader.join().ok()?;
+                return status.success().then(|| String::from_utf8_lossy(&output).to_string());
+            }
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(25)),
+            Ok(None) => {
+                eprintln!("Warning: {} did not finish within {}s", program, timeout.as_secs());
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+            Err(_) => {
+                let _ = child.kill();
+                return None;
+            }
+        }
+    }
+}
+
+pub fn classify_gpu(name: &str) -> (String, GpuKind) {
+    let lower = name.to_lowercase();
+
+    // "ati" has to be a whole word, otherwise any "... Corporation" adapter would count as AMD
+    let is_ati = lower.contains("ati technologies") || lower.split(|c: char| !c.is_alphanumeric()).any(|word| word == "ati");
+
+    if lower.contains("nvidia") || lower.contains("geforce") || lower.contains("quadro") || lower.contains("tesla") {
+        ("NVIDIA".to_string(), GpuKind::Discrete)
+    } else if lower.contains("apple") {
+        ("Apple".to_string(), GpuKind::Integrated)
+    } else if lower.contains("intel") {
+        // Arc cards are Intel's discrete line; everything else is an iGPU
+        let kind = if lower.contains(" arc") || lower.contains("dg2") {
+            GpuKind::Discrete
+        } else {
+            GpuKind::Integrated
+        };
+        ("Intel".to_string(), kind)
+    } else if lower.contains("amd") || is_ati || lower.contains("radeon") {
+        // APUs show up either by codename or as a bare "Radeon Graphics" / "Radeon Vega" adapter
+        let is_apu = AMD_APU_CODENAMES.iter().any(|codename| lower.contains(codename))
+            || lower.contains("radeon(tm) graphics")
+            || lower.contains("radeon graphics")
+            || lower.contains("vega mobile")
+            || lower.contains("radeon vega 8")
+            || lower.ends_with("radeon vega");
+        let kind = if is_apu { GpuKind::Integrated } else { GpuKind::Discrete };
+        ("AMD".to_string(), kind)
+    } else {
+        ("Unknown".to_string(), GpuKind::Unknown)
+    }
+}
+
+fn detect_nvidia_gpus() -> Vec<GpuInfo> {
+    // e.g. "NVIDIA GeForce RTX 3080, 10240" (memory in MiB)
+    let Some(output) = run_command("nvidia-smi", &["--query-gpu=name,memory.total", "--format=csv,noheader,nounits"]) else {
+        return Vec::new();
+    };
+
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, memory_mib) = line.rsplit_once(',')?;
+            let vram_gb = memory_mib.trim().parse::<u64>().ok().map(|mib| bytes_to_gb(mib * 1024 * 1024));
+
+            Some(GpuInfo {
+                name: name.trim().to_string(),
+                vendor: "NVIDIA".to_string(),
+                kind: GpuKind::Discrete,
+                vram_gb,
+                shared_memory: false,
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn detect_platform_gpus() -> Vec<GpuInfo> {
+    let Some(output) = run_command("system_profiler", &["SPDisplaysDataType", "-json"]) else {
+        return Vec::new();
+    };
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(&output) else {
+        return Vec::new();
+    };
+
+    let Some(displays) = json.get("SPDisplaysDataType").and_then(|v| v.as_array()) else {
+        return Vec::new();
+    };
+
+    displays
+        .iter()
+        .filter_map(|display| {
+            let name = display.get("sppci_model").and_then(|v| v.as_str())?.to_string();
+            let (vendor, mut kind) = classify_gpu(&name);
+
+            // Dedicated VRAM is reported as e.g. "4 GB"; shared memory GPUs report spdisplays_vram_shared
+            let vram_gb = display
+                .get("spdisplays_vram")
+                .and_then(|v| v.as_str())
+                .and_then(parse_memory_string_gb);
+            let is_builtin = display.get("sppci_bus").and_then(|v| v.as_str()) == Some("spdisplays_builtin");
+
+            if kind == GpuKind::Unknown {
+                kind = if is_builtin { GpuKind::Integrated } else { GpuKind::Discrete };
+            }
+
+            Some(GpuInfo {
+                name,
+                vendor,
+                shared_memory: vram_gb.is_none() || is_builtin,
+                kind,
+                vram_gb,
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn parse_memory_string_gb(value: &str) -> Option<f64> {
+    let mut parts = value.split_whitespace();
+    let amount = parts.next()?.parse::<f64>().ok()?;
+    match parts.next()?.to_uppercase().as_str() {
+        "GB" => Some(amount),
+        "MB" => Some(amount / 1024.0),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn detect_platform_gpus() -> Vec<GpuInfo> {
+    let script = "Get-CimInstance Win32_VideoController | ForEach-Object { '{0}|{1}' -f $_.Name, $_.AdapterRAM }";
+    let Some(output) = run_command("powershell", &["-NoProfile", "-Command", script]) else {
+        return Vec::new();
+    };
+
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, adapter_ram) = line.trim().rsplit_once('|')?;
+            if name.is_empty() || name.to_lowercase().contains("basic display") {
+                return None;
+            }
+            let (vendor, kind) = classify_gpu(name);
+
+            // AdapterRAM is a uint32 so it saturates at 4GB; only trust it for smaller values
+            let vram_gb = adapter_ram
+                .trim()
+                .parse::<u64>()
+                .ok()
+                .filter(|bytes| *bytes > 0 && *bytes < u32::MAX as u64)
+                .map(bytes_to_gb);
+
+            Some(GpuInfo {
+                name: name.to_string(),
+                vendor,
+                shared_memory: kind == GpuKind::Integrated,
+                kind,
+                vram_gb,
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn detect_platform_gpus() -> Vec<GpuInfo> {
+    let Some(output) = run_command("lspci", &[]) else {
+        return Vec::new();
+    };
+
+    // AMD discrete cards expose their VRAM through the amdgpu driver in sysfs
+    let amd_vram_gb = read_amdgpu_vram_sizes();
+    let mut amd_discrete_index = 0;
+
+    output
+        .lines()
+        .filter(|line| {
+            line.contains("VGA compatible controller") || line.contains("3D controller") || line.contains("Display controller")
+        })
+        .filter_map(|line| {
+            // e.g. "01:00.0 VGA compatible controller: NVIDIA Corporation GA102 [GeForce RTX 3080] (rev a1)"
+            let name = line.splitn(3, ':').nth(2)?.trim();
+            let name = name.split(" (rev").next().unwrap_or(name).to_string();
+            let (vendor, kind) = classify_gpu(&name);
+
+            let vram_gb = if vendor == "AMD" && kind == GpuKind::Discrete {
+                let vram = amd_vram_gb.get(amd_discrete_index).copied();
+                amd_discrete_index += 1;
+                vram
+            } else {
+                None
+            };
+
+            Some(GpuInfo {
+                name,
+                vendor,
+                shared_memory: kind == GpuKind::Integrated,
+                kind,
+                vram_gb,
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn drm_cards() -> Vec<std::path::PathBuf> {
+    let Ok(entries) = std::fs::read_dir("/sys/class/drm") else {
+        return Vec::new();
+    };
+
+    let mut cards: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| name.starts_with("card") && !name.contains('-'))
+                .unwrap_or(false)
+        })
+        .collect();
+    cards.sort();
+    cards
+}
+
+#[cfg(target_os = "linux")]
+fn read_amdgpu_vram_sizes() -> Vec<f64> {
+    drm_cards()
+        .iter()
+        .filter_map(|card| std::fs::read_to_string(card.join("device/mem_info_vram_total")).ok())
+        .filter_map(|bytes| bytes.trim().parse::<u64>().ok())
+        // APUs report a small BIOS carve-out here; only count real dedicated memory
+        .filter(|bytes| *bytes >= 2 * 1024 * 1024 * 1024)
+        .map(bytes_to_gb)
+        .collect()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn detect_platform_gpus() -> Vec<GpuInfo> {
+    Vec::new()
+}
+
+fn sample_nvidia_memory() -> Vec<GpuMemorySample> {
+    // e.g. "0, NVIDIA GeForce RTX 3080, 2311, 7729, 10240" (memory in MiB)
+    let Some(output) = run_command(
+        "nvidia-smi",
+        &["--query-gpu=index,name,memory.used,memory.free,memory.total", "--format=csv,noheader,nounits"],
+    ) else {
+        return Vec::new();
+    };
+
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [index, name, used, free, total] = fields.as_slice() else {
+                return None;
+            };
+            Some(GpuMemorySample {
+                index: index.parse().ok()?,
+                name: name.to_string(),
+                used_mb: used.parse().ok()?,
+                free_mb: free.parse().ok()?,
+                total_mb: total.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+// amdgpu exposes live VRAM counters in sysfs, which is cheap enough to read on every tick
+#[cfg(target_os = "linux")]
+fn sample_amdgpu_memory() -> Vec<GpuMemorySample> {
+    let read_bytes = |path: std::path::PathBuf| -> Option<u64> { std::fs::read_to_string(path).ok()?.trim().parse().ok() };
+    drm_cards()
+        .iter()
+        .filter_map(|card| {
+            let total = read_bytes(card.join("device/mem_info_vram_total"))?;
+            let used = read_bytes(card.join("device/mem_info_vram_used"))?;
+            // Same APU carve-out cutoff as read_amdgpu_vram_sizes
+            (total >= 2 * 1024 * 1024 * 1024).then_some((card, used, total))
+        })
+        .enumerate()
+        .map(|(index, (card, used, total))| GpuMemorySample {
+            index,
+            name: card
+                .file_name()
+                .map(|name| format!("AMD GPU ({})", name.to_string_lossy()))
+                .unwrap_or_else(|| "AMD GPU".to_string()),
+            used_mb: used / (1024 * 1024),
+            free_mb: total.saturating_sub(used) / (1024 * 1024),
+            total_mb: total / (1024 * 1024),
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn sample_amdgpu_memory() -> Vec<GpuMemorySample> {
+    Vec::new()
+}
+
+pub fn sample_gpu_memory() -> Vec<GpuMemorySample> {
+    let mut samples = sample_nvidia_memory();
+    if samples.is_empty() {
+        samples = sample_amdgpu_memory();
+    }
+    samples
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn is_current_stream(id: u64) -> bool {
+    GPU_MEMORY_STREAM.lock().map(|stream| *stream == Some(id)).unwrap_or(false)
+}
+
+pub fn detect_gpus() -> Vec<GpuInfo> {
+    let nvidia_gpus = detect_nvidia_gpus();
+    let mut gpus = nvidia_gpus.clone();
+
+    // nvidia-smi is authoritative for NVIDIA cards, so skip the platform's duplicate entries
+    for gpu in detect_platform_gpus() {
+        if gpu.vendor == "NVIDIA" && !nvidia_gpus.is_empty() {
+            continue;
+        }
+        gpus.push(gpu);
+    }
+
+    gpus
+}
+
+fn detect_cuda_driver() -> Option<String> {
+    let output = run_command_with_timeout("nvidia-smi", &["--query-gpu=driver_version", "--format=csv,noheader"], BACKEND_PROBE_TIMEOUT)?;
+    output.lines().map(|line| line.trim()).find(|line| !line.is_empty()).map(|line| line.to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn detect_rocm() -> Option<String> {
+    // e.g. "6.1.2-119"
+    if let Ok(version) = std::fs::read_to_string("/opt/rocm/.info/version") {
+        let version = version.trim();
+        if !version.is_empty() {
+            return Some(version.split('-').next().unwrap_or(version).to_string());
+        }
+    }
+
+    // rocminfo only succeeds when the kernel driver and runtime are both working
+    run_command_with_timeout("rocminfo", &[], BACKEND_PROBE_TIMEOUT).map(|_| "unknown".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn detect_rocm() -> Option<String> {
+    None
+}
+
+fn detect_vulkan() -> bool {
+    if run_command_with_timeout("vulkaninfo", &["--summary"], BACKEND_PROBE_TIMEOUT).is_some() {
+        return true;
+    }
+
+    // The loader library is enough for llama.cpp's Vulkan backend even without the SDK tools
+    let loader_paths: &[&str] = if cfg!(target_os = "windows") {
+        &["C:\\Windows\\System32\\vulkan-1.dll"]
+    } else if cfg!(target_os = "macos") {
+        &["/usr/local/lib/libvulkan.1.dylib", "/opt/homebrew/lib/libvulkan.1.dylib"]
+    } else {
+        &[
+            "/usr/lib/x86_64-linux-gnu/libvulkan.so.1",
+            "/usr/lib/aarch64-linux-gnu/libvulkan.so.1",
+            "/usr/lib64/libvulkan.so.1",
+            "/usr/lib/libvulkan.so.1",
+        ]
+    };
+    loader_paths.iter().any(|path| std::path::Path::new(path).exists())
+}
+
+pub fn probe_compute_backends() -> ComputeBackends {
+    ComputeBackends {
+        // Ollama only uses Metal on Apple silicon; an x86_64 build still gets it when translated by Rosetta
+        metal: cfg!(all(target_os = "macos", target_arch = "aarch64"))
+            || (cfg!(target_os = "macos") && is_running_under_rosetta()),
+        cuda: detect_cuda_driver(),
+        rocm: detect_rocm(),
+        vulkan: detect_vulkan(),
+    }
+}
+
+// The GPU inference should target: the discrete GPU with the most VRAM, if any
+pub fn primary_gpu(gpus: &[GpuInfo]) -> Option<&GpuInfo> {
+    gpus.iter()
+        .filter(|gpu| gpu.kind == GpuKind::Discrete)
+        .max_by(|a, b| {
+            a.vram_gb
+                .unwrap_or(0.0)
+                .partial_cmp(&b.vram_gb.unwrap_or(0.0))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+}
+
+#[tauri::command]
+pub async fn get_gpu_info() -> Result<Vec<GpuInfo>, String> {
+    tokio::task::spawn_blocking(detect_gpus)
+        .await
+        .map_err(|e| format!("GPU detection failed: {}", e))
+}
+
+#[tauri::command]
+pub async fn detect_compute_backends() -> Result<ComputeBackends, String> {
+    tokio::task::spawn_blocking(probe_compute_backends)
+        .await
+        .map_err(|e| format!("Compute backend detection failed: {}", e))
+}
+
+// Poll per-GPU memory use and emit it as gpu-memory events until stopped; starting again replaces the running stream
+#[tauri::command]
+pub async fn stream_gpu_memory(app: AppHandle, interval_ms: Option<u64>) -> Result<GpuMemoryStreamStatus, String> {
+    let interval = interval_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_GPU_MEMORY_INTERVAL)
+        .max(MIN_GPU_MEMORY_INTERVAL);
+
+    let first = tokio::task::spawn_blocking(sample_gpu_memory)
+        .await
+        .map_err(|e| format!("GPU memory sampling failed: {}", e))?;
+    if first.is_empty() {
+        return Ok(GpuMemoryStreamStatus::Unsupported);
+    }
+
+    let id = NEXT_GPU_MEMORY_STREAM.fetch_add(1, Ordering::Relaxed);
+    *GPU_MEMORY_STREAM
+        .lock()
+        .map_err(|e| format!("Failed to start GPU memory stream: {}", e))? = Some(id);
+
+    tauri::async_runtime::spawn(async move {
+        let mut gpus = first;
+        // Sampling runs one call at a time, so a slow nvidia-smi delays the next tick instead of piling up
+        while is_current_stream(id) {
+            let event = GpuMemoryEvent { timestamp_ms: now_ms(), gpus };
+            if let Err(e) = app.emit("gpu-memory", event) {
+                eprintln!("Warning: Failed to emit GPU memory event: {}", e);
+            }
+            tokio::time::sleep(interval).await;
+            if !is_current_stream(id) {
+                break;
+            }
+            gpus = tokio::task::spawn_blocking(sample_gpu_memory).await.unwrap_or_default();
+        }
+    });
+
+    Ok(GpuMemoryStreamStatus::Started)
+}
+
+// Returns whether a stream was running
+#[tauri::command]
+pub async fn stop_gpu_memory_stream() -> Result<bool, String> {
+    let mut stream = GPU_MEMORY_STREAM
+        .lock()
+        .map_err(|e| format!("Failed to stop GPU memory stream: {}", e))?;
+    Ok(stream.take().is_some())
+}
@@ -0,0 +1,233 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::task::JoinSet;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerKind {
+    Ollama,
+    LmStudio,
+    Jan,
+    LlamaCpp,
+    Vllm,
+    KoboldCpp,
+    Gpt4All,
+    // Answers /v1/models but we can't tell which server it is
+    OpenAiCompatible,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerInfo {
+    pub port: u16,
+    pub kind: ServerKind,
+    // What usually listens on this port; differs from `kind` when another server took it
+    pub expected_kind: ServerKind,
+    pub reachable: bool,
+    pub version: Option<String>,
+}
+
+// Default ports of the common local inference servers
+const KNOWN_PORTS: &[(u16, ServerKind)] = &[
+    (11434, ServerKind::Ollama),
+    (1234, ServerKind::LmStudio),
+    (1337, ServerKind::Jan),
+    (8080, ServerKind::LlamaCpp),
+    (8000, ServerKind::Vllm),
+    (5001, ServerKind::KoboldCpp),
+    (4891, ServerKind::Gpt4All),
+];
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Where llama-server listens unless started with --port
+const DEFAULT_LLAMACPP_URL: &str = "http://127.0.0.1:8080";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlamaCppServer {
+    pub base_url: String,
+    pub reachable: bool,
+    // False while the server is still loading the model (/health answers 503)
+    pub ready: bool,
+    pub model: Option<String>,
+    // The context size the server was started with (-c), which can be smaller than the model's
+    pub context_length: Option<u64>,
+    pub chat_template: Option<String>,
+    pub build_info: Option<String>,
+    // llama-server only checks keys when started with --api-key
+    pub requires_api_key: bool,
+}
+
+async fn get_json(client: &reqwest::Client, url: String) -> Option<serde_json::Value> {
+    let response = client.get(url).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response.json().await.ok()
+}
+
+fn json_string(value: &serde_json::Value, key: &str) -> Option<String> {
+    value.get(key).and_then(|v| v.as_str()).map(|v| v.to_string())
+}
+
+async fn probe_port(client: reqwest::Client, port: u16, expected_kind: ServerKind) -> ServerInfo {
+    let base = format!("http://127.0.0.1:{}", port);
+    let unreachable = ServerInfo {
+        port,
+        kind: ServerKind::Unknown,
+        expected_kind,
+        reachable: false,
+        version: None,
+    };
+
+    // Skip the HTTP sniffing entirely when nothing is listening
+    let connect = tokio::time::timeout(PROBE_TIMEOUT, tokio::net::TcpStream::connect(("127.0.0.1", port))).await;
+    if !matches!(connect, Ok(Ok(_))) {
+        return unreachable;
+    }
+
+    let identified = |kind: ServerKind, version: Option<String>| ServerInfo {
+        port,
+        kind,
+        expected_kind,
+        reachable: true,
+        version,
+    };
+
+    // Ollama's native API: /api/version plus /api/tags
+    if let Some(version) = get_json(&client, format!("{}/api/version", base)).await {
+        if get_json(&client, format!("{}/api/tags", base)).await.is_some() {
+            return identified(ServerKind::Ollama, json_string(&version, "version"));
+        }
+    }
+
+    // llama.cpp's server exposes its settings at /props
+    if let Some(props) = get_json(&client, format!("{}/props", base)).await {
+        if props.get("default_generation_settings").is_some() {
+            return identified(ServerKind::LlamaCpp, json_string(&props, "build_info"));
+        }
+    }
+
+    // KoboldCpp has its own versioned API alongside the OpenAI one
+    if let Some(info) = get_json(&client, format!("{}/api/extra/version", base)).await {
+        return identified(ServerKind::KoboldCpp, json_string(&info, "version"));
+    }
+
+    if let Some(models) = get_json(&client, format!("{}/v1/models", base)).await {
+        // vLLM reports its version at /version; LM Studio and Jan tag models with an owner
+        if let Some(version) = get_json(&client, format!("{}/version", base)).await {
+            return identified(ServerKind::Vllm, json_string(&version, "version"));
+        }
+
+        let owners: Vec<String> = models
+            .get("data")
+            .and_then(|data| data.as_array())
+            .map(|data| data.iter().filter_map(|model| json_string(model, "owned_by")).collect())
+            .unwrap_or_default();
+
+        let kind = if owners.iter().any(|owner| owner.eq_ignore_ascii_case("organization_owner")) {
+            ServerKind::LmStudio
+        } else if expected_kind == ServerKind::Jan || expected_kind == ServerKind::Gpt4All || expected_kind == ServerKind::LmStudio {
+            // These servers don't identify themselves, so trust the port if it speaks the API
+            expected_kind
+        } else {
+            ServerKind::OpenAiCompatible
+        };
+
+        return identified(kind, None);
+    }
+
+    // Something is bound to the port but doesn't speak any API we know
+    identified(ServerKind::Unknown, None)
+}
+
+pub async fn probe_inference_servers() -> Vec<ServerInfo> {
+    let client = reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+
+    let mut probes = JoinSet::new();
+    for (port, expected_kind) in KNOWN_PORTS {
+        probes.spawn(probe_port(client.clone(), *port, *expected_kind));
+    }
+
+    let mut servers = Vec::new();
+    while let Some(result) = probes.join_next().await {
+        match result {
+            Ok(server) => servers.push(server),
+            Err(e) => eprintln!("Warning: Inference server probe failed: {}", e),
+        }
+    }
+
+    // Keep the output in the same order as KNOWN_PORTS
+    servers.sort_by_key(|server| KNOWN_PORTS.iter().position(|(port, _)| *port == server.port));
+    servers
+}
+
+#[tauri::command]
+pub async fn detect_inference_servers() -> Vec<ServerInfo> {
+    probe_inference_servers().await
+}
+
+pub async fn probe_llamacpp_server(base_url: &str) -> Result<LlamaCppServer, String> {
+    let base_url = base_url.trim().trim_end_matches('/').trim_end_matches("/v1").to_string();
+    let client = reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let mut server = LlamaCppServer {
+        base_url: base_url.clone(),
+        reachable: false,
+        ready: false,
+        model: None,
+        context_length: None,
+        chat_template: None,
+        build_info: None,
+        requires_api_key: false,
+    };
+
+    let health = match client.get(format!("{}/health", base_url)).send().await {
+        Ok(response) => response,
+        Err(_) => return Ok(server),
+    };
+    server.reachable = true;
+    server.ready = health.status().is_success();
+
+    let models = client.get(format!("{}/v1/models", base_url)).send().await.ok();
+    match models {
+        Some(response) if response.status() == reqwest::StatusCode::UNAUTHORIZED => server.requires_api_key = true,
+        Some(response) if response.status().is_success() => {
+            let models: Option<serde_json::Value> = response.json().await.ok();
+            server.model = models
+                .as_ref()
+                .and_then(|models| models.get("data"))
+                .and_then(|data| data.as_array())
+                .and_then(|data| data.first())
+                .and_then(|model| json_string(model, "id"));
+        }
+        _ => {}
+    }
+
+    if let Some(props) = get_json(&client, format!("{}/props", base_url)).await {
+        // Older builds only report n_ctx inside default_generation_settings
+        server.context_length = props
+            .pointer("/default_generation_settings/n_ctx")
+            .or_else(|| props.get("n_ctx"))
+            .and_then(|n_ctx| n_ctx.as_u64());
+        server.chat_template = json_string(&props, "chat_template").filter(|template| !template.is_empty());
+        server.build_info = json_string(&props, "build_info");
+        if server.model.is_none() {
+            server.model = json_string(&props, "model_path")
+                .map(|path| path.rsplit(['/', '\\']).next().unwrap_or(&path).to_string());
+        }
+    }
+
+    Ok(server)
+}
+
+#[tauri::command]
+pub async fn detect_llamacpp_server(base_url: Option<String>) -> Result<LlamaCppServer, String> {
+    probe_llamacpp_server(base_url.as_deref().unwrap_or(DEFAULT_LLAMACPP_URL)).await
+}
@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use tauri_plugin_global_shortcut::{Code, Modifiers, Shortcut};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyboardLayout {
+    // Platform identifier for the active layout, e.g. "com.apple.keylayout.German" or "de"
+    pub layout: Option<String>,
+    pub variant: Option<String>,
+    // Character accelerators are resolved against US key positions, so only these layouts map them reliably
+    pub us_compatible: bool,
+    pub shortcut_warning: Option<String>,
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+// Returns (layout, variant)
+#[cfg(target_os = "macos")]
+fn read_layout() -> Option<(String, Option<String>)> {
+    command_output("defaults", &["read", "com.apple.HIToolbox", "AppleCurrentKeyboardLayoutInputSourceID"])
+        .map(|layout| (layout, None))
+}
+
+#[cfg(target_os = "linux")]
+fn read_layout() -> Option<(String, Option<String>)> {
+    let field = |text: &str, name: &str| {
+        text.lines()
+            .find_map(|line| line.trim().strip_prefix(name))
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    if let Some(query) = command_output("setxkbmap", &["-query"]) {
+        if let Some(layout) = field(&query, "layout:") {
+            return Some((layout, field(&query, "variant:")));
+        }
+    }
+
+    // Wayland sessions without XWayland tools still expose the configured layout through systemd
+    let status = command_output("localectl", &["status"])?;
+    Some((field(&status, "X11 Layout:")?, field(&status, "X11 Variant:")))
+}
+
+#[cfg(target_os = "windows")]
+fn read_layout() -> Option<(String, Option<String>)> {
+    // Input method tips look like "0409:00000409"; the part after the colon is the layout id
+    command_output(
+        "powershell",
+        &["-NoProfile", "-Command", "(Get-WinUserLanguageList)[0].InputMethodTips[0]"],
+    )
+    .map(|tip| (tip, None))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn read_layout() -> Option<(String, Option<String>)> {
+    None
+}
+
+fn is_us_compatible(layout: &str, variant: Option<&str>) -> bool {
+    // Alternate arrangements like Dvorak and Colemak move the letters even on a "us" layout
+    if variant.is_some_and(|variant| !variant.is_empty() && variant != "basic") {
+        return false;
+    }
+
+    let layout = layout.to_lowercase();
+    // Multi-layout setups list the primary layout first
+    let primary = layout.split(',').next().unwrap_or_default().trim();
+    let primary = primary.rsplit(':').next().unwrap_or(primary);
+
+    matches!(
+        primary,
+        "us" | "com.apple.keylayout.us" | "com.apple.keylayout.abc" | "00000409"
+    )
+}
+
+// Named keys like F1 or Space sit in the same place on every layout; only character keys move
+fn has_character_key(shortcut: &str) -> bool {
+    shortcut
+        .rsplit('+')
+        .next()
+        .map(|key| key.trim())
+        .is_some_and(|key| key.chars().count() == 1)
+}
+
+pub fn parse_modifiers(modifiers: &[String]) -> Result<Modifiers, String> {
+    let mut parsed = Modifiers::empty();
+    for modifier in modifiers {
+        parsed |= match modifier.trim().to_lowercase().as_str() {
+            "shift" => Modifiers::SHIFT,
+            "alt" | "option" => Modifiers::ALT,
+            "control" | "ctrl" => Modifiers::CONTROL,
+            "super" | "cmd" | "command" | "meta" => Modifiers::SUPER,
+            "commandorcontrol" | "cmdorctrl" | "commandorctrl" | "cmdorcontrol" => {
+                if cfg!(target_os = "macos") {
+                    Modifiers::SUPER
+                } else {
+                    Modifiers::CONTROL
+                }
+            }
+            other => return Err(format!("Unknown shortcut modifier '{}'", other)),
+        };
+    }
+    Ok(parsed)
+}
+
+// Build a shortcut from a physical key code as reported by KeyboardEvent.code, e.g. "KeyK" or "Digit1"
+pub fn shortcut_from_code(modifiers: &[String], code: &str) -> Result<Shortcut, String> {
+    let key = Code::from_str(code.trim())
+        .ok()
+        .filter(|key| *key != Code::Unidentified)
+        .ok_or_else(|| format!("Unknown key code '{}'; expected a value such as KeyK or Digit1", code))?;
+    let modifiers = parse_modifiers(modifiers)?;
+
+    Ok(Shortcut::new((!modifiers.is_empty()).then_some(modifiers), key))
+}
+
+pub fn detect_layout(shortcut: Option<&str>) -> KeyboardLayout {
+    let (layout, variant) = match read_layout() {
+        Some((layout, variant)) => (Some(layout), variant),
+        None => (None, None),
+    };
+
+    // Without layout information, assume US rather than warn on every shortcut
+    let us_compatible = layout
+        .as_deref()
+        .is_none_or(|layout| is_us_compatible(layout, variant.as_deref()));
+
+    let shortcut_warning = match shortcut {
+        Some(shortcut) if !us_compatible && has_character_key(shortcut) => Some(format!(
+            "'{}' is matched by US key position and may trigger on a different key with the {} layout. Record it by physical key instead.",
+            shortcut,
+            layout.as_deref().unwrap_or("current")
+        )),
+        _ => None,
+    };
+
+    KeyboardLayout {
+        layout,
+        variant,
+        us_compatible,
+        shortcut_warning,
+    }
+}
+
+#[tauri::command]
+pub async fn detect_keyboard_layout(shortcut: Option<String>) -> Result<KeyboardLayout, String> {
+    tokio::task::spawn_blocking(move || detect_layout(shortcut.as_deref()))
+        .await
+        .map_err(|e| format!("Keyboard layout detection failed: {}", e))
+}
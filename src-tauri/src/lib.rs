@@ -1,5 +1,42 @@
+mod app_dirs;
+mod audio;
+mod benchmark;
+mod clipboard;
+mod compare;
+mod conversations;
+mod cpu;
+mod database;
+mod db_integrity;
+mod drafts;
+mod export;
+mod file_types;
+mod gpu;
+mod inference_servers;
+mod keyboard;
+mod messages;
+mod model_capabilities;
+mod model_files;
+mod models;
+mod network;
 mod ollama;
+mod ollama_registry;
+mod operations;
+mod param_presets;
+mod provider_format;
+mod provider_ping;
+mod report;
+mod retention;
+mod secrets;
+mod settings;
+mod shutdown;
+mod summarize;
 mod system_info;
+mod templates;
+mod thermal;
+mod tts;
+mod update_bundles;
+mod webview;
+mod wipe;
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
@@ -7,14 +44,30 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
-use tauri::{Manager, WebviewUrl, WebviewWindowBuilder, Position, LogicalPosition, AppHandle};
+use tauri::{Emitter, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder, WindowEvent, Monitor, Position, LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, AppHandle};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+use std::collections::BTreeMap;
 use std::str::FromStr;
 use std::sync::Mutex;
 
+const MINI_WINDOW_SIZE_KEY: &str = "miniWindowSize";
+const MINI_WINDOW_MIN_SIZE: (f64, f64) = (400.0, 400.0);
+const MINI_WINDOW_MAX_SIZE: (f64, f64) = (600.0, 1200.0);
+
 // Track registered shortcuts for proper cleanup
 static REGISTERED_SHORTCUTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
 
+// Action bound to each shortcut registered as part of a profile, keyed by shortcut id.
+// Shortcuts without an entry toggle the mini window.
+static SHORTCUT_ACTIONS: Mutex<BTreeMap<u32, String>> = Mutex::new(BTreeMap::new());
+const TOGGLE_MINI_WINDOW_ACTION: &str = "toggle_mini_window";
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct ShortcutBinding {
+    shortcut: String,
+    action: String,
+}
+
 #[tauri::command]
 async fn toggle_mini_window(app: tauri::AppHandle) -> Result<bool, String> {
     if let Some(window) = app.get_webview_window("mini-chat") {
@@ -29,6 +82,11 @@ async fn toggle_mini_window(app: tauri::AppHandle) -> Result<bool, String> {
                 .map_err(|e| format!("Failed to hide mini window: {}", e))?;
             Ok(false)
         } else {
+            // The saved position may be on a monitor that's no longer connected
+            if let Err(e) = ensure_mini_window_on_screen(&window) {
+                eprintln!("Warning: Failed to keep mini window on screen: {}", e);
+            }
+
             // Window is either hidden or not focused, show and focus it
             window.show()
                 .map_err(|e| format!("Failed to show mini window: {}", e))?;
@@ -37,6 +95,8 @@ async fn toggle_mini_window(app: tauri::AppHandle) -> Result<bool, String> {
             Ok(true)
         }
     } else {
+        let (width, height) = saved_mini_window_size(&app);
+
         // Create new mini window with query parameter
         let mini_window = WebviewWindowBuilder::new(
             &app,
@@ -45,9 +105,9 @@ async fn toggle_mini_window(app: tauri::AppHandle) -> Result<bool, String> {
         )
         .title("Mini Chat")
         .hidden_title(true)
-        .inner_size(400.0, 600.0)
-        .min_inner_size(400.0, 400.0)
-        .max_inner_size(600.0, 1200.0)
+        .inner_size(width, height)
+        .min_inner_size(MINI_WINDOW_MIN_SIZE.0, MINI_WINDOW_MIN_SIZE.1)
+        .max_inner_size(MINI_WINDOW_MAX_SIZE.0, MINI_WINDOW_MAX_SIZE.1)
         .resizable(true)
         .always_on_top(true)
         .skip_taskbar(true)
@@ -60,36 +120,125 @@ async fn toggle_mini_window(app: tauri::AppHandle) -> Result<bool, String> {
             eprintln!("Warning: Failed to set mini window on all workspaces: {}", e);
         }
 
-        // Position window in bottom right corner with error handling
-        if let Ok(monitor) = mini_window.primary_monitor() {
-            if let Some(monitor) = monitor {
-                let screen_size = monitor.size();
-                let window_size = mini_window.inner_size().unwrap_or(tauri::PhysicalSize { width: 400, height: 600 });
-                
-                // Position with some padding from the edges (80px)
-                let x = screen_size.width as f64 - window_size.width as f64 - 80.0;
-                let y = screen_size.height as f64 - window_size.height as f64 - 80.0; // Extra padding for taskbar/dock
-                
-                if let Err(e) = mini_window.set_position(Position::Physical(tauri::PhysicalPosition { x: x as i32, y: y as i32 })) {
-                    eprintln!("Warning: Failed to set mini window position: {}", e);
-                }
-            } else {
-                // Fallback position if monitor detection fails
-                if let Err(e) = mini_window.set_position(Position::Logical(LogicalPosition { x: 100.0, y: 100.0 })) {
-                    eprintln!("Warning: Failed to set fallback mini window position: {}", e);
-                }
-            }
-        } else {
-            // Fallback position if monitor access fails
-            if let Err(e) = mini_window.set_position(Position::Logical(LogicalPosition { x: 100.0, y: 100.0 })) {
-                eprintln!("Warning: Failed to set fallback mini window position: {}", e);
+        // Move the mini window back on screen when the display configuration changes
+        // (e.g. an external monitor is unplugged while the window sat on it). Not every platform reports
+        // a scale change then, so also check on moves and resizes; a window still on a monitor is left alone
+        let window_for_events = mini_window.clone();
+        mini_window.on_window_event(move |event| {
+            if let WindowEvent::ScaleFactorChanged { .. } | WindowEvent::Moved(_) | WindowEvent::Resized(_) = event {
+                let window = window_for_events.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = ensure_mini_window_on_screen(&window) {
+                        eprintln!("Warning: Failed to keep mini window on screen: {}", e);
+                    }
+                });
             }
-        }
+        });
+
+        // Position window in bottom right corner with error handling
+        position_mini_window_default(&mini_window);
         
         Ok(true)
     }
 }
 
+// Place the mini window in the bottom right corner of the primary monitor
+fn position_mini_window_default(window: &WebviewWindow) {
+    let monitor = window.primary_monitor()
+        .ok()
+        .flatten()
+        .or_else(|| window.available_monitors().ok().and_then(|monitors| monitors.into_iter().next()));
+
+    if let Some(monitor) = monitor {
+        let monitor_position = monitor.position();
+        let screen_size = monitor.size();
+        let window_size = window.inner_size().unwrap_or(PhysicalSize { width: 400, height: 600 });
+        
+        // Position with some padding from the edges (80px)
+        let x = monitor_position.x as f64 + screen_size.width as f64 - window_size.width as f64 - 80.0;
+        let y = monitor_position.y as f64 + screen_size.height as f64 - window_size.height as f64 - 80.0; // Extra padding for taskbar/dock
+        
+        if let Err(e) = window.set_position(Position::Physical(PhysicalPosition { x: x as i32, y: y as i32 })) {
+            eprintln!("Warning: Failed to set mini window position: {}", e);
+        }
+    } else {
+        // Fallback position if monitor detection fails
+        if let Err(e) = window.set_position(Position::Logical(LogicalPosition { x: 100.0, y: 100.0 })) {
+            eprintln!("Warning: Failed to set fallback mini window position: {}", e);
+        }
+    }
+}
+
+// Returns a corrected position when the window doesn't meaningfully overlap any monitor,
+// snapping it inside the bounds of the nearest one
+fn snap_position_to_monitors(
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+    monitors: &[Monitor],
+) -> Option<PhysicalPosition<i32>> {
+    // Minimum overlap in pixels for the window to count as reachable on a monitor
+    const MIN_VISIBLE_OVERLAP: i64 = 50;
+
+    let (x, y) = (position.x as i64, position.y as i64);
+    let (width, height) = (size.width as i64, size.height as i64);
+
+    let bounds = |monitor: &Monitor| {
+        let origin = monitor.position();
+        let size = monitor.size();
+        (origin.x as i64, origin.y as i64, size.width as i64, size.height as i64)
+    };
+
+    let is_visible = monitors.iter().any(|monitor| {
+        let (mx, my, mw, mh) = bounds(monitor);
+        let overlap_x = (x + width).min(mx + mw) - x.max(mx);
+        let overlap_y = (y + height).min(my + mh) - y.max(my);
+        overlap_x >= MIN_VISIBLE_OVERLAP.min(width) && overlap_y >= MIN_VISIBLE_OVERLAP.min(height)
+    });
+
+    if is_visible {
+        return None;
+    }
+
+    // Pick the monitor closest to the window's center
+    let (center_x, center_y) = (x + width / 2, y + height / 2);
+    let nearest = monitors.iter().min_by_key(|monitor| {
+        let (mx, my, mw, mh) = bounds(monitor);
+        let dx = (mx - center_x).max(0).max(center_x - (mx + mw));
+        let dy = (my - center_y).max(0).max(center_y - (my + mh));
+        dx * dx + dy * dy
+    })?;
+
+    let (mx, my, mw, mh) = bounds(nearest);
+    let snapped_x = x.min(mx + mw - width).max(mx);
+    let snapped_y = y.min(my + mh - height).max(my);
+
+    Some(PhysicalPosition { x: snapped_x as i32, y: snapped_y as i32 })
+}
+
+fn ensure_mini_window_on_screen(window: &WebviewWindow) -> Result<(), String> {
+    let position = window.outer_position()
+        .map_err(|e| format!("Failed to get mini window position: {}", e))?;
+    let size = window.outer_size()
+        .map_err(|e| format!("Failed to get mini window size: {}", e))?;
+    let monitors = window.available_monitors()
+        .map_err(|e| format!("Failed to list monitors: {}", e))?;
+
+    if let Some(snapped) = snap_position_to_monitors(position, size, &monitors) {
+        window.set_position(Position::Physical(snapped))
+            .map_err(|e| format!("Failed to move mini window on screen: {}", e))?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn reset_mini_window_position(app: tauri::AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window("mini-chat") {
+        position_mini_window_default(&window);
+    }
+    Ok(())
+}
+
 #[tauri::command]
 async fn close_mini_window(app: tauri::AppHandle) -> Result<(), String> {
     if let Some(window) = app.get_webview_window("mini-chat") {
@@ -99,6 +248,112 @@ async fn close_mini_window(app: tauri::AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+// A named preset ("compact", "standard", "tall") or explicit logical dimensions
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+enum MiniWindowSize {
+    Preset(String),
+    Custom { width: f64, height: f64 },
+}
+
+impl MiniWindowSize {
+    fn dimensions(&self) -> Result<(f64, f64), String> {
+        let (width, height) = match self {
+            MiniWindowSize::Preset(preset) => match preset.as_str() {
+                // As small as the window can be resized by hand
+                "compact" => MINI_WINDOW_MIN_SIZE,
+                "standard" => (400.0, 600.0),
+                "tall" => (400.0, 900.0),
+                other => return Err(format!("Unknown mini window size preset '{}'", other)),
+            },
+            MiniWindowSize::Custom { width, height } => {
+                if !width.is_finite() || !height.is_finite() {
+                    return Err("Mini window dimensions must be finite numbers".to_string());
+                }
+                (*width, *height)
+            }
+        };
+
+        Ok((
+            width.clamp(MINI_WINDOW_MIN_SIZE.0, MINI_WINDOW_MAX_SIZE.0),
+            height.clamp(MINI_WINDOW_MIN_SIZE.1, MINI_WINDOW_MAX_SIZE.1),
+        ))
+    }
+}
+
+fn saved_mini_window_size(app: &AppHandle) -> (f64, f64) {
+    let saved = settings::get_setting::<MiniWindowSize>(app, MINI_WINDOW_SIZE_KEY)
+        .unwrap_or_else(|e| {
+            eprintln!("Warning: Failed to read mini window size: {}", e);
+            None
+        });
+
+    saved
+        .and_then(|size| size.dimensions().ok())
+        .unwrap_or((400.0, 600.0))
+}
+
+#[tauri::command]
+async fn set_mini_window_size(app: tauri::AppHandle, preset: MiniWindowSize) -> Result<LogicalSize<f64>, String> {
+    let (width, height) = preset.dimensions()?;
+
+    // Tauri can't animate window resizes, so the window snaps to the new size
+    if let Some(window) = app.get_webview_window("mini-chat") {
+        window.set_size(LogicalSize::new(width, height))
+            .map_err(|e| format!("Failed to resize mini window: {}", e))?;
+
+        // Growing taller can push the bottom edge off a small screen
+        if let Err(e) = ensure_mini_window_on_screen(&window) {
+            eprintln!("Warning: Failed to keep mini window on screen: {}", e);
+        }
+    }
+
+    settings::set_setting(&app, MINI_WINDOW_SIZE_KEY, &preset)?;
+    Ok(LogicalSize::new(width, height))
+}
+
+#[derive(serde::Serialize)]
+struct MiniWindowState {
+    exists: bool,
+    visible: bool,
+    focused: bool,
+    // The mini window is pinned when it stays above other windows
+    pinned: bool,
+    position: Option<LogicalPosition<f64>>,
+    size: Option<LogicalSize<f64>>,
+}
+
+// Read the mini window's actual state, which can drift from what the frontend last toggled
+// (e.g. after the user closes it from the title bar)
+#[tauri::command]
+async fn mini_window_state(app: tauri::AppHandle) -> Result<MiniWindowState, String> {
+    let Some(window) = app.get_webview_window("mini-chat") else {
+        return Ok(MiniWindowState {
+            exists: false,
+            visible: false,
+            focused: false,
+            pinned: false,
+            position: None,
+            size: None,
+        });
+    };
+
+    let scale_factor = window.scale_factor()
+        .map_err(|e| format!("Failed to read mini window scale factor: {}", e))?;
+
+    Ok(MiniWindowState {
+        exists: true,
+        visible: window.is_visible()
+            .map_err(|e| format!("Failed to check window visibility: {}", e))?,
+        focused: window.is_focused()
+            .map_err(|e| format!("Failed to check window focus: {}", e))?,
+        pinned: window.is_always_on_top()
+            .map_err(|e| format!("Failed to check whether mini window is pinned: {}", e))?,
+        position: window.outer_position().ok().map(|position| position.to_logical(scale_factor)),
+        size: window.inner_size().ok().map(|size| size.to_logical(scale_factor)),
+    })
+}
+
 #[tauri::command]
 async fn register_global_shortcut(app: AppHandle, shortcut: String) -> Result<(), String> {
     // Handle empty shortcuts gracefully
@@ -109,25 +364,42 @@ async fn register_global_shortcut(app: AppHandle, shortcut: String) -> Result<()
     }
 
     // Validate shortcut format before attempting registration
-    let parsed_shortcut = Shortcut::from_str(&shortcut)
-        .map_err(|e| format!("Invalid shortcut format '{}': {}", shortcut, e))?;
+    let parsed_shortcut = Shortcut::from_str(&shortcut).map_err(|e| {
+        // Characters that only exist on non-US layouts (ö, ñ, ...) can't be parsed as accelerators
+        format!(
+            "Invalid shortcut format '{}': {}. Try recording it by physical key instead.",
+            shortcut, e
+        )
+    })?;
+
+    register_parsed_shortcut(&app, parsed_shortcut, shortcut.clone()).map_err(|error| {
+        if error.contains("already in use") || keyboard::detect_layout(None).us_compatible {
+            error
+        } else {
+            format!("{}. Your keyboard layout may not match this shortcut; try recording it by physical key instead.", error)
+        }
+    })
+}
+
+// Bind by physical key code (KeyboardEvent.code) so the same key position works on any layout
+#[tauri::command]
+async fn register_global_shortcut_by_code(app: AppHandle, modifiers: Vec<String>, code: String) -> Result<String, String> {
+    let parsed_shortcut = keyboard::shortcut_from_code(&modifiers, &code)?;
 
+    // The canonical form parses back through Shortcut::from_str, so unregister_global_shortcut accepts it
+    let shortcut = parsed_shortcut.into_string();
+    register_parsed_shortcut(&app, parsed_shortcut, shortcut.clone())?;
+    Ok(shortcut)
+}
+
+fn register_parsed_shortcut(app: &AppHandle, parsed_shortcut: Shortcut, shortcut: String) -> Result<(), String> {
     // Unregister existing shortcuts first with proper error handling
-    unregister_all_shortcuts(&app)?;
+    unregister_all_shortcuts(app)?;
 
     // Register the new shortcut
     app.global_shortcut()
-        .register(parsed_shortcut.clone())
-        .map_err(|e| {
-            // Provide helpful error messages for common issues
-            if e.to_string().contains("already registered") {
-                format!("Shortcut '{}' is already in use by another application", shortcut)
-            } else if e.to_string().contains("permission") {
-                format!("Permission denied to register global shortcut '{}'. Please check system accessibility settings.", shortcut)
-            } else {
-                format!("Failed to register global shortcut '{}': {}", shortcut, e)
-            }
-        })?;
+        .register(parsed_shortcut)
+        .map_err(|e| describe_registration_error(&e.to_string(), &shortcut))?;
     
     // Track the registered shortcut for cleanup
     if let Ok(mut shortcuts) = REGISTERED_SHORTCUTS.lock() {
@@ -138,6 +410,93 @@ async fn register_global_shortcut(app: AppHandle, shortcut: String) -> Result<()
     Ok(())
 }
 
+// Provide helpful error messages for common issues
+fn describe_registration_error(error: &str, shortcut: &str) -> String {
+    if error.contains("already registered") {
+        format!("Shortcut '{}' is already in use by another application", shortcut)
+    } else if error.contains("permission") {
+        format!("Permission denied to register global shortcut '{}'. Please check system accessibility settings.", shortcut)
+    } else {
+        format!("Failed to register global shortcut '{}': {}", shortcut, error)
+    }
+}
+
+fn register_bindings(app: &AppHandle, bindings: &[(Shortcut, ShortcutBinding)]) -> Result<(), String> {
+    for (index, (parsed_shortcut, binding)) in bindings.iter().enumerate() {
+        if let Err(e) = app.global_shortcut().register(*parsed_shortcut) {
+            // Roll back this batch so a failure never leaves only part of it registered
+            for (registered, _) in &bindings[..index] {
+                if let Err(e) = app.global_shortcut().unregister(*registered) {
+                    eprintln!("Warning: Failed to roll back shortcut registration: {}", e);
+                }
+            }
+            return Err(format!(
+                "{} (binding for '{}')",
+                describe_registration_error(&e.to_string(), &binding.shortcut),
+                binding.action
+            ));
+        }
+    }
+
+    if let Ok(mut shortcuts) = REGISTERED_SHORTCUTS.lock() {
+        *shortcuts = bindings.iter().map(|(_, binding)| binding.shortcut.clone()).collect();
+    }
+    if let Ok(mut actions) = SHORTCUT_ACTIONS.lock() {
+        *actions = bindings
+            .iter()
+            .map(|(parsed_shortcut, binding)| (parsed_shortcut.id(), binding.action.clone()))
+            .collect();
+    }
+    Ok(())
+}
+
+// Replace every registered shortcut with a keybinding profile, all or nothing
+#[tauri::command]
+async fn register_shortcuts(app: AppHandle, bindings: Vec<ShortcutBinding>) -> Result<(), String> {
+    // Validate every chord before touching what's currently registered
+    let mut parsed: Vec<(Shortcut, ShortcutBinding)> = Vec::with_capacity(bindings.len());
+    for binding in bindings {
+        if binding.action.trim().is_empty() {
+            return Err(format!("Shortcut '{}' has no action", binding.shortcut));
+        }
+        let parsed_shortcut = Shortcut::from_str(&binding.shortcut).map_err(|e| {
+            format!("Invalid shortcut format '{}' for '{}': {}", binding.shortcut, binding.action, e)
+        })?;
+        if let Some((_, existing)) = parsed.iter().find(|(other, _)| other.id() == parsed_shortcut.id()) {
+            return Err(format!(
+                "Shortcut '{}' is bound to both '{}' and '{}'",
+                binding.shortcut, existing.action, binding.action
+            ));
+        }
+        parsed.push((parsed_shortcut, binding));
+    }
+
+    // Keep the current profile so it can be put back if the new one fails
+    let previous_shortcuts = REGISTERED_SHORTCUTS.lock().map(|shortcuts| shortcuts.clone()).unwrap_or_default();
+    let previous_actions = SHORTCUT_ACTIONS.lock().map(|actions| actions.clone()).unwrap_or_default();
+    unregister_all_shortcuts(&app)?;
+
+    let Err(error) = register_bindings(&app, &parsed) else {
+        return Ok(());
+    };
+
+    let previous: Vec<(Shortcut, ShortcutBinding)> = previous_shortcuts
+        .into_iter()
+        .filter_map(|shortcut| {
+            let parsed_shortcut = Shortcut::from_str(&shortcut).ok()?;
+            let action = previous_actions
+                .get(&parsed_shortcut.id())
+                .cloned()
+                .unwrap_or_else(|| TOGGLE_MINI_WINDOW_ACTION.to_string());
+            Some((parsed_shortcut, ShortcutBinding { shortcut, action }))
+        })
+        .collect();
+    if let Err(e) = register_bindings(&app, &previous) {
+        eprintln!("Warning: Failed to restore previous shortcuts: {}", e);
+    }
+    Err(error)
+}
+
 #[tauri::command]
 async fn unregister_global_shortcut(app: AppHandle, shortcut: String) -> Result<(), String> {
     if shortcut.trim().is_empty() {
@@ -172,6 +531,9 @@ fn unregister_all_shortcuts(app: &AppHandle) -> Result<(), String> {
     if let Ok(mut shortcuts) = REGISTERED_SHORTCUTS.lock() {
         shortcuts.clear();
     }
+    if let Ok(mut actions) = SHORTCUT_ACTIONS.lock() {
+        actions.clear();
+    }
     
     Ok(())
 }
@@ -190,10 +552,19 @@ pub fn run() {
         .plugin(tauri_plugin_process::init())
         .plugin(
             tauri_plugin_global_shortcut::Builder::new()
-                .with_handler(|app, _shortcut, event| {
+                .with_handler(|app, shortcut, event| {
                     // Only handle key press events, ignore key release
                     use tauri_plugin_global_shortcut::ShortcutState;
                     if event.state == ShortcutState::Pressed {
+                        let action = SHORTCUT_ACTIONS.lock().ok().and_then(|actions| actions.get(&shortcut.id()).cloned());
+                        if let Some(action) = action.filter(|action| action != TOGGLE_MINI_WINDOW_ACTION) {
+                            // Profile actions other than the mini window are handled by the frontend
+                            if let Err(e) = app.emit("global-shortcut-triggered", action) {
+                                eprintln!("Failed to emit global shortcut action: {}", e);
+                            }
+                            return;
+                        }
+
                         // Handle global shortcut events by triggering the mini window toggle
                         let app_handle = app.clone();
                         tauri::async_runtime::spawn(async move {
@@ -209,15 +580,103 @@ pub fn run() {
             greet,
             toggle_mini_window,
             close_mini_window,
+            reset_mini_window_position,
+            mini_window_state,
+            set_mini_window_size,
             register_global_shortcut,
+            register_global_shortcut_by_code,
+            register_shortcuts,
             unregister_global_shortcut,
             ollama::detect_ollama,
             ollama::start_ollama,
             ollama::stop_ollama,
             ollama::discover_models,
+            ollama::hash_model,
+            ollama::load_ollama_model,
+            ollama::sanity_check_model,
+            ollama::ollama_capabilities,
+            ollama::set_ollama_keep_alive,
+            ollama::check_ollama_update,
+            ollama::set_ollama_env_overrides,
+            ollama_registry::list_model_tags,
+            inference_servers::detect_inference_servers,
             system_info::get_system_info,
-            system_info::validate_model_system_compatibility
+            system_info::validate_model_system_compatibility,
+            gpu::get_gpu_info,
+            thermal::thermal_status,
+            clipboard::get_clipboard_image,
+            export::export_message,
+            export::export_code_block,
+            tts::list_tts_voices,
+            models::set_model_favorite,
+            models::list_favorite_models,
+            network::get_network_status,
+            messages::normalize_messages,
+            conversations::conversation_fingerprint,
+            conversations::find_conversation_by_fingerprint,
+            conversations::summarize_conversation,
+            app_dirs::open_app_data_dir,
+            app_dirs::open_app_log_dir,
+            wipe::wipe_all_data,
+            templates::save_template,
+            templates::list_templates,
+            templates::new_conversation_from_template,
+            secrets::import_keys_from_env,
+            provider_ping::ping_chat_provider,
+            summarize::tool_summarize_text,
+            drafts::save_draft,
+            drafts::get_draft,
+            drafts::clear_draft,
+            audio::list_audio_devices,
+            audio::set_audio_device,
+            report::export_compatibility_report,
+            model_files::inspect_model_file,
+            cpu::get_cpu_topology,
+            gpu::detect_compute_backends,
+            conversations::switch_conversation_model,
+            ollama::resolve_model_alias,
+            inference_servers::detect_llamacpp_server,
+            compare::compare_model_outputs,
+            keyboard::detect_keyboard_layout,
+            operations::list_active_operations,
+            operations::cancel_operation,
+            ollama::preload_model,
+            update_bundles::verify_update_bundle,
+            provider_ping::validate_all_keys,
+            file_types::classify_file,
+            db_integrity::check_database_integrity,
+            db_integrity::repair_database,
+            export::export_model_list,
+            retention::set_retention_policy,
+            retention::apply_retention_policy,
+            retention::restore_conversation,
+            ollama::test_tool_support,
+            ollama::get_recommended_params,
+            model_capabilities::model_capabilities,
+            ollama::diagnose_ollama_install,
+            benchmark::benchmark_models,
+            network::check_clock_skew,
+            webview::get_webview_info,
+            gpu::stream_gpu_memory,
+            gpu::stop_gpu_memory_stream,
+            ollama::is_ollama_managed_by_app,
+            param_presets::save_param_preset,
+            param_presets::list_param_presets,
+            param_presets::get_param_preset,
+            param_presets::delete_param_preset,
+            secrets::uses_dev_key_storage,
+            provider_format::convert_to_provider_format,
+            provider_format::convert_provider_response
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .setup(|app| {
+            retention::start_retention_schedule(app.handle().clone());
+            Ok(())
+        })
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                shutdown::run_shutdown(app);
+            }
+        });
 }
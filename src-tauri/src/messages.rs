@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageRole {
+    System,
+    User,
+    Assistant,
+    Tool,
+}
+
+// Content is either a plain string or an array of provider content parts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: MessageRole,
+    // Assistant turns that only call tools may send null or leave it out
+    #[serde(default)]
+    pub content: Value,
+    // Everything else (tool_calls, tool_call_id, name, ...) is passed through untouched
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl ChatMessage {
+    pub fn new(role: MessageRole, content: Value) -> Self {
+        ChatMessage { role, content, extra: Map::new() }
+    }
+
+    fn has_tool_calls(&self) -> bool {
+        self.extra
+            .get("tool_calls")
+            .and_then(Value::as_array)
+            .is_some_and(|calls| !calls.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProviderKind {
+    Anthropic,
+    OpenAiCompatible,
+}
+
+impl ProviderKind {
+    pub fn from_provider(provider: &str) -> Self {
+        let provider = provider.to_lowercase();
+        if provider.contains("anthropic") || provider.contains("claude") {
+            ProviderKind::Anthropic
+        } else {
+            ProviderKind::OpenAiCompatible
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NormalizedConversation {
+    // Set for providers that take the system prompt as a separate parameter
+    pub system: Option<String>,
+    pub messages: Vec<ChatMessage>,
+    pub transformations: Vec<String>,
+}
+
+// Placeholder used when a provider requires the conversation to open with a user turn
+const CONVERSATION_START_PLACEHOLDER: &str = "(continuing the conversation)";
+
+fn content_to_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| part.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn is_empty_content(content: &Value) -> bool {
+    match content {
+        Value::Null => true,
+        Value::String(text) => text.trim().is_empty(),
+        Value::Array(parts) => parts.is_empty(),
+        _ => false,
+    }
+}
+
+fn content_to_parts(content: Value) -> Vec<Value> {
+    match content {
+        Value::Array(parts) => parts,
+        Value::String(text) => vec![json!({ "type": "text", "text": text })],
+        Value::Null => Vec::new(),
+        other => vec![json!({ "type": "text", "text": other.to_string() })],
+    }
+}
+
+fn merge_content(existing: Value, next: Value) -> Value {
+    match (existing, next) {
+        (Value::String(a), Value::String(b)) => Value::String(format!("{}\n\n{}", a, b)),
+        (a, b) => {
+            let mut parts = content_to_parts(a);
+            parts.extend(content_to_parts(b));
+            Value::Array(parts)
+        }
+    }
+}
+
+// Anthropic has no tool role or tool_calls field: calls become tool_use blocks on the assistant
+// turn and results become tool_result blocks in a user turn
+fn to_anthropic_tool_blocks(message: &mut ChatMessage) -> bool {
+    match message.role {
+        MessageRole::Tool => {
+            let tool_use_id = message.extra.remove("tool_call_id").unwrap_or(Value::Null);
+            let result = content_to_text(&message.content);
+            message.role = MessageRole::User;
+            message.content = json!([{ "type": "tool_result", "tool_use_id": tool_use_id, "content": result }]);
+            true
+        }
+        MessageRole::Assistant if message.has_tool_calls() => {
+            let calls = message.extra.remove("tool_calls").unwrap_or(Value::Null);
+            let mut parts = content_to_parts(std::mem::take(&mut message.content));
+            for call in calls.as_array().into_iter().flatten() {
+                let function = call.get("function").unwrap_or(&Value::Null);
+                // Arguments arrive as a JSON-encoded string; keep them as a string if they don't parse
+                let input = match function.get("arguments") {
+                    Some(Value::String(raw)) => serde_json::from_str(raw).unwrap_or(Value::String(raw.clone())),
+                    Some(arguments) => arguments.clone(),
+                    None => json!({}),
+                };
+                parts.push(json!({
+                    "type": "tool_use",
+                    "id": call.get("id").cloned().unwrap_or(Value::Null),
+                    "name": function.get("name").cloned().unwrap_or(Value::Null),
+                    "input": input
+                }));
+            }
+            message.content = Value::Array(parts);
+            true
+        }
+        _ => false,
+    }
+}
+
+pub fn normalize_conversation(
+    messages: Vec<ChatMessage>,
+    provider: ProviderKind,
+) -> Result<NormalizedConversation, String> {
+    let mut transformations = Vec::new();
+
+    // Pull every system message out so it can be placed where the provider expects it
+    let mut system_parts = Vec::new();
+    let mut conversation = Vec::new();
+    for message in messages {
+        if message.role == MessageRole::System {
+            let text = content_to_text(&message.content);
+            if !text.trim().is_empty() {
+                system_parts.push(text);
+            }
+        } else {
+            conversation.push(message);
+        }
+    }
+
+    if system_parts.len() > 1 {
+        transformations.push(format!("Merged {} system messages into one", system_parts.len()));
+    }
+    let system = if system_parts.is_empty() {
+        None
+    } else {
+        Some(system_parts.join("\n\n"))
+    };
+
+    // Drop empty messages, which most providers reject outright; tool calls and results carry
+    // their payload outside the content, so they always stay
+    let before = conversation.len();
+    conversation.retain(|message| {
+        !is_empty_content(&message.content) || message.has_tool_calls() || message.role == MessageRole::Tool
+    });
+    let removed = before - conversation.len();
+    if removed > 0 {
+        transformations.push(format!("Removed {} empty message(s)", removed));
+    }
+
+    if provider == ProviderKind::Anthropic {
+        let converted = conversation.iter_mut().map(to_anthropic_tool_blocks).filter(|converted| *converted).count();
+        if converted > 0 {
+            transformations.push(format!("Converted {} tool call/result message(s) to Anthropic content blocks", converted));
+        }
+        // Anthropic messages only take role and content
+        for message in &mut conversation {
+            message.extra.clear();
+        }
+    }
+
+    // Merge consecutive same-role messages. Tool results stay separate since each answers a call,
+    // and messages with tool_calls keep their own calls
+    let mut merged: Vec<ChatMessage> = Vec::with_capacity(conversation.len());
+    let mut merge_count = 0;
+    for message in conversation {
+        match merged.last_mut() {
+            Some(last)
+                if last.role == message.role
+                    && message.role != MessageRole::Tool
+                    && !last.has_tool_calls()
+                    && !message.has_tool_calls() =>
+            {
+                let existing = std::mem::replace(&mut last.content, Value::Null);
+                last.content = merge_content(existing, message.content);
+                merge_count += 1;
+            }
+            _ => merged.push(message),
+        }
+    }
+    if merge_count > 0 {
+        transformations.push(format!("Merged {} consecutive same-role message(s)", merge_count));
+    }
+
+    if !merged.iter().any(|message| message.role == MessageRole::User) {
+        return Err("Conversation has no user message to respond to".to_string());
+    }
+
+    match provider {
+        ProviderKind::Anthropic => {
+            // Anthropic requires the first message to be from the user
+            if merged.first().map(|message| message.role) != Some(MessageRole::User) {
+                merged.insert(0, ChatMessage::new(
+                    MessageRole::User,
+                    Value::String(CONVERSATION_START_PLACEHOLDER.to_string()),
+                ));
+                transformations.push("Inserted a user message so the conversation starts with the user".to_string());
+            }
+
+            if system.is_some() {
+                transformations.push("Moved system prompt to the top-level system parameter".to_string());
+            }
+
+            Ok(NormalizedConversation {
+                system,
+                messages: merged,
+                transformations,
+            })
+        }
+        ProviderKind::OpenAiCompatible => {
+            // OpenAI-style APIs take a single system message at the start of the list
+            if let Some(system) = system {
+                merged.insert(0, ChatMessage::new(MessageRole::System, Value::String(system)));
+            }
+
+            Ok(NormalizedConversation {
+                system: None,
+                messages: merged,
+                transformations,
+            })
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn normalize_messages(
+    messages: Vec<ChatMessage>,
+    provider: String,
+) -> Result<NormalizedConversation, String> {
+    normalize_conversation(messages, ProviderKind::from_provider(&provider))
+}
@@ -0,0 +1,288 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::model_files::inspect_model_path;
+use crate::models::ModelRef;
+use crate::ollama::show_ollama_model;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CapabilitySource {
+    // Reported by Ollama's /api/show
+    Ollama,
+    // Read from the model file's GGUF metadata, with name heuristics for the feature flags
+    FileMetadata,
+    // Guessed from the model name alone
+    Heuristic,
+    // Matched against OpenRouter's public model list
+    OpenRouter,
+    // Nothing is known about the model, so every feature is reported as unsupported
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelCapabilities {
+    pub vision: bool,
+    pub tools: bool,
+    pub json_mode: bool,
+    pub reasoning: bool,
+    pub audio_input: bool,
+    pub context_length: Option<u64>,
+    pub max_output_tokens: Option<u64>,
+    pub source: CapabilitySource,
+}
+
+impl ModelCapabilities {
+    fn unknown() -> Self {
+        ModelCapabilities {
+            vision: false,
+            tools: false,
+            json_mode: false,
+            reasoning: false,
+            audio_input: false,
+            context_length: None,
+            max_output_tokens: None,
+            source: CapabilitySource::Unknown,
+        }
+    }
+}
+
+// Same source and lifetime as the frontend's metadata cache in modelsService.ts
+const OPENROUTER_MODELS_URL: &str = "https://openrouter.ai/api/v1/models";
+const OPENROUTER_CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+const OPENROUTER_PROVIDER_PREFIXES: &[&str] = &["openai/", "anthropic/", "meta-llama/", "google/", "mistralai/"];
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct OpenRouterArchitecture {
+    #[serde(default)]
+    input_modalities: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct OpenRouterTopProvider {
+    max_completion_tokens: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct OpenRouterModel {
+    id: String,
+    #[serde(default)]
+    architecture: OpenRouterArchitecture,
+    context_length: Option<u64>,
+    #[serde(default)]
+    top_provider: OpenRouterTopProvider,
+    #[serde(default)]
+    supported_parameters: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct OpenRouterModelList {
+    data: Vec<OpenRouterModel>,
+}
+
+static OPENROUTER_CACHE: Mutex<Option<(Instant, Vec<OpenRouterModel>)>> = Mutex::new(None);
+
+// Name fragments of well-known model families with each feature
+const VISION_NAME_HINTS: &[&str] = &["vision", "llava", "bakllava", "-vl", "2.5vl", "moondream", "minicpm-v", "pixtral", "gemma3"];
+const TOOLS_NAME_HINTS: &[&str] = &["llama3.1", "llama3.2", "llama3.3", "qwen2.5", "qwen3", "mistral", "mixtral", "command-r", "hermes", "firefunction", "granite3", "gpt-oss"];
+const REASONING_NAME_HINTS: &[&str] = &["deepseek-r1", "qwq", "qwen3", "gpt-oss", "magistral", "phi4-reasoning", "thinking"];
+const AUDIO_NAME_HINTS: &[&str] = &["qwen2-audio", "qwen2.5-omni", "voxtral", "gemma3n"];
+
+fn name_has(name: &str, hints: &[&str]) -> bool {
+    let name = name.to_lowercase();
+    hints.iter().any(|hint| name.contains(hint))
+}
+
+fn from_name(name: &str) -> ModelCapabilities {
+    ModelCapabilities {
+        vision: name_has(name, VISION_NAME_HINTS),
+        tools: name_has(name, TOOLS_NAME_HINTS),
+        // Local runtimes constrain output to JSON with a grammar, so any local model can do it
+        json_mode: true,
+        reasoning: name_has(name, REASONING_NAME_HINTS),
+        audio_input: name_has(name, AUDIO_NAME_HINTS),
+        context_length: None,
+        max_output_tokens: None,
+        source: CapabilitySource::Heuristic,
+    }
+}
+
+// Ollama 0.6.4+ lists capabilities such as "vision", "tools" and "thinking" in /api/show
+async fn from_ollama(name: &str) -> Option<ModelCapabilities> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .ok()?;
+    let show = show_ollama_model(&client, name).await.ok()?;
+
+    let mut capabilities = from_name(name);
+    if let Some(reported) = show.get("capabilities").and_then(|reported| reported.as_array()) {
+        let has = |capability: &str| reported.iter().any(|reported| reported.as_str() == Some(capability));
+        capabilities.vision = has("vision");
+        capabilities.tools = has("tools");
+        capabilities.reasoning = has("thinking");
+        capabilities.audio_input = has("audio");
+        capabilities.source = CapabilitySource::Ollama;
+    }
+    capabilities.context_length = show
+        .get("model_info")
+        .and_then(|info| info.as_object())
+        .and_then(|info| {
+            info.iter()
+                .find(|(key, _)| key.ends_with(".context_length"))
+                .and_then(|(_, value)| value.as_u64())
+        });
+    Some(capabilities)
+}
+
+fn from_file(name: &str, path: &Path) -> Option<ModelCapabilities> {
+    let inspection = inspect_model_path(path).ok()?;
+    if inspection.format != "GGUF" {
+        return None;
+    }
+
+    let mut capabilities = from_name(name);
+    let metadata = &inspection.metadata;
+    capabilities.context_length = metadata
+        .get("general.architecture")
+        .and_then(|architecture| architecture.as_str())
+        .and_then(|architecture| metadata.get(&format!("{}.context_length", architecture)))
+        .and_then(|value| value.as_u64());
+
+    // Only the start of the chat template is kept, so it can confirm tool support but not rule it out
+    if let Some(template) = metadata.get("tokenizer.chat_template").and_then(|template| template.as_str()) {
+        capabilities.tools |= template.contains("tools");
+        capabilities.reasoning |= template.contains("<think>");
+    }
+    // Projector weights for images live in the same file for some multimodal GGUFs
+    capabilities.vision |= metadata.keys().any(|key| key.starts_with("clip.vision"));
+    capabilities.audio_input |= metadata.keys().any(|key| key.starts_with("clip.audio"));
+    capabilities.source = CapabilitySource::FileMetadata;
+    Some(capabilities)
+}
+
+async fn fetch_openrouter_models() -> Result<Vec<OpenRouterModel>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let response = client
+        .get(OPENROUTER_MODELS_URL)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch OpenRouter models: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("OpenRouter API error: {}", response.status()));
+    }
+
+    let list: OpenRouterModelList = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse OpenRouter models: {}", e))?;
+    Ok(list.data)
+}
+
+async fn openrouter_models() -> Vec<OpenRouterModel> {
+    if let Ok(cache) = OPENROUTER_CACHE.lock() {
+        if let Some((fetched, models)) = cache.as_ref() {
+            if fetched.elapsed() < OPENROUTER_CACHE_TTL {
+                return models.clone();
+            }
+        }
+    }
+
+    match fetch_openrouter_models().await {
+        Ok(models) => {
+            if let Ok(mut cache) = OPENROUTER_CACHE.lock() {
+                *cache = Some((Instant::now(), models.clone()));
+            }
+            models
+        }
+        Err(e) => {
+            // Keep using stale metadata rather than none at all
+            eprintln!("Warning: {}", e);
+            OPENROUTER_CACHE
+                .lock()
+                .ok()
+                .and_then(|cache| cache.as_ref().map(|(_, models)| models.clone()))
+                .unwrap_or_default()
+        }
+    }
+}
+
+// Provider model ids usually lack OpenRouter's "vendor/" prefix
+fn find_openrouter_match<'a>(models: &'a [OpenRouterModel], provider: &str, id: &str) -> Option<&'a OpenRouterModel> {
+    let id = id.to_lowercase();
+    let provider_prefixed = format!("{}/{}", provider.to_lowercase(), id);
+
+    models
+        .iter()
+        .find(|model| model.id.to_lowercase() == id)
+        .or_else(|| models.iter().find(|model| model.id.to_lowercase() == provider_prefixed))
+        .or_else(|| {
+            OPENROUTER_PROVIDER_PREFIXES.iter().find_map(|prefix| {
+                let prefixed = format!("{}{}", prefix, id);
+                models.iter().find(|model| model.id.to_lowercase() == prefixed)
+            })
+        })
+        .or_else(|| {
+            models.iter().find(|model| {
+                let model_id = model.id.to_lowercase();
+                model_id.split_once('/').map(|(_, name)| name == id).unwrap_or(false)
+            })
+        })
+}
+
+fn from_openrouter(model: &OpenRouterModel) -> ModelCapabilities {
+    let has_input = |modality: &str| model.architecture.input_modalities.iter().any(|input| input == modality);
+    let supports = |parameter: &str| model.supported_parameters.iter().any(|supported| supported == parameter);
+
+    ModelCapabilities {
+        vision: has_input("image"),
+        tools: supports("tools"),
+        json_mode: supports("response_format") || supports("structured_outputs"),
+        reasoning: supports("reasoning") || supports("include_reasoning"),
+        audio_input: has_input("audio"),
+        context_length: model.context_length,
+        max_output_tokens: model.top_provider.max_completion_tokens,
+        source: CapabilitySource::OpenRouter,
+    }
+}
+
+async fn from_cloud(provider: &str, id: &str) -> Option<ModelCapabilities> {
+    let models = openrouter_models().await;
+    find_openrouter_match(&models, provider, id).map(from_openrouter)
+}
+
+pub async fn resolve_capabilities(model_ref: &ModelRef) -> ModelCapabilities {
+    match model_ref {
+        ModelRef::Local { name, path } => {
+            if let Some(capabilities) = from_ollama(name).await {
+                return capabilities;
+            }
+            if let Some(path) = path.clone() {
+                let file_name = name.clone();
+                let from_metadata = tokio::task::spawn_blocking(move || from_file(&file_name, Path::new(&path)))
+                    .await
+                    .ok()
+                    .flatten();
+                if let Some(capabilities) = from_metadata {
+                    return capabilities;
+                }
+            }
+            from_name(name)
+        }
+        // Models OpenRouter doesn't list get the conservative defaults
+        ModelRef::Cloud { provider, id } => from_cloud(provider, id)
+            .await
+            .unwrap_or_else(ModelCapabilities::unknown),
+    }
+}
+
+#[tauri::command]
+pub async fn model_capabilities(model_ref: ModelRef) -> Result<ModelCapabilities, String> {
+    Ok(resolve_capabilities(&model_ref).await)
+}
@@ -0,0 +1,450 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek};
+use std::path::Path;
+
+const GGUF_MAGIC: &[u8; 4] = b"GGUF";
+const GGUF_DEFAULT_ALIGNMENT: u64 = 32;
+const GGML_MAX_DIMS: u32 = 4;
+// Real headers are a few MB at most; anything larger is a corrupt length prefix
+const MAX_SAFETENSORS_HEADER_BYTES: u64 = 100 * 1024 * 1024;
+// Chat templates and similar can be huge, so long metadata strings are cut short in the result
+const MAX_METADATA_STRING_CHARS: usize = 512;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelFileInspection {
+    pub path: String,
+    pub format: String,
+    pub size_bytes: u64,
+    pub valid: bool,
+    // GGUF format version; safetensors has none
+    pub version: Option<u32>,
+    pub tensor_count: u64,
+    pub metadata: BTreeMap<String, serde_json::Value>,
+    pub warnings: Vec<String>,
+}
+
+impl ModelFileInspection {
+    fn new(path: &Path, format: &str, size_bytes: u64) -> Self {
+        Self {
+            path: path.to_string_lossy().to_string(),
+            format: format.to_string(),
+            size_bytes,
+            valid: true,
+            version: None,
+            tensor_count: 0,
+            metadata: BTreeMap::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    fn invalid(&mut self, warning: String) {
+        self.valid = false;
+        self.warnings.push(warning);
+    }
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn describe_read_error(e: &io::Error, what: &str) -> String {
+    if e.kind() == io::ErrorKind::UnexpectedEof {
+        format!("File ends inside the {}; it is truncated or only partially downloaded", what)
+    } else {
+        format!("Invalid {}: {}", what, e)
+    }
+}
+
+// (elements per block, bytes per block) for ggml tensor types
+fn ggml_type_layout(ggml_type: u32) -> Option<(u64, u64)> {
+    Some(match ggml_type {
+        0 => (1, 4),     // F32
+        1 => (1, 2),     // F16
+        2 => (32, 18),   // Q4_0
+        3 => (32, 20),   // Q4_1
+        6 => (32, 22),   // Q5_0
+        7 => (32, 24),   // Q5_1
+        8 => (32, 34),   // Q8_0
+        9 => (32, 36),   // Q8_1
+        10 => (256, 84), // Q2_K
+        11 => (256, 110), // Q3_K
+        12 => (256, 144), // Q4_K
+        13 => (256, 176), // Q5_K
+        14 => (256, 210), // Q6_K
+        15 => (256, 292), // Q8_K
+        16 => (256, 66), // IQ2_XXS
+        17 => (256, 74), // IQ2_XS
+        18 => (256, 98), // IQ3_XXS
+        19 => (256, 50), // IQ1_S
+        20 => (32, 18),  // IQ4_NL
+        21 => (256, 110), // IQ3_S
+        22 => (256, 82), // IQ2_S
+        23 => (256, 136), // IQ4_XS
+        24 => (1, 1),    // I8
+        25 => (1, 2),    // I16
+        26 => (1, 4),    // I32
+        27 => (1, 8),    // I64
+        28 => (1, 8),    // F64
+        29 => (256, 56), // IQ1_M
+        30 => (1, 2),    // BF16
+        34 => (256, 54), // TQ1_0
+        35 => (256, 66), // TQ2_0
+        _ => return None,
+    })
+}
+
+// Size of fixed-width GGUF metadata value types; strings and arrays are variable
+fn gguf_scalar_size(value_type: u32) -> Option<u64> {
+    match value_type {
+        0 | 1 | 7 => Some(1),
+        2 | 3 => Some(2),
+        4..=6 => Some(4),
+        10..=12 => Some(8),
+        _ => None,
+    }
+}
+
+struct GgufReader {
+    reader: BufReader<File>,
+    file_len: u64,
+}
+
+impl GgufReader {
+    fn read_array<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut bytes = [0u8; N];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn read_u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    fn position(&mut self) -> io::Result<u64> {
+        self.reader.stream_position()
+    }
+
+    fn remaining(&mut self) -> io::Result<u64> {
+        Ok(self.file_len.saturating_sub(self.position()?))
+    }
+
+    // Lengths are checked against the file so a corrupt prefix can't trigger a huge allocation
+    fn skip(&mut self, bytes: u64) -> io::Result<()> {
+        if bytes > self.remaining()? {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        self.reader.seek_relative(bytes as i64)
+    }
+
+    fn read_string(&mut self) -> io::Result<String> {
+        let len = self.read_u64()?;
+        if len > self.remaining()? {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let mut bytes = vec![0u8; len as usize];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    fn read_value(&mut self, value_type: u32) -> io::Result<serde_json::Value> {
+        let value = match value_type {
+            0 => u8::from_le_bytes(self.read_array()?).into(),
+            1 => i8::from_le_bytes(self.read_array()?).into(),
+            2 => u16::from_le_bytes(self.read_array()?).into(),
+            3 => i16::from_le_bytes(self.read_array()?).into(),
+            4 => self.read_u32()?.into(),
+            5 => i32::from_le_bytes(self.read_array()?).into(),
+            6 => f32::from_le_bytes(self.read_array()?).into(),
+            7 => (self.read_array::<1>()?[0] != 0).into(),
+            8 => {
+                let text = self.read_string()?;
+                if text.chars().count() > MAX_METADATA_STRING_CHARS {
+                    format!("{}…", text.chars().take(MAX_METADATA_STRING_CHARS).collect::<String>()).into()
+                } else {
+                    text.into()
+                }
+            }
+            9 => {
+                // Arrays are mostly tokenizer vocabularies, so only their length is reported
+                let element_type = self.read_u32()?;
+                let count = self.read_u64()?;
+                match gguf_scalar_size(element_type) {
+                    Some(size) => self.skip(count.checked_mul(size).ok_or_else(|| invalid_data("array size overflows"))?)?,
+                    None => {
+                        for _ in 0..count {
+                            self.read_value(element_type)?;
+                        }
+                    }
+                }
+                serde_json::json!({ "array_length": count })
+            }
+            10 => self.read_u64()?.into(),
+            11 => i64::from_le_bytes(self.read_array()?).into(),
+            12 => f64::from_le_bytes(self.read_array()?).into(),
+            other => return Err(invalid_data(format!("unknown metadata value type {}", other))),
+        };
+        Ok(value)
+    }
+}
+
+fn inspect_gguf(file: File, inspection: &mut ModelFileInspection) {
+    let mut gguf = GgufReader {
+        reader: BufReader::new(file),
+        file_len: inspection.size_bytes,
+    };
+
+    let header = (|| -> io::Result<(u32, u64, u64)> {
+        let magic = gguf.read_array::<4>()?;
+        if &magic != GGUF_MAGIC {
+            return Err(invalid_data("missing GGUF magic bytes"));
+        }
+        let version = gguf.read_u32()?;
+        if !(2..=3).contains(&version) {
+            return Err(invalid_data(format!("unsupported GGUF version {}", version)));
+        }
+        Ok((version, gguf.read_u64()?, gguf.read_u64()?))
+    })();
+    let (version, tensor_count, metadata_count) = match header {
+        Ok(header) => header,
+        Err(e) => return inspection.invalid(describe_read_error(&e, "GGUF header")),
+    };
+    inspection.version = Some(version);
+    inspection.tensor_count = tensor_count;
+
+    for _ in 0..metadata_count {
+        let entry = gguf.read_string().and_then(|key| {
+            let value_type = gguf.read_u32()?;
+            Ok((key, gguf.read_value(value_type)?))
+        });
+        match entry {
+            Ok((key, value)) => {
+                inspection.metadata.insert(key, value);
+            }
+            Err(e) => return inspection.invalid(describe_read_error(&e, "GGUF metadata")),
+        }
+    }
+
+    let alignment = inspection
+        .metadata
+        .get("general.alignment")
+        .and_then(|alignment| alignment.as_u64())
+        .filter(|alignment| *alignment > 0)
+        .unwrap_or(GGUF_DEFAULT_ALIGNMENT);
+
+    // (name, ggml type, element count, offset relative to the data section)
+    let mut tensors = Vec::new();
+    for _ in 0..tensor_count {
+        let tensor = (|| -> io::Result<(String, u32, Option<u64>, u64)> {
+            let name = gguf.read_string()?;
+            let dimensions = gguf.read_u32()?;
+            if dimensions > GGML_MAX_DIMS {
+                return Err(invalid_data(format!("tensor {} has {} dimensions", name, dimensions)));
+            }
+            let mut elements = Some(1u64);
+            for _ in 0..dimensions {
+                let dimension = gguf.read_u64()?;
+                elements = elements.and_then(|elements| elements.checked_mul(dimension));
+            }
+            let ggml_type = gguf.read_u32()?;
+            let offset = gguf.read_u64()?;
+            Ok((name, ggml_type, elements, offset))
+        })();
+        match tensor {
+            Ok(tensor) => tensors.push(tensor),
+            Err(e) => return inspection.invalid(describe_read_error(&e, "GGUF tensor table")),
+        }
+    }
+
+    let data_start = match gguf.position() {
+        Ok(position) => position.div_ceil(alignment) * alignment,
+        Err(e) => return inspection.invalid(format!("Failed to read GGUF file: {}", e)),
+    };
+
+    let mut out_of_range = Vec::new();
+    let mut unknown_types = Vec::new();
+    for (name, ggml_type, elements, offset) in &tensors {
+        if offset % alignment != 0 {
+            inspection.invalid(format!("Tensor {} is not aligned to {} bytes", name, alignment));
+        }
+
+        let Some((block_size, block_bytes)) = ggml_type_layout(*ggml_type) else {
+            unknown_types.push(*ggml_type);
+            if data_start.saturating_add(*offset) > inspection.size_bytes {
+                out_of_range.push(name.clone());
+            }
+            continue;
+        };
+
+        let end = elements
+            .map(|elements| elements.div_ceil(block_size).saturating_mul(block_bytes))
+            .map(|size| data_start.saturating_add(*offset).saturating_add(size));
+        if end.is_none_or(|end| end > inspection.size_bytes) {
+            out_of_range.push(name.clone());
+        }
+    }
+
+    if let Some(first) = out_of_range.first() {
+        inspection.invalid(format!(
+            "{} of {} tensors extend past the end of the file (first: {}); the file is truncated or corrupt",
+            out_of_range.len(),
+            tensors.len(),
+            first
+        ));
+    }
+
+    if !unknown_types.is_empty() {
+        unknown_types.sort_unstable();
+        unknown_types.dedup();
+        inspection.warnings.push(format!(
+            "Unrecognized tensor types {:?}; their sizes could not be checked",
+            unknown_types
+        ));
+    }
+}
+
+// Bytes per element for safetensors dtypes
+fn safetensors_dtype_size(dtype: &str) -> Option<u64> {
+    match dtype {
+        "BOOL" | "U8" | "I8" | "F8_E4M3" | "F8_E5M2" => Some(1),
+        "U16" | "I16" | "F16" | "BF16" => Some(2),
+        "U32" | "I32" | "F32" => Some(4),
+        "U64" | "I64" | "F64" => Some(8),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SafetensorsTensor {
+    dtype: String,
+    shape: Vec<u64>,
+    data_offsets: (u64, u64),
+}
+
+fn inspect_safetensors(mut file: File, inspection: &mut ModelFileInspection) {
+    let mut length_prefix = [0u8; 8];
+    if let Err(e) = file.read_exact(&mut length_prefix) {
+        return inspection.invalid(describe_read_error(&e, "safetensors header length"));
+    }
+    let header_len = u64::from_le_bytes(length_prefix);
+
+    if header_len > MAX_SAFETENSORS_HEADER_BYTES {
+        return inspection.invalid(format!("Header length {} is implausibly large; the file is corrupt", header_len));
+    }
+    if 8 + header_len > inspection.size_bytes {
+        return inspection.invalid(format!(
+            "Header claims {} bytes but the file is only {} bytes; it is truncated or only partially downloaded",
+            header_len, inspection.size_bytes
+        ));
+    }
+
+    let mut header_bytes = vec![0u8; header_len as usize];
+    if let Err(e) = file.read_exact(&mut header_bytes) {
+        return inspection.invalid(describe_read_error(&e, "safetensors header"));
+    }
+    let header: BTreeMap<String, serde_json::Value> = match serde_json::from_slice(&header_bytes) {
+        Ok(header) => header,
+        Err(e) => return inspection.invalid(format!("Header is not valid JSON: {}", e)),
+    };
+
+    let data_len = inspection.size_bytes - 8 - header_len;
+    let mut out_of_range = Vec::new();
+
+    for (name, value) in header {
+        if name == "__metadata__" {
+            if let serde_json::Value::Object(metadata) = value {
+                inspection.metadata.extend(metadata);
+            }
+            continue;
+        }
+
+        inspection.tensor_count += 1;
+        let tensor: SafetensorsTensor = match serde_json::from_value(value) {
+            Ok(tensor) => tensor,
+            Err(e) => {
+                inspection.invalid(format!("Tensor {} has an invalid header entry: {}", name, e));
+                continue;
+            }
+        };
+
+        let (start, end) = tensor.data_offsets;
+        if start > end {
+            inspection.invalid(format!("Tensor {} has a negative byte range", name));
+            continue;
+        }
+        if end > data_len {
+            out_of_range.push(name);
+            continue;
+        }
+
+        match safetensors_dtype_size(&tensor.dtype) {
+            Some(dtype_size) => {
+                let expected = tensor
+                    .shape
+                    .iter()
+                    .try_fold(dtype_size, |size, dimension| size.checked_mul(*dimension));
+                if expected != Some(end - start) {
+                    inspection.invalid(format!(
+                        "Tensor {} spans {} bytes but its shape and dtype need {}",
+                        name,
+                        end - start,
+                        expected.map(|bytes| bytes.to_string()).unwrap_or_else(|| "more than fits in 64 bits".to_string())
+                    ));
+                }
+            }
+            None => inspection.warnings.push(format!("Tensor {} has unrecognized dtype {}", name, tensor.dtype)),
+        }
+    }
+
+    if let Some(first) = out_of_range.first() {
+        inspection.invalid(format!(
+            "{} of {} tensors extend past the end of the file (first: {}); the file is truncated or corrupt",
+            out_of_range.len(),
+            inspection.tensor_count,
+            first
+        ));
+    }
+}
+
+pub fn inspect_model_path(path: &Path) -> Result<ModelFileInspection, String> {
+    let mut file = File::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let size_bytes = file.metadata()
+        .map_err(|e| format!("Failed to read metadata for {}: {}", path.display(), e))?
+        .len();
+
+    // Sniff the magic first so GGUF files are recognized regardless of their name
+    let mut magic = [0u8; 4];
+    let has_gguf_magic = file.read_exact(&mut magic).is_ok() && &magic == GGUF_MAGIC;
+    file.rewind()
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    if has_gguf_magic || extension == "gguf" {
+        let mut inspection = ModelFileInspection::new(path, "GGUF", size_bytes);
+        inspect_gguf(file, &mut inspection);
+        Ok(inspection)
+    } else if extension == "safetensors" {
+        let mut inspection = ModelFileInspection::new(path, "SafeTensors", size_bytes);
+        inspect_safetensors(file, &mut inspection);
+        Ok(inspection)
+    } else {
+        Err(format!("{} is not a GGUF or safetensors file", path.display()))
+    }
+}
+
+#[tauri::command]
+pub async fn inspect_model_file(path: String) -> Result<ModelFileInspection, String> {
+    tokio::task::spawn_blocking(move || inspect_model_path(Path::new(&path)))
+        .await
+        .map_err(|e| format!("Model file inspection failed: {}", e))?
+}
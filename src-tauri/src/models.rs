@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::settings::{get_setting, set_setting};
+
+const FAVORITE_MODELS_KEY: &str = "favoriteModels";
+
+// Identifies a model regardless of where it runs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ModelRef {
+    Local { name: String, path: Option<String> },
+    Cloud { provider: String, id: String },
+}
+
+impl ModelRef {
+    pub fn matches(&self, other: &ModelRef) -> bool {
+        match (self, other) {
+            // Local models are matched by path when both sides know it, otherwise by name
+            (
+                ModelRef::Local { path: Some(path), .. },
+                ModelRef::Local { path: Some(other_path), .. },
+            ) => path == other_path,
+            (ModelRef::Local { name, .. }, ModelRef::Local { name: other_name, .. }) => name == other_name,
+            (
+                ModelRef::Cloud { provider, id },
+                ModelRef::Cloud { provider: other_provider, id: other_id },
+            ) => provider == other_provider && id == other_id,
+            _ => false,
+        }
+    }
+}
+
+pub fn get_favorite_models(app: &AppHandle) -> Result<Vec<ModelRef>, String> {
+    Ok(get_setting(app, FAVORITE_MODELS_KEY)?.unwrap_or_default())
+}
+
+pub fn is_favorite(favorites: &[ModelRef], model: &ModelRef) -> bool {
+    favorites.iter().any(|favorite| favorite.matches(model))
+}
+
+#[tauri::command]
+pub async fn set_model_favorite(
+    app: AppHandle,
+    model_ref: ModelRef,
+    favorite: bool,
+) -> Result<Vec<ModelRef>, String> {
+    let mut favorites = get_favorite_models(&app)?;
+    favorites.retain(|existing| !existing.matches(&model_ref));
+
+    if favorite {
+        favorites.push(model_ref);
+    }
+
+    set_setting(&app, FAVORITE_MODELS_KEY, &favorites)?;
+    Ok(favorites)
+}
+
+#[tauri::command]
+pub async fn list_favorite_models(app: AppHandle) -> Result<Vec<ModelRef>, String> {
+    get_favorite_models(&app)
+}
@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant, SystemTime};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum InterfaceType {
+    Wifi,
+    Ethernet,
+    Cellular,
+    Other,
+    Unknown,
+}
+
+// Well-run HTTPS endpoints whose Date headers track real time closely
+const TIME_SOURCES: &[&str] = &["https://www.cloudflare.com", "https://www.google.com", "https://www.apple.com"];
+// Signed API requests and TLS start failing around here; beyond it auth errors are likely the clock's fault
+const CLOCK_SKEW_WARNING_SECONDS: f64 = 30.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockSkew {
+    // False when no time source could be reached, in which case nothing is known about the clock
+    pub checked: bool,
+    // Positive when the local clock is ahead of the time source
+    pub offset_seconds: Option<f64>,
+    pub source: Option<String>,
+    pub warning: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkStatus {
+    pub online: bool,
+    pub interface_type: InterfaceType,
+    pub interface_name: Option<String>,
+    // None when the platform doesn't tell us whether the connection is metered
+    pub is_metered: Option<bool>,
+}
+
+async fn check_online() -> bool {
+    // A TCP handshake with a well-known anycast resolver avoids depending on DNS or HTTP
+    let targets = ["1.1.1.1:443", "8.8.8.8:443"];
+
+    for target in targets {
+        let attempt = tokio::time::timeout(Duration::from_secs(3), tokio::net::TcpStream::connect(target)).await;
+        if let Ok(Ok(_)) = attempt {
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(target_os = "macos")]
+fn detect_interface() -> (InterfaceType, Option<String>, Option<bool>) {
+    use std::process::Command;
+
+    // "route -n get default" prints a line like "interface: en0"
+    let interface_name = Command::new("route")
+        .args(["-n", "get", "default"])
+        .output()
+        .ok()
+        .and_then(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("interface:").map(|name| name.trim().to_string()))
+        });
+
+    let Some(interface_name) = interface_name else {
+        return (InterfaceType::Unknown, None, None);
+    };
+
+    // Map the BSD device name back to its hardware port ("Wi-Fi", "Ethernet", "iPhone USB", ...)
+    let hardware_port = Command::new("networksetup")
+        .arg("-listallhardwareports")
+        .output()
+        .ok()
+        .and_then(|output| {
+            let listing = String::from_utf8_lossy(&output.stdout).to_string();
+            let mut current_port: Option<String> = None;
+            for line in listing.lines() {
+                if let Some(port) = line.strip_prefix("Hardware Port:") {
+                    current_port = Some(port.trim().to_string());
+                } else if let Some(device) = line.strip_prefix("Device:") {
+                    if device.trim() == interface_name {
+                        return current_port;
+                    }
+                }
+            }
+            None
+        });
+
+    let interface_type = match hardware_port.as_deref().map(str::to_lowercase) {
+        Some(port) if port.contains("wi-fi") || port.contains("airport") => InterfaceType::Wifi,
+        Some(port) if port.contains("iphone") || port.contains("ipad") => InterfaceType::Cellular,
+        Some(port) if port.contains("ethernet") || port.contains("thunderbolt") || port.contains("lan") => InterfaceType::Ethernet,
+        Some(_) => InterfaceType::Other,
+        None => InterfaceType::Unknown,
+    };
+
+    // macOS only exposes "Low Data Mode" through private APIs; treat tethering as metered
+    let is_metered = match interface_type {
+        InterfaceType::Cellular => Some(true),
+        _ => None,
+    };
+
+    (interface_type, Some(interface_name), is_metered)
+}
+
+#[cfg(target_os = "windows")]
+fn detect_interface() -> (InterfaceType, Option<String>, Option<bool>) {
+    use std::process::Command;
+
+    // The WinRT connection profile knows both the link type and the cost type
+    let script = "$p = [Windows.Networking.Connectivity.NetworkInformation,Windows.Networking.Connectivity,ContentType=WindowsRuntime]::GetInternetConnectionProfile(); \
+        if ($p) { '{0}|{1}|{2}|{3}' -f $p.IsWlanConnectionProfile, $p.IsWwanConnectionProfile, $p.GetConnectionCost().NetworkCostType, $p.ProfileName }";
+
+    let output = match Command::new("powershell").args(["-NoProfile", "-Command", script]).output() {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        _ => return (InterfaceType::Unknown, None, None),
+    };
+
+    let fields: Vec<&str> = output.split('|').collect();
+    if fields.len() < 4 {
+        return (InterfaceType::Unknown, None, None);
+    }
+
+    let interface_type = if fields[0].eq_ignore_ascii_case("true") {
+        InterfaceType::Wifi
+    } else if fields[1].eq_ignore_ascii_case("true") {
+        InterfaceType::Cellular
+    } else {
+        InterfaceType::Ethernet
+    };
+
+    // NetworkCostType is Unrestricted, Fixed, Variable, or Unknown
+    let is_metered = match fields[2].to_lowercase().as_str() {
+        "unrestricted" => Some(false),
+        "fixed" | "variable" => Some(true),
+        _ => None,
+    };
+
+    (interface_type, Some(fields[3].to_string()).filter(|name| !name.is_empty()), is_metered)
+}
+
+#[cfg(target_os = "linux")]
+fn detect_interface() -> (InterfaceType, Option<String>, Option<bool>) {
+    use std::process::Command;
+
+    // "ip route show default" prints e.g. "default via 192.168.1.1 dev wlp2s0 proto dhcp ..."
+    let interface_name = Command::new("ip")
+        .args(["route", "show", "default"])
+        .output()
+        .ok()
+        .and_then(|output| {
+            let routes = String::from_utf8_lossy(&output.stdout).to_string();
+            let fields: Vec<&str> = routes.split_whitespace().collect();
+            fields.iter().position(|field| *field == "dev").and_then(|i| fields.get(i + 1)).map(|name| name.to_string())
+        });
+
+    let Some(interface_name) = interface_name else {
+        return (InterfaceType::Unknown, None, None);
+    };
+
+    let sys_path = std::path::Path::new("/sys/class/net").join(&interface_name);
+    let interface_type = if sys_path.join("wireless").exists() || sys_path.join("phy80211").exists() {
+        InterfaceType::Wifi
+    } else if interface_name.starts_with("ww") || interface_name.starts_with("usb") || interface_name.starts_with("rmnet") {
+        InterfaceType::Cellular
+    } else if interface_name.starts_with("en") || interface_name.starts_with("eth") {
+        InterfaceType::Ethernet
+    } else {
+        InterfaceType::Other
+    };
+
+    // NetworkManager tracks metered state ("yes", "no", "yes (guessed)", "unknown")
+    let is_metered = Command::new("nmcli")
+        .args(["-t", "-f", "GENERAL.METERED", "dev", "show", &interface_name])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| {
+            let value = String::from_utf8_lossy(&output.stdout).to_lowercase();
+            let value = value.trim().trim_start_matches("general.metered:");
+            if value.starts_with("yes") {
+                Some(true)
+            } else if value.starts_with("no") {
+                Some(false)
+            } else {
+                None
+            }
+        });
+
+    (interface_type, Some(interface_name), is_metered)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn detect_interface() -> (InterfaceType, Option<String>, Option<bool>) {
+    (InterfaceType::Unknown, None, None)
+}
+
+pub async fn get_network_status_info() -> NetworkStatus {
+    let (online, interface) = tokio::join!(check_online(), tokio::task::spawn_blocking(detect_interface));
+    let (interface_type, interface_name, is_metered) = interface.unwrap_or((InterfaceType::Unknown, None, None));
+
+    NetworkStatus {
+        online,
+        interface_type,
+        interface_name,
+        is_metered,
+    }
+}
+
+// Date headers only have one-second resolution, so the offset is accurate to about a second
+async fn query_time_source(client: &reqwest::Client, url: &str) -> Option<f64> {
+    let sent = SystemTime::now();
+    let started = Instant::now();
+    let response = client.head(url).send().await.ok()?;
+    let round_trip = started.elapsed();
+
+    let date = response.headers().get(reqwest::header::DATE)?.to_str().ok()?;
+    let server_time = httpdate::parse_http_date(date).ok()?;
+    // Assume the server stamped the response halfway through the round trip
+    let local_time = sent + round_trip / 2;
+    Some(match local_time.duration_since(server_time) {
+        Ok(ahead) => ahead.as_secs_f64(),
+        Err(behind) => -behind.duration().as_secs_f64(),
+    })
+}
+
+pub async fn measure_clock_skew() -> ClockSkew {
+    let Ok(client) = reqwest::Client::builder().timeout(Duration::from_secs(5)).build() else {
+        return ClockSkew { checked: false, offset_seconds: None, source: None, warning: None };
+    };
+
+    for source in TIME_SOURCES {
+        let Some(offset) = query_time_source(&client, source).await else {
+            continue;
+        };
+        let warning = (offset.abs() > CLOCK_SKEW_WARNING_SECONDS).then(|| {
+            format!(
+                "Your system clock is {:.0} seconds {}. Providers can reject requests from a clock this far off, which looks like an invalid API key; turn on automatic time in your system settings.",
+                offset.abs(),
+                if offset > 0.0 { "fast" } else { "slow" }
+            )
+        });
+        return ClockSkew {
+            checked: true,
+            offset_seconds: Some(offset),
+            source: Some(source.to_string()),
+            warning,
+        };
+    }
+
+    ClockSkew { checked: false, offset_seconds: None, source: None, warning: None }
+}
+
+#[tauri::command]
+pub async fn check_clock_skew() -> ClockSkew {
+    measure_clock_skew().await
+}
+
+#[tauri::command]
+pub async fn get_network_status() -> NetworkStatus {
+    get_network_status_info().await
+}
@@ -1,8 +1,49 @@
 use serde::{Deserialize, Serialize};
-use std::process::Command;
-use std::time::Duration;
-use std::path::Path;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::process::{Child, Command};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use std::path::{Path, PathBuf};
 use std::fs;
+use tauri::{AppHandle, Emitter};
+
+use crate::models::{get_favorite_models, is_favorite, ModelRef};
+use crate::operations::{self, OperationKind};
+use crate::param_presets::preset_options;
+use crate::settings::{get_setting, set_setting};
+use crate::system_info::{estimate_model_memory_requirements, get_system_resources};
+
+pub const OLLAMA_BASE_URL: &str = "http://localhost:11434";
+
+// How long Ollama keeps a model resident after a request (Ollama's own default is "5m")
+const KEEP_ALIVE_KEY: &str = "ollamaKeepAlive";
+
+// Environment variables applied to the `ollama serve` process the app launches
+const ENV_OVERRIDES_KEY: &str = "ollamaEnvOverrides";
+
+// How long stop_ollama waits for the server to exit before killing it
+const OLLAMA_STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+// The `ollama serve` process we launched ourselves, so quitting never kills a user-started server
+static APP_STARTED_OLLAMA: Mutex<Option<Child>> = Mutex::new(None);
+
+// Overrides the running app-started server was launched with
+static ACTIVE_ENV_OVERRIDES: Mutex<Option<BTreeMap<String, String>>> = Mutex::new(None);
+
+// Per-model result of the tool-calling round trip test
+static TOOL_SUPPORT_CACHE: Mutex<BTreeMap<String, bool>> = Mutex::new(BTreeMap::new());
+
+// Cache of computed model hashes, keyed by path and validated against mtime + size
+static MODEL_HASH_CACHE: Mutex<BTreeMap<PathBuf, CachedModelHash>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone)]
+struct CachedModelHash {
+    modified: Option<SystemTime>,
+    size_bytes: u64,
+    sha256: String,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -59,17 +100,31 @@ pub async fn detect_ollama_installation() -> Result<OllamaDetectionResult, Strin
 }
 
 #[tauri::command]
-pub async fn start_ollama() -> Result<(), String> {
+pub async fn start_ollama(app: AppHandle) -> Result<(), String> {
     // Find the Ollama binary
     let binary_path = find_ollama_binary()
         .ok_or_else(|| "Ollama binary not found. Please install Ollama first.".to_string())?;
 
+    let env_overrides = get_setting::<BTreeMap<String, String>>(&app, ENV_OVERRIDES_KEY)
+        .unwrap_or_else(|e| {
+            eprintln!("Warning: {}", e);
+            None
+        })
+        .unwrap_or_default();
+
     // Try to start Ollama in serve mode
     match Command::new(&binary_path)
         .arg("serve")
+        .envs(&env_overrides)
         .spawn()
     {
-        Ok(_) => {
+        Ok(child) => {
+            if let Ok(mut started) = APP_STARTED_OLLAMA.lock() {
+                *started = Some(child);
+            }
+            if let Ok(mut active) = ACTIVE_ENV_OVERRIDES.lock() {
+                *active = Some(env_overrides);
+            }
             println!("Ollama start command issued");
             Ok(())
         }
@@ -79,73 +134,85 @@ pub async fn start_ollama() -> Result<(), String> {
     }
 }
 
+// Stop the server started by start_ollama, waiting up to `timeout` before killing it outright
+pub fn stop_app_started_ollama(timeout: Duration) {
+    let Some(mut child) = APP_STARTED_OLLAMA.lock().ok().and_then(|mut started| started.take()) else {
+        return;
+    };
+
+    // Already exited (e.g. the user stopped it some other way)
+    if let Ok(Some(_)) = child.try_wait() {
+        return;
+    }
+
+    // Ask politely first so Ollama can unload models and clean up its runners
+    #[cfg(unix)]
+    {
+        let _ = Command::new("kill").arg("-TERM").arg(child.id().to_string()).output();
+    }
+
+    let deadline = std::time::Instant::now() + timeout;
+    while std::time::Instant::now() < deadline {
+        match child.try_wait() {
+            Ok(Some(_)) => return,
+            Ok(None) => std::thread::sleep(Duration::from_millis(100)),
+            Err(_) => break,
+        }
+    }
+
+    if let Err(e) = child.kill() {
+        eprintln!("Warning: Failed to stop Ollama server: {}", e);
+    }
+    let _ = child.wait();
+}
+
+// True while the server from start_ollama is still running; a server that has exited is forgotten
+pub fn is_app_started_ollama_running() -> bool {
+    let Ok(mut started) = APP_STARTED_OLLAMA.lock() else {
+        return false;
+    };
+    let running = match started.as_mut() {
+        Some(child) => matches!(child.try_wait(), Ok(None)),
+        None => false,
+    };
+    if !running {
+        *started = None;
+    }
+    running
+}
+
 #[tauri::command]
 pub async fn stop_ollama() -> Result<(), String> {
-    // Try to stop Ollama gracefully by sending a request to shutdown
-    // This will automatically unload all models from memory as well
-    
+    // Stopping the server also unloads all models from memory
+
     // First check if Ollama is running
     if !test_ollama_api().await {
         return Ok(()); // Already stopped
     }
-    
-    // On Unix systems, we can try to find and stop the Ollama process
-    #[cfg(unix)]
-    {
-        use std::process::Command;
-        
-        // Try to find Ollama process and stop it gracefully
-        match Command::new("pkill")
-            .arg("-f")
-            .arg("ollama")
-            .output()
-        {
-            Ok(output) => {
-                if output.status.success() {
-                    println!("Ollama stop signal sent successfully");
-                    Ok(())
-                } else {
-                    // pkill failed, but that's not necessarily an error
-                    // (could mean no process was found)
-                    Ok(())
-                }
-            }
-            Err(e) => {
-                Err(format!("Failed to stop Ollama: {}", e))
-            }
-        }
-    }
-    
-    // On Windows, try a different approach
-    #[cfg(windows)]
-    {
-        use std::process::Command;
-        
-        match Command::new("taskkill")
-            .args(["/F", "/IM", "ollama.exe"])
-            .output()
-        {
-            Ok(output) => {
-                if output.status.success() {
-                    println!("Ollama stopped successfully");
-                    Ok(())
-                } else {
-                    // Task not found is OK
-                    Ok(())
-                }
-            }
-            Err(e) => {
-                Err(format!("Failed to stop Ollama: {}", e))
-            }
-        }
+
+    // Only the process the app launched is ours to stop; a server the user started may be serving other work
+    if !is_app_started_ollama_running() {
+        return Err("Ollama was started externally; not stopping it".to_string());
     }
+
+    tokio::task::spawn_blocking(|| stop_app_started_ollama(OLLAMA_STOP_TIMEOUT))
+        .await
+        .map_err(|e| format!("Failed to stop Ollama: {}", e))
 }
 
 fn find_ollama_binary() -> Option<String> {
-    // Try using the 'which' command first for cross-platform compatibility
-    if let Ok(path) = which::which("ollama") {
-        return Some(path.to_string_lossy().to_string());
-    }
+    find_ollama_binaries()
+        .into_iter()
+        .next()
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+// Every ollama binary on PATH or in a known install location, PATH entries first in PATH order.
+// Symlinks to the same binary (e.g. Homebrew's bin link) are only listed once.
+fn find_ollama_binaries() -> Vec<PathBuf> {
+    let mut candidates: Vec<PathBuf> = which::which_all("ollama")
+        .map(|paths| paths.collect())
+        .unwrap_or_default();
 
     // Platform-specific fallback paths
     let potential_paths = if cfg!(target_os = "windows") {
@@ -167,14 +234,29 @@ fn find_ollama_binary() -> Option<String> {
             "/opt/ollama/bin/ollama",
         ]
     };
-
-    for path in potential_paths {
-        if std::path::Path::new(path).exists() {
-            return Some(path.to_string());
+    candidates.extend(potential_paths.into_iter().map(PathBuf::from).filter(|path| path.exists()));
+    if cfg!(target_os = "windows") {
+        // The per-user installer puts Ollama under %LOCALAPPDATA%
+        if let Some(local_app_data) = std::env::var_os("LOCALAPPDATA") {
+            let path = PathBuf::from(local_app_data).join("Programs").join("Ollama").join("ollama.exe");
+            if path.exists() {
+                candidates.push(path);
+            }
         }
     }
 
-    None
+    let mut seen = Vec::new();
+    candidates
+        .into_iter()
+        .filter(|path| {
+            let resolved = fs::canonicalize(path).unwrap_or_else(|_| path.clone());
+            if seen.contains(&resolved) {
+                return false;
+            }
+            seen.push(resolved);
+            true
+        })
+        .collect()
 }
 
 async fn test_ollama_api() -> bool {
@@ -184,7 +266,7 @@ async fn test_ollama_api() -> bool {
         .unwrap_or_default();
 
     // Test the Ollama API endpoint
-    match client.get("http://localhost:11434/api/tags").send().await {
+    match client.get(format!("{}/api/tags", OLLAMA_BASE_URL)).send().await {
         Ok(response) => response.status().is_success(),
         Err(_) => false,
     }
@@ -211,13 +293,451 @@ fn get_ollama_version(binary_path: &str) -> Option<String> {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaInstallation {
+    pub path: String,
+    // Where the path points after following symlinks, e.g. into Homebrew's Cellar
+    pub resolved_path: String,
+    pub version: Option<String>,
+    pub on_path: bool,
+    // "homebrew", "app", "system" or "manual", inferred from the location
+    pub install_method: String,
+    pub models_directory: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaInstallDiagnosis {
+    pub installations: Vec<OllamaInstallation>,
+    // The binary a terminal runs for `ollama`
+    pub path_binary: Option<String>,
+    pub server_version: Option<String>,
+    pub shares_models_directory: bool,
+    pub warnings: Vec<String>,
+}
+
+fn install_method(resolved_path: &Path) -> &'static str {
+    let path = resolved_path.to_string_lossy();
+    if path.contains("/Cellar/") || path.contains("/homebrew/") || path.contains("/linuxbrew/") {
+        "homebrew"
+    } else if path.contains("Ollama.app") || path.contains("\\Programs\\Ollama\\") || path.contains("\\Program Files") {
+        "app"
+    } else if path.starts_with("/usr/") || path.starts_with("/opt/") {
+        "system"
+    } else {
+        "manual"
+    }
+}
+
+// The install script runs a system service as the `ollama` user, which keeps models under its own home
+fn installation_models_directory(method: &str) -> PathBuf {
+    if let Some(models) = std::env::var_os("OLLAMA_MODELS") {
+        return PathBuf::from(models);
+    }
+    let service_models = Path::new("/usr/share/ollama/.ollama/models");
+    if cfg!(target_os = "linux") && method == "system" && service_models.exists() {
+        return service_models.to_path_buf();
+    }
+    get_ollama_models_directory().unwrap_or_default()
+}
+
+fn describe_installations() -> Vec<OllamaInstallation> {
+    let path_binary = which::which("ollama").ok().map(|path| fs::canonicalize(&path).unwrap_or(path));
+    find_ollama_binaries()
+        .into_iter()
+        .map(|path| {
+            let resolved = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+            let method = install_method(&resolved);
+            OllamaInstallation {
+                path: path.to_string_lossy().to_string(),
+                resolved_path: resolved.to_string_lossy().to_string(),
+                version: get_ollama_version(&path.to_string_lossy()),
+                on_path: path_binary.as_ref() == Some(&resolved),
+                install_method: method.to_string(),
+                models_directory: installation_models_directory(method).to_string_lossy().to_string(),
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct OllamaVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl OllamaVersion {
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        OllamaVersion { major, minor, patch }
+    }
+
+    // Accepts "0.1.32", "v0.5.7" and pre-release forms like "0.6.0-rc1"
+    pub fn parse(version: &str) -> Option<Self> {
+        let version = version.trim().trim_start_matches('v');
+        let core = version.split(['-', '+']).next()?;
+        let mut parts = core.split('.').map(|part| part.parse::<u64>());
+
+        Some(OllamaVersion {
+            major: parts.next()?.ok()?,
+            minor: parts.next().unwrap_or(Ok(0)).ok()?,
+            patch: parts.next().unwrap_or(Ok(0)).ok()?,
+        })
+    }
+}
+
+impl std::fmt::Display for OllamaVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+// Releases that introduced the features we depend on
+const MIN_VERSION_KEEP_ALIVE: OllamaVersion = OllamaVersion::new(0, 1, 23);
+const MIN_VERSION_PROCESS_STATUS: OllamaVersion = OllamaVersion::new(0, 1, 38);
+const MIN_VERSION_EMBED: OllamaVersion = OllamaVersion::new(0, 3, 0);
+const MIN_VERSION_TOOLS: OllamaVersion = OllamaVersion::new(0, 3, 0);
+const MIN_VERSION_STRUCTURED_OUTPUTS: OllamaVersion = OllamaVersion::new(0, 5, 0);
+const MIN_VERSION_DEFAULT_NUM_CTX_4096: OllamaVersion = OllamaVersion::new(0, 6, 4);
+const MIN_RECOMMENDED_VERSION: OllamaVersion = OllamaVersion::new(0, 3, 0);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaCapabilities {
+    pub version: Option<OllamaVersion>,
+    pub keep_alive: bool,
+    pub process_status: bool,
+    // /api/embed (batch) replaced /api/embeddings (single prompt)
+    pub embed: bool,
+    pub embed_endpoint: String,
+    pub tool_calling: bool,
+    pub structured_outputs: bool,
+    // Context window used when neither the request nor the Modelfile sets num_ctx
+    pub default_num_ctx: u64,
+    pub warning: Option<String>,
+}
+
+impl OllamaCapabilities {
+    // An unknown version is treated as current, since Ollama auto-updates on most installs
+    pub fn for_version(version: Option<OllamaVersion>) -> Self {
+        let supports = |minimum: OllamaVersion| version.map(|v| v >= minimum).unwrap_or(true);
+        let embed = supports(MIN_VERSION_EMBED);
+
+        let warning = match version {
+            Some(v) if v < MIN_RECOMMENDED_VERSION => Some(format!(
+                "Ollama {} is older than the recommended {}. Some features will be unavailable until you update.",
+                v, MIN_RECOMMENDED_VERSION
+            )),
+            Some(_) => None,
+            None => Some("Could not determine the Ollama version; assuming a current release.".to_string()),
+        };
+
+        OllamaCapabilities {
+            version,
+            keep_alive: supports(MIN_VERSION_KEEP_ALIVE),
+            process_status: supports(MIN_VERSION_PROCESS_STATUS),
+            embed,
+            embed_endpoint: if embed { "/api/embed" } else { "/api/embeddings" }.to_string(),
+            tool_calling: supports(MIN_VERSION_TOOLS),
+            structured_outputs: supports(MIN_VERSION_STRUCTURED_OUTPUTS),
+            default_num_ctx: if supports(MIN_VERSION_DEFAULT_NUM_CTX_4096) { 4096 } else { 2048 },
+            warning,
+        }
+    }
+}
+
+// The running server's version is what matters; the CLI can differ after a partial update
+async fn get_server_version() -> Option<String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .ok()?;
+
+    let response = client.get(format!("{}/api/version", OLLAMA_BASE_URL)).send().await.ok()?;
+    let body: serde_json::Value = response.json().await.ok()?;
+    body.get("version").and_then(|v| v.as_str()).map(|v| v.to_string())
+}
+
+// Prefer the running server, falling back to asking the installed binary
+pub async fn detect_installed_version() -> Option<String> {
+    match get_server_version().await {
+        Some(version) => Some(version),
+        None => match find_ollama_binary() {
+            Some(path) => tokio::task::spawn_blocking(move || get_ollama_version(&path)).await.ok().flatten(),
+            None => None,
+        },
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InstalledTag {
+    pub name: String,
+    #[serde(default)]
+    pub size: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaTagsResponse {
+    #[serde(default)]
+    models: Vec<InstalledTag>,
+}
+
+// Models the running Ollama server has installed, as reported by /api/tags
+pub async fn list_installed_tags(client: &reqwest::Client) -> Result<Vec<InstalledTag>, String> {
+    let tags: OllamaTagsResponse = client
+        .get(format!("{}/api/tags", OLLAMA_BASE_URL))
+        .send()
+        .await
+        .map_err(|e| format!("Ollama is not reachable: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid response from Ollama: {}", e))?;
+    Ok(tags.models)
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaRunningModel {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaPsResponse {
+    #[serde(default)]
+    models: Vec<OllamaRunningModel>,
+}
+
+// Models currently resident in memory, as reported by /api/ps
+pub async fn list_running_models(client: &reqwest::Client) -> Result<Vec<String>, String> {
+    let ps: OllamaPsResponse = client
+        .get(format!("{}/api/ps", OLLAMA_BASE_URL))
+        .send()
+        .await
+        .map_err(|e| format!("Ollama is not reachable: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid response from Ollama: {}", e))?;
+    Ok(ps.models.into_iter().map(|model| model.name).collect())
+}
+
+fn is_model_running(running: &[String], model: &str) -> bool {
+    running
+        .iter()
+        .any(|name| name == model || name.strip_suffix(":latest") == Some(model))
+}
+
+// Installed models read straight from Ollama's manifest store, for when the server isn't running.
+// Manifests live at manifests/<host>/<namespace>/<model>/<tag> and list the blob sizes.
+fn read_manifest_tags() -> Vec<InstalledTag> {
+    let Ok(manifests_dir) = get_ollama_models_directory().map(|dir| dir.join("manifests")) else {
+        return Vec::new();
+    };
+
+    let mut tags = Vec::new();
+    let subdirs = |dir: &Path| -> Vec<PathBuf> {
+        fs::read_dir(dir)
+            .map(|entries| entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect())
+            .unwrap_or_default()
+    };
+    let file_name = |path: &Path| path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+
+    for host in subdirs(&manifests_dir).into_iter().filter(|path| path.is_dir()) {
+        for namespace in subdirs(&host).into_iter().filter(|path| path.is_dir()) {
+            for model in subdirs(&namespace).into_iter().filter(|path| path.is_dir()) {
+                for tag in subdirs(&model).into_iter().filter(|path| path.is_file()) {
+                    let (host, namespace, model, tag_name) = (file_name(&host), file_name(&namespace), file_name(&model), file_name(&tag));
+
+                    let size = fs::read_to_string(&tag)
+                        .ok()
+                        .and_then(|manifest| serde_json::from_str::<serde_json::Value>(&manifest).ok())
+                        .map(|manifest| {
+                            let layers = manifest.get("layers").and_then(|layers| layers.as_array()).cloned().unwrap_or_default();
+                            layers.iter().filter_map(|layer| layer.get("size").and_then(|size| size.as_u64())).sum()
+                        })
+                        .unwrap_or(0);
+
+                    // Mirror how Ollama itself names models from each registry
+                    let name = match (host.as_str(), namespace.as_str()) {
+                        ("registry.ollama.ai", "library") => format!("{}:{}", model, tag_name),
+                        ("registry.ollama.ai", _) => format!("{}/{}:{}", namespace, model, tag_name),
+                        _ => format!("{}/{}/{}:{}", host, namespace, model, tag_name),
+                    };
+                    tags.push(InstalledTag { name, size });
+                }
+            }
+        }
+    }
+
+    tags
+}
+
+// Full /api/show output for an installed model: modelfile parameters, template and model_info
+pub async fn show_ollama_model(client: &reqwest::Client, model: &str) -> Result<serde_json::Value, String> {
+    let response = client
+        .post(format!("{}/api/show", OLLAMA_BASE_URL))
+        .json(&serde_json::json!({ "model": model }))
+        .send()
+        .await
+        .map_err(|e| format!("Ollama is not reachable: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Ollama returned {} for {}: {}", status, model, body.trim()));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid response from Ollama: {}", e))
+}
+
+// The context length the model was trained with, read from /api/show's model_info
+// Length the model was trained for, from an /api/show response
+fn trained_context_length(show: &serde_json::Value) -> Option<u64> {
+    // Keys are prefixed with the architecture, e.g. "llama.context_length"
+    show.get("model_info")?
+        .as_object()?
+        .iter()
+        .find(|(key, _)| key.ends_with(".context_length"))
+        .and_then(|(_, value)| value.as_u64())
+}
+
+// Ollama runs every model at its default context unless the Modelfile sets num_ctx, whatever it was trained for
+pub async fn effective_num_ctx(client: &reqwest::Client, model: &str) -> Option<u64> {
+    let show = show_ollama_model(client, model).await.ok()?;
+
+    let configured = show
+        .get("parameters")
+        .and_then(|parameters| parameters.as_str())
+        .and_then(|parameters| {
+            parse_modelfile_parameters(parameters)
+                .into_iter()
+                .find(|(name, _)| name == "num_ctx")
+                .and_then(|(_, value)| value.parse::<u64>().ok())
+        });
+    let num_ctx = match configured {
+        Some(num_ctx) => num_ctx,
+        None => {
+            let version = get_server_version().await.as_deref().and_then(OllamaVersion::parse);
+            OllamaCapabilities::for_version(version).default_num_ctx
+        }
+    };
+
+    // A larger num_ctx than the model was trained for doesn't give it a longer usable window
+    Some(trained_context_length(&show).map_or(num_ctx, |trained| num_ctx.min(trained)))
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbedResponse {
+    #[serde(default)]
+    embeddings: Vec<Vec<f64>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LegacyEmbeddingResponse {
+    #[serde(default)]
+    embedding: Vec<f64>,
+}
+
+// Embed several texts with whichever embeddings endpoint the installed Ollama supports
+pub async fn embed_texts(client: &reqwest::Client, model: &str, texts: &[&str]) -> Result<Vec<Vec<f64>>, String> {
+    let capabilities = get_ollama_capabilities().await;
+
+    if capabilities.embed {
+        let response: EmbedResponse = client
+            .post(format!("{}{}", OLLAMA_BASE_URL, capabilities.embed_endpoint))
+            .json(&serde_json::json!({ "model": model, "input": texts }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Ollama: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid embeddings response from Ollama: {}", e))?;
+        return Ok(response.embeddings);
+    }
+
+    // The legacy endpoint takes one prompt per request
+    let mut embeddings = Vec::with_capacity(texts.len());
+    for text in texts {
+        let response: LegacyEmbeddingResponse = client
+            .post(format!("{}{}", OLLAMA_BASE_URL, capabilities.embed_endpoint))
+            .json(&serde_json::json!({ "model": model, "prompt": text }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Ollama: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid embeddings response from Ollama: {}", e))?;
+        embeddings.push(response.embedding);
+    }
+    Ok(embeddings)
+}
+
+pub async fn get_ollama_capabilities() -> OllamaCapabilities {
+    let version = detect_installed_version().await;
+    OllamaCapabilities::for_version(version.as_deref().and_then(OllamaVersion::parse))
+}
+
+const OLLAMA_LATEST_RELEASE_URL: &str = "https://api.github.com/repos/ollama/ollama/releases/latest";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaUpdateInfo {
+    pub current: Option<String>,
+    // None when GitHub couldn't be reached
+    pub latest: Option<String>,
+    pub update_available: bool,
+    pub release_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    html_url: String,
+}
+
+async fn fetch_latest_ollama_release() -> Result<GithubRelease, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    // GitHub rejects API requests without a User-Agent
+    client
+        .get(OLLAMA_LATEST_RELEASE_URL)
+        .header("User-Agent", "open-chat")
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach GitHub: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("GitHub returned an error: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid release data from GitHub: {}", e))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LocalModel {
     pub name: String,
+    // Display path; may be lossy if the real path isn't valid UTF-8
     pub path: String,
+    // Real on-disk path used for filesystem operations
+    #[serde(skip)]
+    pub file_path: PathBuf,
     pub size_bytes: u64,
     pub source: ModelSource,
     pub format: Option<String>,
+    // SHA-256 of the file, only populated once it has been computed via hash_model
+    pub hash: Option<String>,
+    #[serde(default)]
+    pub is_favorite: bool,
+}
+
+impl LocalModel {
+    pub fn model_ref(&self) -> ModelRef {
+        ModelRef::Local {
+            name: self.name.clone(),
+            path: Some(self.path.clone()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -277,7 +797,7 @@ async fn discover_ollama_models() -> Result<Vec<LocalModel>, String> {
     // Get Ollama models directory
     let ollama_dir = get_ollama_models_directory()?;
     
-    if !Path::new(&ollama_dir).exists() {
+    if !ollama_dir.exists() {
         return Ok(models); // Return empty vec if directory doesn't exist
     }
 
@@ -297,10 +817,10 @@ async fn discover_lmstudio_models() -> Result<Vec<LocalModel>, String> {
     let lmstudio_dirs = get_lmstudio_directories();
     
     for dir in lmstudio_dirs {
-        if Path::new(&dir).exists() {
+        if dir.exists() {
             match scan_directory_for_models(&dir, ModelSource::LmStudio) {
                 Ok(mut discovered) => models.append(&mut discovered),
-                Err(e) => eprintln!("Warning: Failed to scan LMStudio directory {}: {}", dir, e),
+                Err(e) => eprintln!("Warning: Failed to scan LMStudio directory {}: {}", dir.display(), e),
             }
         }
     }
@@ -308,52 +828,53 @@ async fn discover_lmstudio_models() -> Result<Vec<LocalModel>, String> {
     Ok(models)
 }
 
-fn get_ollama_models_directory() -> Result<String, String> {
-    // Ollama models are typically stored in ~/.ollama/models
-    let home_dir = if cfg!(target_os = "windows") {
-        std::env::var("USERPROFILE").or_else(|_| std::env::var("HOMEPATH"))
+fn get_home_directory() -> Option<PathBuf> {
+    // Use var_os so home directories with non-UTF8 names are preserved
+    if cfg!(target_os = "windows") {
+        std::env::var_os("USERPROFILE").or_else(|| std::env::var_os("HOMEPATH"))
     } else {
-        std::env::var("HOME")
-    }.map_err(|_| "Could not determine home directory")?;
+        std::env::var_os("HOME")
+    }.map(PathBuf::from)
+}
+
+fn get_ollama_models_directory() -> Result<PathBuf, String> {
+    // Ollama models are typically stored in ~/.ollama/models
+    let home_dir = get_home_directory().ok_or("Could not determine home directory")?;
 
     let models_dir = if cfg!(target_os = "windows") {
-        format!("{}\\AppData\\Local\\ollama\\models", home_dir)
+        home_dir.join("AppData").join("Local").join("ollama").join("models")
     } else {
-        format!("{}/.ollama/models", home_dir)
+        home_dir.join(".ollama").join("models")
     };
 
     Ok(models_dir)
 }
 
-fn get_lmstudio_directories() -> Vec<String> {
+fn get_lmstudio_directories() -> Vec<PathBuf> {
     let mut dirs = Vec::new();
     
-    if let Ok(home_dir) = if cfg!(target_os = "windows") {
-        std::env::var("USERPROFILE").or_else(|_| std::env::var("HOMEPATH"))
-    } else {
-        std::env::var("HOME")
-    } {
+    if let Some(home_dir) = get_home_directory() {
         if cfg!(target_os = "windows") {
-            dirs.push(format!("{}\\AppData\\Roaming\\LM Studio\\models", home_dir));
-            dirs.push(format!("{}\\Documents\\LM Studio\\models", home_dir));
+            dirs.push(home_dir.join("AppData").join("Roaming").join("LM Studio").join("models"));
+            dirs.push(home_dir.join("Documents").join("LM Studio").join("models"));
         } else if cfg!(target_os = "macos") {
-            dirs.push(format!("{}/Library/Application Support/LM Studio/models", home_dir));
-            dirs.push(format!("{}/.lmstudio/models", home_dir));
+            dirs.push(home_dir.join("Library").join("Application Support").join("LM Studio").join("models"));
+            dirs.push(home_dir.join(".lmstudio").join("models"));
         } else {
             // Linux
-            dirs.push(format!("{}/.config/lmstudio/models", home_dir));
-            dirs.push(format!("{}/.lmstudio/models", home_dir));
+            dirs.push(home_dir.join(".config").join("lmstudio").join("models"));
+            dirs.push(home_dir.join(".lmstudio").join("models"));
         }
     }
 
     dirs
 }
 
-fn scan_directory_for_models(dir_path: &str, source: ModelSource) -> Result<Vec<LocalModel>, String> {
+fn scan_directory_for_models(dir_path: &Path, source: ModelSource) -> Result<Vec<LocalModel>, String> {
     let mut models = Vec::new();
     
     let entries = fs::read_dir(dir_path)
-        .map_err(|e| format!("Failed to read directory {}: {}", dir_path, e))?;
+        .map_err(|e| format!("Failed to read directory {}: {}", dir_path.display(), e))?;
 
     for entry in entries {
         let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
@@ -365,10 +886,7 @@ fn scan_directory_for_models(dir_path: &str, source: ModelSource) -> Result<Vec<
             }
         } else if path.is_dir() {
             // Recursively scan subdirectories (common in Ollama structure)
-            if let Ok(mut subdir_models) = scan_directory_for_models(
-                &path.to_string_lossy(),
-                source.clone()
-            ) {
+            if let Ok(mut subdir_models) = scan_directory_for_models(&path, source.clone()) {
                 models.append(&mut subdir_models);
             }
         }
@@ -378,6 +896,7 @@ fn scan_directory_for_models(dir_path: &str, source: ModelSource) -> Result<Vec<
 }
 
 fn try_parse_model_file(path: &Path, source: &ModelSource) -> Option<LocalModel> {
+    // Only lossy-convert for display and name extraction; keep the real path for I/O
     let path_str = path.to_string_lossy().to_string();
     let file_name = path.file_name()?.to_string_lossy().to_string();
     
@@ -392,10 +911,11 @@ fn try_parse_model_file(path: &Path, source: &ModelSource) -> Option<LocalModel>
     }
 
     // Get file size
-    let size_bytes = match fs::metadata(path) {
-        Ok(metadata) => metadata.len(),
-        Err(_) => 0,
-    };
+    let metadata = fs::metadata(path).ok();
+    let size_bytes = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
+
+    // Hashing is expensive, so only reuse a previously computed value here
+    let hash = metadata.as_ref().and_then(|m| get_cached_model_hash(path, m));
 
     // Extract model name (try to clean up the path/filename)
     let model_name = extract_model_name(&path_str, source);
@@ -406,9 +926,12 @@ fn try_parse_model_file(path: &Path, source: &ModelSource) -> Option<LocalModel>
     Some(LocalModel {
         name: model_name,
         path: path_str,
+        file_path: path.to_path_buf(),
         size_bytes,
         source: source.clone(),
         format,
+        hash,
+        is_favorite: false,
     })
 }
 
@@ -467,12 +990,988 @@ fn determine_model_format(filename: &str) -> Option<String> {
     }
 }
 
+fn get_cached_model_hash(path: &Path, metadata: &fs::Metadata) -> Option<String> {
+    let cache = MODEL_HASH_CACHE.lock().ok()?;
+    let cached = cache.get(path)?;
+
+    // Only trust the cached hash if the file hasn't changed since it was computed
+    if cached.size_bytes == metadata.len() && cached.modified == metadata.modified().ok() {
+        Some(cached.sha256.clone())
+    } else {
+        None
+    }
+}
+
+fn compute_file_sha256(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+
+    // Stream the file through the hasher so multi-GB models aren't loaded into memory
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+pub fn hash_model_file(path: &Path) -> Result<String, String> {
+    let metadata = fs::metadata(path)
+        .map_err(|e| format!("Failed to read metadata for {}: {}", path.display(), e))?;
+
+    if let Some(hash) = get_cached_model_hash(path, &metadata) {
+        return Ok(hash);
+    }
+
+    let sha256 = compute_file_sha256(path)?;
+
+    if let Ok(mut cache) = MODEL_HASH_CACHE.lock() {
+        cache.insert(path.to_path_buf(), CachedModelHash {
+            modified: metadata.modified().ok(),
+            size_bytes: metadata.len(),
+            sha256: sha256.clone(),
+        });
+    }
+
+    Ok(sha256)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum OllamaLoadProgress {
+    Loading { model: String, elapsed_ms: u64 },
+    Ready { model: String, load_duration_ms: u64 },
+    Error { model: String, error: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelPreload {
+    pub model: String,
+    pub already_loaded: bool,
+    // Zero when the model was already resident
+    pub load_duration_ms: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaGenerateChunk {
+    #[serde(default)]
+    done: bool,
+    load_duration: Option<u64>,
+    error: Option<String>,
+    #[serde(default)]
+    response: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelSanityCheck {
+    pub ok: bool,
+    pub first_token_ms: Option<u64>,
+    pub sample_output: String,
+    pub reason: Option<String>,
+}
+
+const SANITY_CHECK_PROMPT: &str = "Reply with the single word: hello";
+const SANITY_CHECK_TIMEOUT: Duration = Duration::from_secs(120);
+
+// Accepts Go-style durations ("30m", "1h30m", "90s") or a bare number of seconds ("-1" keeps forever, "0" unloads)
+fn parse_keep_alive(duration: &str) -> Result<serde_json::Value, String> {
+    let duration = duration.trim();
+
+    if let Ok(seconds) = duration.parse::<i64>() {
+        // Ollama only understands unitless values as JSON numbers
+        return Ok(serde_json::json!(seconds));
+    }
+
+    let unsigned = duration.strip_prefix('-').unwrap_or(duration);
+    let mut rest = unsigned;
+    let mut saw_component = false;
+    while !rest.is_empty() {
+        let number_len = rest.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(rest.len());
+        let number = &rest[..number_len];
+        if number.is_empty() || number.parse::<f64>().is_err() {
+            return Err(format!("Invalid keep-alive duration '{}'", duration));
+        }
+        rest = &rest[number_len..];
+
+        let unit = ["ms", "h", "m", "s"]
+            .iter()
+            .find(|unit| rest.starts_with(*unit))
+            .ok_or_else(|| format!("Invalid keep-alive duration '{}': expected a unit of ms, s, m or h", duration))?;
+        rest = &rest[unit.len()..];
+        saw_component = true;
+    }
+
+    if !saw_component {
+        return Err("Keep-alive duration cannot be empty".to_string());
+    }
+
+    Ok(serde_json::json!(duration))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaEnvOverrides {
+    // What will be applied the next time the app starts Ollama
+    pub saved: BTreeMap<String, String>,
+    // What the currently running app-started server was launched with, if there is one
+    pub active: Option<BTreeMap<String, String>>,
+    pub restart_required: bool,
+    pub warnings: Vec<String>,
+}
+
+// Returns None for variables we don't know how to validate
+fn validate_ollama_env(name: &str, value: &str) -> Option<Result<(), String>> {
+    let result = match name {
+        "OLLAMA_NUM_PARALLEL" | "OLLAMA_MAX_LOADED_MODELS" => match value.parse::<u32>() {
+            Ok(count) if count > 0 => Ok(()),
+            _ => Err(format!("{} must be a positive whole number, got '{}'", name, value)),
+        },
+        "OLLAMA_FLASH_ATTENTION" => match value.to_lowercase().as_str() {
+            "0" | "1" | "true" | "false" => Ok(()),
+            _ => Err(format!("{} must be 1 or 0, got '{}'", name, value)),
+        },
+        "OLLAMA_KV_CACHE_TYPE" => match value {
+            "f16" | "q8_0" | "q4_0" => Ok(()),
+            _ => Err(format!("{} must be one of f16, q8_0 or q4_0, got '{}'", name, value)),
+        },
+        _ => return None,
+    };
+    Some(result)
+}
+
+// Add the user's keep_alive preference to an Ollama request body
+pub fn with_keep_alive(app: &AppHandle, mut body: serde_json::Value) -> serde_json::Value {
+    let keep_alive = get_setting::<String>(app, KEEP_ALIVE_KEY)
+        .unwrap_or_else(|e| {
+            eprintln!("Warning: {}", e);
+            None
+        })
+        .and_then(|duration| parse_keep_alive(&duration).ok());
+
+    if let (Some(keep_alive), Some(object)) = (keep_alive, body.as_object_mut()) {
+        object.insert("keep_alive".to_string(), keep_alive);
+    }
+    body
+}
+
+fn emit_load_progress(app: &AppHandle, progress: OllamaLoadProgress) {
+    if let Err(e) = app.emit("ollama-load-progress", progress) {
+        eprintln!("Warning: Failed to emit model load progress: {}", e);
+    }
+}
+
+pub async fn load_model_with_progress(app: &AppHandle, model: &str, preset: &serde_json::Value) -> Result<u64, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(600))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let started = std::time::Instant::now();
+    emit_load_progress(app, OllamaLoadProgress::Loading { model: model.to_string(), elapsed_ms: 0 });
+
+    // A generate request without a prompt only loads the model into memory; num_ctx decides how much it allocates
+    let mut response = client
+        .post(format!("{}/api/generate", OLLAMA_BASE_URL))
+        .json(&with_keep_alive(app, serde_json::json!({ "model": model, "stream": true, "options": preset })))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Ollama returned {} while loading {}: {}", status, model, body.trim()));
+    }
+
+    // Ollama sends nothing until the model is resident, so emit heartbeats while we wait
+    let mut heartbeat = tokio::time::interval(Duration::from_secs(1));
+    heartbeat.tick().await;
+    // Bytes, not text: a multi-byte character can be split across chunks, so only complete lines are decoded
+    let mut buffer: Vec<u8> = Vec::new();
+
+    loop {
+        tokio::select! {
+            _ = heartbeat.tick() => {
+                emit_load_progress(app, OllamaLoadProgress::Loading {
+                    model: model.to_string(),
+                    elapsed_ms: started.elapsed().as_millis() as u64,
+                });
+            }
+            chunk = response.chunk() => {
+                let chunk = chunk.map_err(|e| format!("Failed to read Ollama response: {}", e))?;
+                let Some(chunk) = chunk else {
+                    return Err(format!("Ollama closed the connection before {} finished loading", model));
+                };
+                buffer.extend_from_slice(&chunk);
+
+                // The response is newline-delimited JSON
+                while let Some(newline) = buffer.iter().position(|byte| *byte == b'\n') {
+                    let line: Vec<u8> = buffer.drain(..=newline).collect();
+                    let line = String::from_utf8_lossy(&line);
+                    let line = line.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
+
+                    let parsed: OllamaGenerateChunk = serde_json::from_str(line)
+                        .map_err(|e| format!("Failed to parse Ollama response: {}", e))?;
+
+                    if let Some(error) = parsed.error {
+                        return Err(format!("Ollama failed to load {}: {}", model, error));
+                    }
+
+                    if parsed.done {
+                        // Ollama reports durations in nanoseconds
+                        let load_duration_ms = parsed.load_duration
+                            .map(|ns| ns / 1_000_000)
+                            .unwrap_or_else(|| started.elapsed().as_millis() as u64);
+                        emit_load_progress(app, OllamaLoadProgress::Ready {
+                            model: model.to_string(),
+                            load_duration_ms,
+                        });
+                        return Ok(load_duration_ms);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl ModelSanityCheck {
+    fn failed(first_token_ms: Option<u64>, sample_output: String, reason: String) -> Self {
+        ModelSanityCheck {
+            ok: false,
+            first_token_ms,
+            sample_output,
+            reason: Some(reason),
+        }
+    }
+}
+
+// Broken quants tend to emit replacement characters or control bytes instead of text
+fn looks_like_garbage(output: &str) -> bool {
+    let total = output.chars().count();
+    if total == 0 {
+        return false;
+    }
+    let garbled = output
+        .chars()
+        .filter(|c| *c == '\u{FFFD}' || (c.is_control() && !c.is_whitespace()))
+        .count();
+    garbled * 2 >= total
+}
+
+async fn stream_sanity_check(
+    app: &AppHandle,
+    model: &str,
+    started: std::time::Instant,
+    first_token_ms: &mut Option<u64>,
+    output: &mut String,
+) -> Result<(), String> {
+    let client = reqwest::Client::new();
+
+    // Greedy decoding with a tiny budget keeps this fast and repeatable, so it deliberately ignores parameter presets
+    let mut response = client
+        .post(format!("{}/api/generate", OLLAMA_BASE_URL))
+        .json(&with_keep_alive(app, serde_json::json!({
+            "model": model,
+            "prompt": SANITY_CHECK_PROMPT,
+            "stream": true,
+            "options": { "temperature": 0, "seed": 0, "num_predict": 8 }
+        })))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Ollama returned {}: {}", status, body.trim()));
+    }
+
+    // Decoding per chunk would garble characters split across chunks, which is exactly what this check looks for
+    let mut buffer: Vec<u8> = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to read Ollama response: {}", e))?
+    {
+        buffer.extend_from_slice(&chunk);
+
+        while let Some(newline) = buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let parsed: OllamaGenerateChunk = serde_json::from_str(line)
+                .map_err(|e| format!("Failed to parse Ollama response: {}", e))?;
+
+            if let Some(error) = parsed.error {
+                return Err(error);
+            }
+
+            if !parsed.response.is_empty() && first_token_ms.is_none() {
+                *first_token_ms = Some(started.elapsed().as_millis() as u64);
+            }
+            output.push_str(&parsed.response);
+
+            if parsed.done {
+                return Ok(());
+            }
+        }
+    }
+
+    Err("Ollama closed the connection before the response finished".to_string())
+}
+
+pub async fn run_model_sanity_check(app: &AppHandle, model: &str) -> ModelSanityCheck {
+    let started = std::time::Instant::now();
+    let mut first_token_ms = None;
+    let mut output = String::new();
+
+    let result = tokio::time::timeout(
+        SANITY_CHECK_TIMEOUT,
+        stream_sanity_check(app, model, started, &mut first_token_ms, &mut output),
+    )
+    .await;
+
+    match result {
+        Err(_) => ModelSanityCheck::failed(
+            first_token_ms,
+            output,
+            format!("No complete response within {} seconds", SANITY_CHECK_TIMEOUT.as_secs()),
+        ),
+        Ok(Err(error)) => ModelSanityCheck::failed(first_token_ms, output, error),
+        Ok(Ok(())) if output.trim().is_empty() => {
+            ModelSanityCheck::failed(first_token_ms, output, "Model returned an empty response".to_string())
+        }
+        Ok(Ok(())) if looks_like_garbage(&output) => ModelSanityCheck::failed(
+            first_token_ms,
+            output,
+            "Model output is garbled, the download may be corrupt".to_string(),
+        ),
+        Ok(Ok(())) => ModelSanityCheck {
+            ok: true,
+            first_token_ms,
+            sample_output: output.trim().to_string(),
+            reason: None,
+        },
+    }
+}
+
+const TOOL_TEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+// Ask for something only the dummy tool can answer, so a model that understands tools has to call it
+async fn probe_tool_support(app: &AppHandle, model: &str) -> Result<bool, String> {
+    let client = reqwest::Client::builder()
+        .timeout(TOOL_TEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let response = client
+        .post(format!("{}/api/chat", OLLAMA_BASE_URL))
+        .json(&with_keep_alive(app, serde_json::json!({
+            "model": model,
+            "stream": false,
+            "messages": [{ "role": "user", "content": "What is the weather in Paris right now? Use the provided tool." }],
+            "tools": [{
+                "type": "function",
+                "function": {
+                    "name": "get_current_weather",
+                    "description": "Get the current weather for a city",
+                    "parameters": {
+                        "type": "object",
+                        "properties": {
+                            "city": { "type": "string", "description": "The name of the city" }
+                        },
+                        "required": ["city"]
+                    }
+                }
+            }],
+            "options": { "temperature": 0, "seed": 0 }
+        })))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        // Models whose template has no tool section are rejected up front
+        if body.contains("does not support tools") {
+            return Ok(false);
+        }
+        return Err(format!("Ollama returned {}: {}", status, body.trim()));
+    }
+
+    let parsed: serde_json::Value = serde_json::from_str(&body)
+        .map_err(|e| format!("Failed to parse Ollama response: {}", e))?;
+    Ok(parsed
+        .pointer("/message/tool_calls")
+        .and_then(|calls| calls.as_array())
+        .is_some_and(|calls| !calls.is_empty()))
+}
+
+// Long contexts cost memory linearly, so the trained length is only suggested up to this size
+const MAX_RECOMMENDED_NUM_CTX: u64 = 8192;
+const DEFAULT_NUM_CTX: u64 = 4096;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecommendedParams {
+    pub temperature: f64,
+    pub top_p: f64,
+    pub top_k: u64,
+    pub stop: Vec<String>,
+    pub num_ctx: u64,
+    pub architecture: Option<String>,
+    // Which of the values above came from the model's own parameters rather than fallbacks
+    pub from_model: Vec<String>,
+}
+
+// (temperature, top_p, top_k) commonly recommended for each model family; Ollama's defaults otherwise
+fn architecture_defaults(architecture: Option<&str>) -> (f64, f64, u64) {
+    let architecture = architecture.unwrap_or_default().to_lowercase();
+    if architecture.starts_with("llama") {
+        (0.6, 0.9, 40)
+    } else if architecture.starts_with("qwen") {
+        (0.7, 0.8, 20)
+    } else if architecture.starts_with("gemma") {
+        (1.0, 0.95, 64)
+    } else if architecture.starts_with("mistral") || architecture.starts_with("mixtral") {
+        (0.7, 0.9, 40)
+    } else if architecture.starts_with("deepseek") {
+        (0.6, 0.95, 40)
+    } else if architecture.starts_with("phi") {
+        (0.7, 0.95, 40)
+    } else {
+        (0.8, 0.9, 40)
+    }
+}
+
+// The modelfile parameters arrive as "name value" lines, with stop strings quoted
+fn parse_modelfile_parameters(parameters: &str) -> Vec<(String, String)> {
+    parameters
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line.trim().split_once(char::is_whitespace)?;
+            let value = value.trim();
+            let value = serde_json::from_str::<String>(value).unwrap_or_else(|_| value.to_string());
+            Some((name.to_string(), value))
+        })
+        .collect()
+}
+
 #[tauri::command]
 pub async fn detect_ollama() -> Result<OllamaDetectionResult, String> {
     detect_ollama_installation().await
 }
 
 #[tauri::command]
-pub async fn discover_models() -> Result<ModelDiscoveryResult, String> {
-    discover_local_models().await
-}
\ No newline at end of file
+pub async fn discover_models(app: AppHandle) -> Result<ModelDiscoveryResult, String> {
+    let mut result = operations::run(OperationKind::ModelDiscovery, "Scanning for local models", |_| {
+        discover_local_models()
+    })
+    .await?;
+
+    // Flag favorites so the model picker can pin them
+    match get_favorite_models(&app) {
+        Ok(favorites) => {
+            for model in &mut result.models {
+                model.is_favorite = is_favorite(&favorites, &model.model_ref());
+            }
+        }
+        Err(e) => result.errors.push(format!("Failed to load favorite models: {}", e)),
+    }
+
+    Ok(result)
+}
+
+// The frontend only sees the display path, which is lossy for non-UTF-8 names, so map it back to the discovered file
+async fn resolve_model_file_path(path: &str) -> PathBuf {
+    if Path::new(path).exists() {
+        return PathBuf::from(path);
+    }
+    discover_local_models()
+        .await
+        .ok()
+        .and_then(|result| result.models.into_iter().find(|model| model.path == path))
+        .map(|model| model.file_path)
+        .unwrap_or_else(|| PathBuf::from(path))
+}
+
+#[tauri::command]
+pub async fn hash_model(path: String) -> Result<String, String> {
+    let file_path = resolve_model_file_path(&path).await;
+    // Hashing large models takes a while, keep it off the async runtime threads
+    tokio::task::spawn_blocking(move || hash_model_file(&file_path))
+        .await
+        .map_err(|e| format!("Hash task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn load_ollama_model(app: AppHandle, model: String, preset: Option<String>) -> Result<u64, String> {
+    let preset = preset_options(&app, preset.as_deref())?;
+    let result = operations::run(OperationKind::ModelLoad, format!("Loading {}", model), |_| {
+        load_model_with_progress(&app, &model, &preset)
+    })
+    .await;
+    if let Err(ref error) = result {
+        emit_load_progress(&app, OllamaLoadProgress::Error { model, error: error.clone() });
+    }
+    result
+}
+
+// Load a model ahead of the first message so the user doesn't wait on a cold start
+#[tauri::command]
+pub async fn preload_model(app: AppHandle, model: String, preset: Option<String>) -> Result<ModelPreload, String> {
+    let has_preset = preset.is_some();
+    let preset = preset_options(&app, preset.as_deref())?;
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    // Ollama reloads a resident model when the options change, so only skip the load without a preset
+    if !has_preset && is_model_running(&list_running_models(&client).await?, &model) {
+        return Ok(ModelPreload { model, already_loaded: true, load_duration_ms: 0 });
+    }
+
+    let result = operations::run(OperationKind::ModelLoad, format!("Preloading {}", model), |_| {
+        load_model_with_progress(&app, &model, &preset)
+    })
+    .await;
+    let load_duration_ms = match result {
+        Ok(load_duration_ms) => load_duration_ms,
+        Err(error) => {
+            emit_load_progress(&app, OllamaLoadProgress::Error { model, error: error.clone() });
+            return Err(error);
+        }
+    };
+
+    // The load request finishing means Ollama answered; /api/ps confirms the model actually stayed resident
+    if !is_model_running(&list_running_models(&client).await?, &model) {
+        return Err(format!(
+            "{} loaded but was unloaded again immediately; check the keep-alive setting or free up memory",
+            model
+        ));
+    }
+
+    Ok(ModelPreload { model, already_loaded: false, load_duration_ms })
+}
+
+#[tauri::command]
+pub async fn sanity_check_model(app: AppHandle, model: String) -> Result<ModelSanityCheck, String> {
+    Ok(run_model_sanity_check(&app, &model).await)
+}
+
+#[tauri::command]
+pub async fn test_tool_support(app: AppHandle, model: String) -> Result<bool, String> {
+    if let Some(supported) = TOOL_SUPPORT_CACHE.lock().ok().and_then(|cache| cache.get(&model).copied()) {
+        return Ok(supported);
+    }
+
+    // Servers older than tool calling support reject the field outright; that isn't a property of the model
+    if !get_ollama_capabilities().await.tool_calling {
+        return Ok(false);
+    }
+
+    let supported = probe_tool_support(&app, &model).await?;
+    if let Ok(mut cache) = TOOL_SUPPORT_CACHE.lock() {
+        cache.insert(model, supported);
+    }
+    Ok(supported)
+}
+
+#[tauri::command]
+pub async fn get_recommended_params(model: String) -> Result<RecommendedParams, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let show = show_ollama_model(&client, &model).await?;
+
+    let model_info = show.get("model_info").and_then(|info| info.as_object());
+    let architecture = model_info
+        .and_then(|info| info.get("general.architecture"))
+        .and_then(|architecture| architecture.as_str())
+        .map(|architecture| architecture.to_string());
+    let trained_context = trained_context_length(&show);
+
+    let (temperature, top_p, top_k) = architecture_defaults(architecture.as_deref());
+    let mut params = RecommendedParams {
+        temperature,
+        top_p,
+        top_k,
+        stop: Vec::new(),
+        num_ctx: trained_context.map(|context| context.min(MAX_RECOMMENDED_NUM_CTX)).unwrap_or(DEFAULT_NUM_CTX),
+        architecture,
+        from_model: Vec::new(),
+    };
+
+    let parameters = show.get("parameters").and_then(|parameters| parameters.as_str()).unwrap_or_default();
+    for (name, value) in parse_modelfile_parameters(parameters) {
+        let applied = match name.as_str() {
+            "temperature" => value.parse().map(|value| params.temperature = value).is_ok(),
+            "top_p" => value.parse().map(|value| params.top_p = value).is_ok(),
+            "top_k" => value.parse().map(|value| params.top_k = value).is_ok(),
+            "num_ctx" => value.parse().map(|value| params.num_ctx = value).is_ok(),
+            "stop" => {
+                params.stop.push(value);
+                true
+            }
+            _ => false,
+        };
+        if applied && !params.from_model.contains(&name) {
+            params.from_model.push(name);
+        }
+    }
+
+    Ok(params)
+}
+
+#[tauri::command]
+pub async fn diagnose_ollama_install() -> Result<OllamaInstallDiagnosis, String> {
+    let installations = tokio::task::spawn_blocking(describe_installations)
+        .await
+        .map_err(|e| format!("Failed to inspect Ollama installations: {}", e))?;
+    let server_version = get_server_version().await;
+
+    let path_installation = installations.iter().find(|installation| installation.on_path);
+    let mut warnings = Vec::new();
+    if installations.len() > 1 {
+        warnings.push(format!(
+            "Found {} Ollama installations: {}",
+            installations.len(),
+            installations
+                .iter()
+                .map(|installation| format!(
+                    "{} ({}, {})",
+                    installation.path,
+                    installation.install_method,
+                    installation.version.as_deref().unwrap_or("unknown version")
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    if path_installation.is_none() && !installations.is_empty() {
+        warnings.push("No Ollama binary is on PATH, so `ollama` in a terminal won't find the installed one".to_string());
+    }
+
+    let mut versions: Vec<&str> = installations.iter().filter_map(|installation| installation.version.as_deref()).collect();
+    versions.sort_unstable();
+    versions.dedup();
+    if versions.len() > 1 {
+        warnings.push(format!("Installed Ollama versions differ: {}", versions.join(", ")));
+    }
+
+    if let (Some(server), Some(path_version)) = (
+        server_version.as_deref(),
+        path_installation.and_then(|installation| installation.version.as_deref()),
+    ) {
+        if server != path_version {
+            warnings.push(format!(
+                "The running Ollama server is version {} but `ollama` on PATH is {}; the server was started from a different installation",
+                server, path_version
+            ));
+        }
+    }
+
+    let shares_models_directory = installations
+        .windows(2)
+        .all(|pair| pair[0].models_directory == pair[1].models_directory);
+    if !shares_models_directory {
+        warnings.push("Installations keep models in different directories, so each one sees a different set of models".to_string());
+    }
+
+    Ok(OllamaInstallDiagnosis {
+        path_binary: path_installation.map(|installation| installation.path.clone()),
+        installations,
+        server_version,
+        shares_models_directory,
+        warnings,
+    })
+}
+
+#[tauri::command]
+pub async fn is_ollama_managed_by_app() -> Result<bool, String> {
+    Ok(is_app_started_ollama_running())
+}
+
+#[tauri::command]
+pub async fn ollama_capabilities() -> Result<OllamaCapabilities, String> {
+    Ok(get_ollama_capabilities().await)
+}
+
+#[tauri::command]
+pub async fn set_ollama_keep_alive(app: AppHandle, duration: String) -> Result<(), String> {
+    parse_keep_alive(&duration)?;
+    set_setting(&app, KEEP_ALIVE_KEY, &duration.trim())
+}
+
+#[tauri::command]
+pub async fn set_ollama_env_overrides(
+    app: AppHandle,
+    overrides: BTreeMap<String, String>,
+) -> Result<OllamaEnvOverrides, String> {
+    let mut saved = BTreeMap::new();
+    let mut warnings = Vec::new();
+
+    for (name, value) in overrides {
+        let name = name.trim().to_string();
+        let value = value.trim().to_string();
+        if name.is_empty() || name.contains(['=', '\0']) || value.contains('\0') {
+            return Err(format!("'{}' is not a valid environment variable", name));
+        }
+        // An empty value removes the override
+        if value.is_empty() {
+            continue;
+        }
+
+        match validate_ollama_env(&name, &value) {
+            Some(result) => result?,
+            None => warnings.push(format!("{} is not a setting Open Chat recognizes; it will be passed to Ollama unchanged", name)),
+        }
+        saved.insert(name, value);
+    }
+
+    set_setting(&app, ENV_OVERRIDES_KEY, &saved)?;
+
+    let active = if is_app_started_ollama_running() {
+        ACTIVE_ENV_OVERRIDES.lock().ok().and_then(|active| active.clone())
+    } else {
+        None
+    };
+    let restart_required = active.as_ref().is_some_and(|active| *active != saved);
+
+    Ok(OllamaEnvOverrides {
+        saved,
+        active,
+        restart_required,
+        warnings,
+    })
+}
+
+fn base_model_name(tag: &str) -> &str {
+    tag.split(':').next().unwrap_or(tag)
+}
+
+fn list_candidates(candidates: &[&InstalledTag]) -> String {
+    candidates.iter().map(|tag| tag.name.as_str()).collect::<Vec<_>>().join(", ")
+}
+
+// Exact tag, then `:latest`, then the largest installed variant that fits in memory
+pub fn pick_model_tag(input: &str, installed: &[InstalledTag], available_memory_gb: f64) -> Result<String, String> {
+    if let Some(tag) = installed.iter().find(|tag| tag.name == input) {
+        return Ok(tag.name.clone());
+    }
+
+    let candidates: Vec<&InstalledTag> = if input.contains(':') {
+        // A partial tag such as "llama3:8b" matches "llama3:8b-instruct-q4_K_M"
+        installed.iter().filter(|tag| tag.name.starts_with(input)).collect()
+    } else {
+        let same_base: Vec<&InstalledTag> = installed.iter().filter(|tag| base_model_name(&tag.name) == input).collect();
+        if same_base.is_empty() {
+            // Fall back to a prefix of the model name, but only when it points at a single model
+            let prefixed: Vec<&InstalledTag> = installed.iter().filter(|tag| base_model_name(&tag.name).starts_with(input)).collect();
+            let bases: std::collections::BTreeSet<&str> = prefixed.iter().map(|tag| base_model_name(&tag.name)).collect();
+            if bases.len() > 1 {
+                return Err(format!("'{}' matches several models: {}", input, list_candidates(&prefixed)));
+            }
+            prefixed
+        } else {
+            same_base
+        }
+    };
+
+    if candidates.is_empty() {
+        return Err(format!("No installed model matches '{}'", input));
+    }
+
+    if let Some(latest) = candidates.iter().find(|tag| tag.name.ends_with(":latest")) {
+        return Ok(latest.name.clone());
+    }
+    if let [only] = candidates.as_slice() {
+        return Ok(only.name.clone());
+    }
+
+    candidates
+        .iter()
+        .filter(|tag| estimate_model_memory_requirements(tag.size, &tag.name) <= available_memory_gb)
+        .max_by_key(|tag| tag.size)
+        .map(|tag| tag.name.clone())
+        .ok_or_else(|| format!(
+            "None of the installed variants of '{}' fit in the {:.1}GB of available memory: {}",
+            input,
+            available_memory_gb,
+            list_candidates(&candidates)
+        ))
+}
+
+#[tauri::command]
+pub async fn resolve_model_alias(input: String) -> Result<String, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("Model name cannot be empty".to_string());
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let installed = match list_installed_tags(&client).await {
+        Ok(installed) => installed,
+        Err(e) => {
+            eprintln!("Warning: Resolving '{}' from Ollama's manifests instead: {}", input, e);
+            tokio::task::spawn_blocking(read_manifest_tags)
+                .await
+                .map_err(|e| format!("Failed to read installed models: {}", e))?
+        }
+    };
+
+    // Same 2GB system reserve as the compatibility check; unknown memory shouldn't block resolution
+    let available_memory_gb = match get_system_resources().await {
+        Ok(resources) => resources.available_memory_gb - 2.0,
+        Err(e) => {
+            eprintln!("Warning: {}", e);
+            f64::MAX
+        }
+    };
+
+    pick_model_tag(input, &installed, available_memory_gb)
+}
+
+#[tauri::command]
+pub async fn check_ollama_update() -> Result<OllamaUpdateInfo, String> {
+    let current = detect_installed_version().await;
+
+    let release = match fetch_latest_ollama_release().await {
+        Ok(release) => release,
+        Err(e) => {
+            // Offline is expected; just report what's installed
+            eprintln!("Warning: Could not check for Ollama updates: {}", e);
+            return Ok(OllamaUpdateInfo {
+                current,
+                latest: None,
+                update_available: false,
+                release_url: None,
+            });
+        }
+    };
+
+    let latest = release.tag_name.trim_start_matches('v').to_string();
+    let update_available = match (
+        current.as_deref().and_then(OllamaVersion::parse),
+        OllamaVersion::parse(&latest),
+    ) {
+        (Some(current), Some(latest)) => latest > current,
+        _ => false,
+    };
+
+    Ok(OllamaUpdateInfo {
+        current,
+        latest: Some(latest),
+        update_available,
+        release_url: Some(release.html_url),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capabilities(version: &str) -> OllamaCapabilities {
+        OllamaCapabilities::for_version(OllamaVersion::parse(version))
+    }
+
+    #[test]
+    fn parses_release_and_prefixed_versions() {
+        assert_eq!(OllamaVersion::parse("0.1.32"), Some(OllamaVersion::new(0, 1, 32)));
+        assert_eq!(OllamaVersion::parse("v0.5.7"), Some(OllamaVersion::new(0, 5, 7)));
+        assert_eq!(OllamaVersion::parse(" 0.3\n"), Some(OllamaVersion::new(0, 3, 0)));
+    }
+
+    #[test]
+    fn pre_release_versions_parse_as_their_release() {
+        assert_eq!(OllamaVersion::parse("0.6.0-rc1"), Some(OllamaVersion::new(0, 6, 0)));
+        assert_eq!(OllamaVersion::parse("0.5.0+build.3"), Some(OllamaVersion::new(0, 5, 0)));
+        assert!(capabilities("0.5.0-rc2").structured_outputs);
+    }
+
+    #[test]
+    fn unparsable_versions_are_rejected() {
+        for version in ["", "abc", "0.x.1", "ollama version 0.5.0", "-1.0.0"] {
+            assert_eq!(OllamaVersion::parse(version), None, "{:?}", version);
+        }
+    }
+
+    #[test]
+    fn each_capability_starts_at_its_minimum_version() {
+        assert!(!capabilities("0.1.22").keep_alive);
+        assert!(capabilities("0.1.23").keep_alive);
+
+        assert!(!capabilities("0.1.37").process_status);
+        assert!(capabilities("0.1.38").process_status);
+
+        let before_embed = capabilities("0.2.99");
+        assert!(!before_embed.embed);
+        assert_eq!(before_embed.embed_endpoint, "/api/embeddings");
+        assert!(!before_embed.tool_calling);
+        let with_embed = capabilities("0.3.0");
+        assert!(with_embed.embed);
+        assert_eq!(with_embed.embed_endpoint, "/api/embed");
+        assert!(with_embed.tool_calling);
+
+        assert!(!capabilities("0.4.7").structured_outputs);
+        assert!(capabilities("0.5.0").structured_outputs);
+
+        assert_eq!(capabilities("0.6.3").default_num_ctx, 2048);
+        assert_eq!(capabilities("0.6.4").default_num_ctx, 4096);
+    }
+
+    #[test]
+    fn warns_below_the_recommended_version() {
+        assert!(capabilities("0.2.8").warning.is_some());
+        assert!(capabilities("0.3.0").warning.is_none());
+        assert!(capabilities("1.0.0").warning.is_none());
+    }
+
+    #[test]
+    fn unknown_version_assumes_a_current_release() {
+        let unknown = capabilities("not a version");
+        assert_eq!(unknown.version, None);
+        assert!(unknown.keep_alive && unknown.process_status && unknown.embed);
+        assert!(unknown.tool_calling && unknown.structured_outputs);
+        assert!(unknown.warning.is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8_model_path_is_kept_for_file_operations() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = std::env::temp_dir().join(format!("open-chat-model-path-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(OsStr::from_bytes(b"model-\xff.gguf"));
+        fs::write(&path, b"GGUF").unwrap();
+
+        let model = try_parse_model_file(&path, &ModelSource::Other).expect("model file should be discovered");
+        assert_eq!(model.file_path, path);
+        assert_ne!(Path::new(&model.path), path.as_path());
+        assert_eq!(model.size_bytes, 4);
+        assert!(hash_model_file(&model.file_path).is_ok());
+        assert!(hash_model_file(Path::new(&model.path)).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
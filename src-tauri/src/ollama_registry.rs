@@ -0,0 +1,199 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::system_info::{estimate_model_memory_requirements, get_system_resources};
+
+const OLLAMA_REGISTRY_URL: &str = "https://registry.ollama.ai/v2";
+const MANIFEST_ACCEPT: &str = "application/vnd.docker.distribution.manifest.v2+json";
+
+// Popular models have dozens of tags; don't open a connection for every one at once
+const MAX_CONCURRENT_MANIFEST_REQUESTS: usize = 8;
+
+// Same headroom validate_model_compatibility leaves for the OS
+const MEMORY_BUFFER_GB: f64 = 2.0;
+// The display and other apps keep some VRAM busy even when nothing else is running on the GPU
+const VRAM_BUFFER_GB: f64 = 0.5;
+
+static QUANTIZATION_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:^|[-.:])(iq\d[a-z0-9_]*|q\d(?:_[a-z0-9]+)*|fp16|f16|bf16|fp32)$").unwrap()
+});
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryTag {
+    pub tag: String,
+    pub size_bytes: u64,
+    pub quantization: Option<String>,
+    pub estimated_memory_gb: f64,
+    pub fits_in_memory: bool,
+    // Whether the whole model fits on the primary GPU, which runs much faster than a CPU split
+    pub fits_in_vram: bool,
+    // The largest tag that fits, preferring VRAM; at most one tag is recommended
+    pub recommended: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagList {
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestLayer {
+    size: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    config: ManifestLayer,
+    #[serde(default)]
+    layers: Vec<ManifestLayer>,
+}
+
+// "llama3.1" -> "library/llama3.1", "user/model:tag" -> "user/model"
+fn registry_repository(model: &str) -> String {
+    let name = model.split(':').next().unwrap_or(model).trim();
+    if name.contains('/') {
+        name.to_string()
+    } else {
+        format!("library/{}", name)
+    }
+}
+
+pub fn parse_quantization(tag: &str) -> Option<String> {
+    QUANTIZATION_PATTERN
+        .captures(tag)
+        .and_then(|captures| captures.get(1))
+        .map(|m| m.as_str().to_string())
+}
+
+async fn fetch_tag_size(client: &reqwest::Client, repository: &str, tag: &str) -> Result<u64, String> {
+    let manifest: Manifest = client
+        .get(format!("{}/{}/manifests/{}", OLLAMA_REGISTRY_URL, repository, tag))
+        .header("Accept", MANIFEST_ACCEPT)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch manifest for {}: {}", tag, e))?
+        .error_for_status()
+        .map_err(|e| format!("Registry rejected manifest request for {}: {}", tag, e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid manifest for {}: {}", tag, e))?;
+
+    Ok(manifest.config.size + manifest.layers.iter().map(|layer| layer.size).sum::<u64>())
+}
+
+async fn fetch_tag_list(client: &reqwest::Client, repository: &str, model: &str) -> Result<Vec<String>, String> {
+    let response = client
+        .get(format!("{}/{}/tags/list", OLLAMA_REGISTRY_URL, repository))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach the Ollama registry: {}", e))?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(format!("Model '{}' was not found in the Ollama registry", model));
+    }
+
+    let tag_list: TagList = response
+        .error_for_status()
+        .map_err(|e| format!("Ollama registry returned an error: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid tag list from the Ollama registry: {}", e))?;
+    Ok(tag_list.tags)
+}
+
+pub async fn fetch_registry_tags(model: &str) -> Result<Vec<(String, u64)>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let repository = registry_repository(model);
+
+    // tags/list isn't served for every repository, so fall back to the one tag that was asked for
+    let tag_list = match fetch_tag_list(&client, &repository, model).await {
+        Ok(tags) => tags,
+        Err(list_error) => {
+            let tag = model.split_once(':').map(|(_, tag)| tag.trim()).unwrap_or("latest");
+            return match fetch_tag_size(&client, &repository, tag).await {
+                Ok(size) => {
+                    eprintln!("Warning: {}; only showing the {} tag", list_error, tag);
+                    Ok(vec![(tag.to_string(), size)])
+                }
+                Err(_) => Err(list_error),
+            };
+        }
+    };
+
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_MANIFEST_REQUESTS));
+    let mut requests = JoinSet::new();
+    for tag in tag_list {
+        let client = client.clone();
+        let repository = repository.clone();
+        let semaphore = semaphore.clone();
+        requests.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let size = fetch_tag_size(&client, &repository, &tag).await;
+            (tag, size)
+        });
+    }
+
+    let mut tags = Vec::new();
+    while let Some(result) = requests.join_next().await {
+        match result {
+            Ok((tag, Ok(size))) => tags.push((tag, size)),
+            // One bad manifest shouldn't hide every other tag
+            Ok((tag, Err(e))) => eprintln!("Warning: Skipping registry tag {}: {}", tag, e),
+            Err(e) => eprintln!("Warning: Registry manifest task failed: {}", e),
+        }
+    }
+
+    Ok(tags)
+}
+
+#[tauri::command]
+pub async fn list_model_tags(model: String) -> Result<Vec<RegistryTag>, String> {
+    let tags = fetch_registry_tags(&model).await?;
+    let resources = get_system_resources().await?;
+    let usable_memory_gb = resources.available_memory_gb - MEMORY_BUFFER_GB;
+    let usable_vram_gb = resources.primary_gpu_vram_gb.map(|vram_gb| vram_gb - VRAM_BUFFER_GB);
+    let model_name = model.split(':').next().unwrap_or(&model);
+
+    let mut tags: Vec<RegistryTag> = tags
+        .into_iter()
+        .map(|(tag, size_bytes)| {
+            // Tags usually carry the parameter count ("8b-instruct-q4_K_M"), so estimate with both names
+            let estimated_memory_gb = estimate_model_memory_requirements(size_bytes, &format!("{}:{}", model_name, tag));
+            RegistryTag {
+                quantization: parse_quantization(&tag),
+                fits_in_memory: estimated_memory_gb <= usable_memory_gb,
+                fits_in_vram: usable_vram_gb.is_some_and(|vram_gb| estimated_memory_gb <= vram_gb),
+                tag,
+                size_bytes,
+                estimated_memory_gb,
+                recommended: false,
+            }
+        })
+        .collect();
+
+    // Largest first, since bigger quants of the same model are better when they fit
+    tags.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then_with(|| a.tag.cmp(&b.tag)));
+
+    // Fully on the GPU beats a bigger quant split across RAM; "latest" is an alias, so prefer the descriptive name
+    let best = [
+        |tag: &RegistryTag| tag.fits_in_vram && tag.tag != "latest",
+        |tag: &RegistryTag| tag.fits_in_vram,
+        |tag: &RegistryTag| tag.fits_in_memory && tag.tag != "latest",
+        |tag: &RegistryTag| tag.fits_in_memory,
+    ]
+    .iter()
+    .find_map(|fits| tags.iter().position(fits));
+    if let Some(best) = best {
+        tags[best].recommended = true;
+    }
+
+    Ok(tags)
+}
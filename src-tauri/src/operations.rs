@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::Notify;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationKind {
+    ModelLoad,
+    ModelDiscovery,
+    Comparison,
+    Summarization,
+    Benchmark,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operation {
+    pub id: u64,
+    pub kind: OperationKind,
+    pub label: String,
+    pub started_at_ms: u64,
+    // Fraction between 0 and 1; None when the operation can't estimate how far along it is
+    pub progress: Option<f64>,
+}
+
+struct RegisteredOperation {
+    operation: Operation,
+    cancel: Arc<Notify>,
+}
+
+static NEXT_OPERATION_ID: AtomicU64 = AtomicU64::new(1);
+static OPERATIONS: Mutex<BTreeMap<u64, RegisteredOperation>> = Mutex::new(BTreeMap::new());
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// Removes the operation when dropped, so it leaves the registry even if the caller's future is dropped or panics
+struct Registration(u64);
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        if let Ok(mut operations) = OPERATIONS.lock() {
+            operations.remove(&self.0);
+        }
+    }
+}
+
+pub fn set_progress(id: u64, progress: f64) {
+    if let Ok(mut operations) = OPERATIONS.lock() {
+        if let Some(registered) = operations.get_mut(&id) {
+            registered.operation.progress = Some(progress.clamp(0.0, 1.0));
+        }
+    }
+}
+
+// Run a long-running task as a registered operation. The task receives its operation id for progress
+// updates and is dropped at its next await point if the operation is cancelled.
+pub async fn run<T, F, Fut>(kind: OperationKind, label: impl Into<String>, task: F) -> Result<T, String>
+where
+    F: FnOnce(u64) -> Fut,
+    Fut: Future<Output = Result<T, String>>,
+{
+    let id = NEXT_OPERATION_ID.fetch_add(1, Ordering::Relaxed);
+    let label = label.into();
+    let cancel = Arc::new(Notify::new());
+
+    OPERATIONS
+        .lock()
+        .map_err(|e| format!("Failed to register operation: {}", e))?
+        .insert(
+            id,
+            RegisteredOperation {
+                operation: Operation {
+                    id,
+                    kind,
+                    label: label.clone(),
+                    started_at_ms: now_ms(),
+                    progress: None,
+                },
+                cancel: cancel.clone(),
+            },
+        );
+    let _registration = Registration(id);
+
+    tokio::select! {
+        result = task(id) => result,
+        _ = cancel.notified() => Err(format!("{} was cancelled", label)),
+    }
+}
+
+#[tauri::command]
+pub async fn list_active_operations() -> Result<Vec<Operation>, String> {
+    let operations = OPERATIONS
+        .lock()
+        .map_err(|e| format!("Failed to read active operations: {}", e))?;
+    Ok(operations.values().map(|registered| registered.operation.clone()).collect())
+}
+
+#[tauri::command]
+pub async fn cancel_operation(id: u64) -> Result<(), String> {
+    let operations = OPERATIONS
+        .lock()
+        .map_err(|e| format!("Failed to read active operations: {}", e))?;
+    let registered = operations
+        .get(&id)
+        .ok_or_else(|| format!("Operation {} is not running", id))?;
+
+    // notify_one stores a permit, so a cancel that lands before the task starts waiting still counts
+    registered.cancel.notify_one();
+    Ok(())
+}
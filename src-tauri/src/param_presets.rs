@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::AppHandle;
+
+use crate::settings::{get_setting, set_setting};
+
+const PARAM_PRESETS_KEY: &str = "paramPresets";
+
+// Unset fields leave the model's own default in place
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParamPreset {
+    pub temperature: Option<f64>,
+    pub top_p: Option<f64>,
+    pub top_k: Option<u64>,
+    pub num_ctx: Option<u64>,
+    pub stop: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedParamPreset {
+    pub name: String,
+    pub params: ParamPreset,
+}
+
+impl ParamPreset {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(temperature) = self.temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                return Err(format!("temperature must be between 0 and 2, got {}", temperature));
+            }
+        }
+        if let Some(top_p) = self.top_p {
+            if !(0.0..=1.0).contains(&top_p) {
+                return Err(format!("top_p must be between 0 and 1, got {}", top_p));
+            }
+        }
+        if self.top_k == Some(0) {
+            return Err("top_k must be at least 1".to_string());
+        }
+        if self.num_ctx == Some(0) {
+            return Err("num_ctx must be at least 1".to_string());
+        }
+        if self.stop.as_ref().is_some_and(|stop| stop.iter().any(|sequence| sequence.is_empty())) {
+            return Err("Stop sequences cannot be empty".to_string());
+        }
+        Ok(())
+    }
+
+    // The preset as an Ollama "options" object, with unset fields left out
+    pub fn to_ollama_options(&self) -> serde_json::Value {
+        let mut options = serde_json::Map::new();
+        if let Some(temperature) = self.temperature {
+            options.insert("temperature".to_string(), temperature.into());
+        }
+        if let Some(top_p) = self.top_p {
+            options.insert("top_p".to_string(), top_p.into());
+        }
+        if let Some(top_k) = self.top_k {
+            options.insert("top_k".to_string(), top_k.into());
+        }
+        if let Some(num_ctx) = self.num_ctx {
+            options.insert("num_ctx".to_string(), num_ctx.into());
+        }
+        if let Some(stop) = &self.stop {
+            options.insert("stop".to_string(), stop.clone().into());
+        }
+        serde_json::Value::Object(options)
+    }
+}
+
+fn load_presets(app: &AppHandle) -> Result<BTreeMap<String, ParamPreset>, String> {
+    Ok(get_setting(app, PARAM_PRESETS_KEY)?.unwrap_or_default())
+}
+
+pub fn find_param_preset(app: &AppHandle, name: &str) -> Result<ParamPreset, String> {
+    load_presets(app)?
+        .remove(name.trim())
+        .ok_or_else(|| format!("There is no parameter preset named '{}'", name))
+}
+
+// Options for a request that may name a preset; no preset means no overrides
+pub fn preset_options(app: &AppHandle, preset: Option<&str>) -> Result<serde_json::Value, String> {
+    match preset {
+        Some(name) => Ok(find_param_preset(app, name)?.to_ollama_options()),
+        None => Ok(serde_json::json!({})),
+    }
+}
+
+// A command's own options with a preset's values (from preset_options) laid over them
+pub fn merge_preset_options(defaults: serde_json::Value, preset: &serde_json::Value) -> serde_json::Value {
+    let mut options = defaults;
+    if let (Some(options), Some(overrides)) = (options.as_object_mut(), preset.as_object()) {
+        options.extend(overrides.iter().map(|(key, value)| (key.clone(), value.clone())));
+    }
+    options
+}
+
+#[tauri::command]
+pub async fn save_param_preset(app: AppHandle, name: String, params: ParamPreset) -> Result<ParamPreset, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Preset name cannot be empty".to_string());
+    }
+    params.validate()?;
+
+    let mut presets = load_presets(&app)?;
+    presets.insert(name, params.clone());
+    set_setting(&app, PARAM_PRESETS_KEY, &presets)?;
+    Ok(params)
+}
+
+#[tauri::command]
+pub async fn list_param_presets(app: AppHandle) -> Result<Vec<NamedParamPreset>, String> {
+    Ok(load_presets(&app)?
+        .into_iter()
+        .map(|(name, params)| NamedParamPreset { name, params })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_param_preset(app: AppHandle, name: String) -> Result<ParamPreset, String> {
+    find_param_preset(&app, &name)
+}
+
+#[tauri::command]
+pub async fn delete_param_preset(app: AppHandle, name: String) -> Result<(), String> {
+    let mut presets = load_presets(&app)?;
+    if presets.remove(name.trim()).is_none() {
+        return Err(format!("There is no parameter preset named '{}'", name));
+    }
+    set_setting(&app, PARAM_PRESETS_KEY, &presets)
+}
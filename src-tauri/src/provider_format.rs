@@ -0,0 +1,563 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::messages::{MessageRole, ProviderKind};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ImageSource {
+    Base64 { media_type: String, data: String },
+    Url { url: String },
+}
+
+// Provider-neutral content; each provider's wire format is derived from this
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentBlock {
+    Text {
+        text: String,
+    },
+    Image {
+        source: ImageSource,
+    },
+    ToolCall {
+        id: String,
+        name: String,
+        arguments: Value,
+    },
+    ToolResult {
+        tool_call_id: String,
+        content: String,
+        #[serde(default)]
+        is_error: bool,
+    },
+    Thinking {
+        text: String,
+        // Anthropic signs thinking blocks and rejects edited or unsigned ones when they are sent back
+        #[serde(default)]
+        signature: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Message {
+    pub role: MessageRole,
+    pub content: Vec<ContentBlock>,
+}
+
+fn text_of(blocks: &[ContentBlock]) -> String {
+    blocks
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+// "data:image/png;base64,AAAA" -> (media type, data)
+fn parse_data_url(url: &str) -> Option<(String, String)> {
+    let (header, data) = url.strip_prefix("data:")?.split_once(',')?;
+    let media_type = header.strip_suffix(";base64")?;
+    Some((media_type.to_string(), data.to_string()))
+}
+
+fn openai_image(source: &ImageSource) -> Value {
+    let url = match source {
+        ImageSource::Base64 { media_type, data } => format!("data:{};base64,{}", media_type, data),
+        ImageSource::Url { url } => url.clone(),
+    };
+    json!({ "type": "image_url", "image_url": { "url": url } })
+}
+
+fn openai_tool_result(tool_call_id: &str, content: &str) -> Value {
+    json!({ "role": "tool", "tool_call_id": tool_call_id, "content": content })
+}
+
+// Plain strings where there is only text, since some OpenAI-compatible servers reject content arrays
+fn openai_content(blocks: &[&ContentBlock]) -> Value {
+    if blocks.iter().all(|block| matches!(block, ContentBlock::Text { .. })) {
+        let texts: Vec<&str> = blocks
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        return Value::String(texts.join("\n\n"));
+    }
+
+    Value::Array(
+        blocks
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text } => Some(json!({ "type": "text", "text": text })),
+                ContentBlock::Image { source } => Some(openai_image(source)),
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
+fn to_openai(messages: &[Message]) -> Value {
+    let mut converted = Vec::new();
+
+    for message in messages {
+        // Tool results are separate "tool" messages, whichever role carried them
+        for block in &message.content {
+            if let ContentBlock::ToolResult { tool_call_id, content, .. } = block {
+                converted.push(openai_tool_result(tool_call_id, content));
+            }
+        }
+
+        match message.role {
+            MessageRole::System => converted.push(json!({ "role": "system", "content": text_of(&message.content) })),
+            MessageRole::User => {
+                let blocks: Vec<&ContentBlock> = message
+                    .content
+                    .iter()
+                    .filter(|block| matches!(block, ContentBlock::Text { .. } | ContentBlock::Image { .. }))
+                    .collect();
+                if !blocks.is_empty() {
+                    converted.push(json!({ "role": "user", "content": openai_content(&blocks) }));
+                }
+            }
+            MessageRole::Assistant => {
+                let mut assistant = serde_json::Map::new();
+                assistant.insert("role".to_string(), json!("assistant"));
+
+                let text = text_of(&message.content);
+                assistant.insert("content".to_string(), if text.is_empty() { Value::Null } else { Value::String(text) });
+
+                // OpenAI has no thinking blocks; DeepSeek and OpenRouter accept reasoning_content instead
+                let thinking: Vec<&str> = message
+                    .content
+                    .iter()
+                    .filter_map(|block| match block {
+                        ContentBlock::Thinking { text, .. } => Some(text.as_str()),
+                        _ => None,
+                    })
+                    .collect();
+                if !thinking.is_empty() {
+                    assistant.insert("reasoning_content".to_string(), Value::String(thinking.join("\n\n")));
+                }
+
+                // Arguments travel as a JSON-encoded string
+                let tool_calls: Vec<Value> = message
+                    .content
+                    .iter()
+                    .filter_map(|block| match block {
+                        ContentBlock::ToolCall { id, name, arguments } => Some(json!({
+                            "id": id,
+                            "type": "function",
+                            "function": { "name": name, "arguments": arguments.to_string() }
+                        })),
+                        _ => None,
+                    })
+                    .collect();
+                if !tool_calls.is_empty() {
+                    assistant.insert("tool_calls".to_string(), Value::Array(tool_calls));
+                }
+
+                converted.push(Value::Object(assistant));
+            }
+            // Already emitted above
+            MessageRole::Tool => {}
+        }
+    }
+
+    json!({ "messages": converted })
+}
+
+fn anthropic_block(block: &ContentBlock) -> Value {
+    match block {
+        ContentBlock::Text { text } => json!({ "type": "text", "text": text }),
+        ContentBlock::Image { source: ImageSource::Base64 { media_type, data } } => json!({
+            "type": "image",
+            "source": { "type": "base64", "media_type": media_type, "data": data }
+        }),
+        ContentBlock::Image { source: ImageSource::Url { url } } => json!({
+            "type": "image",
+            "source": { "type": "url", "url": url }
+        }),
+        ContentBlock::ToolCall { id, name, arguments } => json!({
+            "type": "tool_use",
+            "id": id,
+            "name": name,
+            "input": arguments
+        }),
+        ContentBlock::ToolResult { tool_call_id, content, is_error } => json!({
+            "type": "tool_result",
+            "tool_use_id": tool_call_id,
+            "content": content,
+            "is_error": is_error
+        }),
+        ContentBlock::Thinking { text, signature } => json!({
+            "type": "thinking",
+            "thinking": text,
+            "signature": signature
+        }),
+    }
+}
+
+fn to_anthropic(messages: &[Message]) -> Value {
+    let mut system = Vec::new();
+    let mut converted: Vec<Value> = Vec::new();
+
+    for message in messages {
+        let role = match message.role {
+            MessageRole::System => {
+                system.push(text_of(&message.content));
+                continue;
+            }
+            MessageRole::Assistant => "assistant",
+            // Tool results go back to Anthropic inside a user turn
+            MessageRole::User | MessageRole::Tool => "user",
+        };
+
+        // Unsigned thinking (e.g. another provider's reasoning_content) would be rejected, so it's dropped
+        let mut blocks: Vec<&ContentBlock> = message
+            .content
+            .iter()
+            .filter(|block| !matches!(block, ContentBlock::Thinking { signature: None, .. }))
+            .collect();
+        // Tool results have to lead the user turn that answers the tool calls
+        if role == "user" {
+            blocks.sort_by_key(|block| !matches!(block, ContentBlock::ToolResult { .. }));
+        }
+        let blocks: Vec<Value> = blocks.into_iter().map(anthropic_block).collect();
+        if blocks.is_empty() {
+            continue;
+        }
+
+        // Roles have to alternate, so consecutive tool results and user text share one turn
+        match converted.last_mut() {
+            Some(last) if last["role"] == role && role == "user" => {
+                if let Some(content) = last["content"].as_array_mut() {
+                    content.extend(blocks);
+                }
+            }
+            _ => converted.push(json!({ "role": role, "content": blocks })),
+        }
+    }
+
+    let mut request = json!({ "messages": converted });
+    if !system.is_empty() {
+        request["system"] = Value::String(system.join("\n\n"));
+    }
+    request
+}
+
+pub fn to_provider_format(messages: &[Message], provider: ProviderKind) -> Value {
+    match provider {
+        ProviderKind::Anthropic => to_anthropic(messages),
+        ProviderKind::OpenAiCompatible => to_openai(messages),
+    }
+}
+
+fn string_field(value: &Value, field: &str) -> String {
+    value.get(field).and_then(Value::as_str).unwrap_or_default().to_string()
+}
+
+fn role_from_str(role: &str) -> Result<MessageRole, String> {
+    serde_json::from_value(Value::String(role.to_string())).map_err(|_| format!("Unknown message role '{}'", role))
+}
+
+fn openai_parts(content: &Value) -> Vec<ContentBlock> {
+    match content {
+        Value::String(text) if !text.is_empty() => vec![ContentBlock::Text { text: text.clone() }],
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| match part.get("type").and_then(Value::as_str) {
+                Some("text") => Some(ContentBlock::Text { text: string_field(part, "text") }),
+                Some("image_url") => {
+                    let url = part.pointer("/image_url/url").and_then(Value::as_str).unwrap_or_default();
+                    let source = match parse_data_url(url) {
+                        Some((media_type, data)) => ImageSource::Base64 { media_type, data },
+                        None => ImageSource::Url { url: url.to_string() },
+                    };
+                    Some(ContentBlock::Image { source })
+                }
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn message_from_openai(message: &Value) -> Result<Message, String> {
+    let role = role_from_str(message.get("role").and_then(Value::as_str).unwrap_or("assistant"))?;
+
+    if role == MessageRole::Tool {
+        return Ok(Message {
+            role,
+            content: vec![ContentBlock::ToolResult {
+                tool_call_id: string_field(message, "tool_call_id"),
+                content: string_field(message, "content"),
+                is_error: false,
+            }],
+        });
+    }
+
+    let mut content = Vec::new();
+    // DeepSeek calls it reasoning_content, OpenRouter reasoning
+    let reasoning = message
+        .get("reasoning_content")
+        .or_else(|| message.get("reasoning"))
+        .and_then(Value::as_str)
+        .filter(|reasoning| !reasoning.is_empty());
+    if let Some(reasoning) = reasoning {
+        content.push(ContentBlock::Thinking { text: reasoning.to_string(), signature: None });
+    }
+    content.extend(openai_parts(message.get("content").unwrap_or(&Value::Null)));
+
+    for call in message.get("tool_calls").and_then(Value::as_array).into_iter().flatten() {
+        let function = call.get("function").unwrap_or(&Value::Null);
+        let raw_arguments = string_field(function, "arguments");
+        // Models sometimes emit invalid JSON; keep it as a string rather than losing it
+        let arguments = serde_json::from_str(&raw_arguments).unwrap_or(Value::String(raw_arguments));
+        content.push(ContentBlock::ToolCall {
+            id: string_field(call, "id"),
+            name: string_field(function, "name"),
+            arguments,
+        });
+    }
+
+    Ok(Message { role, content })
+}
+
+fn anthropic_tool_result_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|block| block.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        _ => String::new(),
+    }
+}
+
+fn block_from_anthropic(block: &Value) -> Option<ContentBlock> {
+    match block.get("type").and_then(Value::as_str)? {
+        "text" => Some(ContentBlock::Text { text: string_field(block, "text") }),
+        "image" => {
+            let source = block.get("source")?;
+            let source = match source.get("type").and_then(Value::as_str)? {
+                "base64" => ImageSource::Base64 {
+                    media_type: string_field(source, "media_type"),
+                    data: string_field(source, "data"),
+                },
+                "url" => ImageSource::Url { url: string_field(source, "url") },
+                _ => return None,
+            };
+            Some(ContentBlock::Image { source })
+        }
+        "tool_use" => Some(ContentBlock::ToolCall {
+            id: string_field(block, "id"),
+            name: string_field(block, "name"),
+            arguments: block.get("input").cloned().unwrap_or_else(|| json!({})),
+        }),
+        "tool_result" => Some(ContentBlock::ToolResult {
+            tool_call_id: string_field(block, "tool_use_id"),
+            content: anthropic_tool_result_text(block.get("content").unwrap_or(&Value::Null)),
+            is_error: block.get("is_error").and_then(Value::as_bool).unwrap_or(false),
+        }),
+        "thinking" => Some(ContentBlock::Thinking {
+            text: string_field(block, "thinking"),
+            signature: block.get("signature").and_then(Value::as_str).map(|signature| signature.to_string()),
+        }),
+        // Redacted thinking is encrypted and can't be represented as text
+        _ => None,
+    }
+}
+
+fn message_from_anthropic(message: &Value) -> Result<Message, String> {
+    let role = role_from_str(message.get("role").and_then(Value::as_str).unwrap_or("assistant"))?;
+    let content = match message.get("content") {
+        Some(Value::String(text)) => vec![ContentBlock::Text { text: text.clone() }],
+        Some(Value::Array(blocks)) => blocks.iter().filter_map(block_from_anthropic).collect(),
+        _ => Vec::new(),
+    };
+    Ok(Message { role, content })
+}
+
+// Read the assistant reply out of a (non-streaming) chat completion or messages response
+pub fn from_provider_response(response: &Value, provider: ProviderKind) -> Result<Message, String> {
+    if let Some(error) = response.pointer("/error/message").and_then(Value::as_str) {
+        return Err(format!("Provider returned an error: {}", error));
+    }
+
+    match provider {
+        ProviderKind::Anthropic => {
+            if response.get("content").is_none() {
+                return Err("Response has no content".to_string());
+            }
+            message_from_anthropic(response)
+        }
+        ProviderKind::OpenAiCompatible => {
+            let message = response
+                .pointer("/choices/0/message")
+                .ok_or_else(|| "Response has no choices".to_string())?;
+            message_from_openai(message)
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn convert_to_provider_format(messages: Vec<Message>, provider: String) -> Result<Value, String> {
+    Ok(to_provider_format(&messages, ProviderKind::from_provider(&provider)))
+}
+
+#[tauri::command]
+pub async fn convert_provider_response(response: Value, provider: String) -> Result<Message, String> {
+    from_provider_response(&response, ProviderKind::from_provider(&provider))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str) -> ContentBlock {
+        ContentBlock::Text { text: text.to_string() }
+    }
+
+    fn conversation() -> Vec<Message> {
+        vec![
+            Message {
+                role: MessageRole::User,
+                content: vec![
+                    text("What's in this picture, and what's the weather there?"),
+                    ContentBlock::Image {
+                        source: ImageSource::Base64 { media_type: "image/png".to_string(), data: "iVBORw0KGgo=".to_string() },
+                    },
+                ],
+            },
+            Message {
+                role: MessageRole::Assistant,
+                content: vec![
+                    ContentBlock::Thinking { text: "The picture shows Paris.".to_string(), signature: None },
+                    text("That's the Eiffel Tower. Let me check the weather."),
+                    ContentBlock::ToolCall {
+                        id: "call_1".to_string(),
+                        name: "get_weather".to_string(),
+                        arguments: json!({ "city": "Paris" }),
+                    },
+                ],
+            },
+            Message {
+                role: MessageRole::Tool,
+                content: vec![ContentBlock::ToolResult {
+                    tool_call_id: "call_1".to_string(),
+                    content: "18°C and sunny".to_string(),
+                    is_error: false,
+                }],
+            },
+            Message {
+                role: MessageRole::Assistant,
+                content: vec![text("It's 18°C and sunny in Paris.")],
+            },
+        ]
+    }
+
+    #[test]
+    fn openai_round_trip_preserves_every_block() {
+        let request = to_provider_format(&conversation(), ProviderKind::OpenAiCompatible);
+        let messages = request["messages"].as_array().unwrap();
+        assert_eq!(messages[1]["tool_calls"][0]["function"]["arguments"], json!("{\"city\":\"Paris\"}"));
+        assert_eq!(messages[2]["role"], "tool");
+
+        let round_tripped: Vec<Message> = messages.iter().map(|message| message_from_openai(message).unwrap()).collect();
+        assert_eq!(round_tripped, conversation());
+    }
+
+    #[test]
+    fn anthropic_round_trip_preserves_every_block() {
+        let mut conversation = conversation();
+        // Anthropic carries thinking signatures and tool errors, which OpenAI has no place for
+        conversation[1].content[0] = ContentBlock::Thinking {
+            text: "The picture shows Paris.".to_string(),
+            signature: Some("sig".to_string()),
+        };
+        if let ContentBlock::ToolResult { is_error, .. } = &mut conversation[2].content[0] {
+            *is_error = true;
+        }
+
+        let request = to_provider_format(&conversation, ProviderKind::Anthropic);
+        let messages = request["messages"].as_array().unwrap();
+        assert_eq!(messages[2]["role"], "user");
+        assert_eq!(messages[2]["content"][0]["type"], "tool_result");
+
+        let round_tripped: Vec<Message> = messages.iter().map(|message| message_from_anthropic(message).unwrap()).collect();
+        // The tool result comes back in a user turn, which is how Anthropic represents it
+        conversation[2].role = MessageRole::User;
+        assert_eq!(round_tripped, conversation);
+    }
+
+    #[test]
+    fn unsigned_thinking_is_dropped_for_anthropic() {
+        // The conversation's thinking block came from an OpenAI-compatible reasoning_content field
+        let request = to_provider_format(&conversation(), ProviderKind::Anthropic);
+        let messages = request["messages"].as_array().unwrap();
+        assert!(messages[1]["content"].as_array().unwrap().iter().all(|block| block["type"] != "thinking"));
+
+        let round_tripped: Vec<Message> = messages.iter().map(|message| message_from_anthropic(message).unwrap()).collect();
+        let mut expected = conversation();
+        expected[1].content.remove(0);
+        expected[2].role = MessageRole::User;
+        assert_eq!(round_tripped, expected);
+    }
+
+    #[test]
+    fn system_messages_move_to_the_top_level_for_anthropic() {
+        let messages = vec![
+            Message { role: MessageRole::System, content: vec![text("Be brief.")] },
+            Message { role: MessageRole::User, content: vec![text("Hi")] },
+        ];
+
+        let anthropic = to_provider_format(&messages, ProviderKind::Anthropic);
+        assert_eq!(anthropic["system"], "Be brief.");
+        assert_eq!(anthropic["messages"].as_array().unwrap().len(), 1);
+
+        let openai = to_provider_format(&messages, ProviderKind::OpenAiCompatible);
+        assert_eq!(openai["messages"][0], json!({ "role": "system", "content": "Be brief." }));
+        assert_eq!(openai["messages"][1], json!({ "role": "user", "content": "Hi" }));
+    }
+
+    #[test]
+    fn reads_replies_from_provider_responses() {
+        let openai = json!({
+            "choices": [{
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{ "id": "call_2", "type": "function", "function": { "name": "lookup", "arguments": "not json" } }]
+                }
+            }]
+        });
+        let message = from_provider_response(&openai, ProviderKind::OpenAiCompatible).unwrap();
+        assert_eq!(message.content, vec![ContentBlock::ToolCall {
+            id: "call_2".to_string(),
+            name: "lookup".to_string(),
+            arguments: json!("not json"),
+        }]);
+
+        let anthropic = json!({
+            "role": "assistant",
+            "content": [
+                { "type": "thinking", "thinking": "Simple.", "signature": "abc" },
+                { "type": "redacted_thinking", "data": "..." },
+                { "type": "text", "text": "Hello!" }
+            ]
+        });
+        let message = from_provider_response(&anthropic, ProviderKind::Anthropic).unwrap();
+        assert_eq!(message.content, vec![
+            ContentBlock::Thinking { text: "Simple.".to_string(), signature: Some("abc".to_string()) },
+            text("Hello!"),
+        ]);
+
+        let error = json!({ "error": { "message": "overloaded" } });
+        assert!(from_provider_response(&error, ProviderKind::Anthropic).is_err());
+    }
+}
@@ -0,0 +1,369 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::messages::ProviderKind;
+use crate::network::measure_clock_skew;
+use crate::secrets::get_api_key;
+use crate::settings::get_setting;
+
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const PING_TIMEOUT: Duration = Duration::from_secs(30);
+const KEY_CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+// Background key checks hit every provider, so they run at most hourly unless forced
+const KEY_VALIDATION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const KEY_VALIDATION_CONCURRENCY: usize = 4;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PingFailure {
+    Auth,
+    ModelNotFound,
+    RateLimited,
+    Network,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PingResult {
+    pub ok: bool,
+    pub latency_ms: u64,
+    // The model id the provider says it served, which can differ from an alias we asked for
+    pub model_echoed: Option<String>,
+    pub failure: Option<PingFailure>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyStatus {
+    Valid,
+    Invalid,
+    // The provider couldn't be reached or gave an answer that says nothing about the key
+    Unreachable,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyValidation {
+    pub provider: String,
+    pub status: KeyStatus,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyValidationReport {
+    pub results: Vec<KeyValidation>,
+    pub checked_at_ms: u64,
+    // True when the results are from an earlier run inside the throttle window
+    pub cached: bool,
+}
+
+static LAST_KEY_VALIDATION: Mutex<Option<(Instant, KeyValidationReport)>> = Mutex::new(None);
+
+// The subset of the frontend's provider settings we need
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderSettings {
+    name: String,
+    endpoint: String,
+    #[serde(default)]
+    is_local: bool,
+}
+
+fn classify_status(status: reqwest::StatusCode) -> PingFailure {
+    match status.as_u16() {
+        401 | 403 => PingFailure::Auth,
+        404 => PingFailure::ModelNotFound,
+        429 => PingFailure::RateLimited,
+        _ => PingFailure::Other,
+    }
+}
+
+// Pull the human-readable message out of either provider's error shape
+fn error_message(body: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|json| {
+            json.pointer("/error/message")
+                .or_else(|| json.get("message"))
+                .and_then(|message| message.as_str())
+                .map(|message| message.to_string())
+        })
+        .unwrap_or_else(|| body.trim().chars().take(300).collect())
+}
+
+// Machine-readable error code, e.g. OpenAI's and Groq's "model_not_found"
+fn error_code(body: &str) -> Option<String> {
+    let json = serde_json::from_str::<serde_json::Value>(body).ok()?;
+    json.pointer("/error/code")
+        .or_else(|| json.get("code"))
+        .and_then(|code| code.as_str())
+        .map(|code| code.to_string())
+}
+
+fn build_request(
+    client: &reqwest::Client,
+    provider: &ProviderSettings,
+    provider_id: &str,
+    api_key: Option<&str>,
+    model: &str,
+) -> reqwest::RequestBuilder {
+    let endpoint = provider.endpoint.trim_end_matches('/');
+    let kind = ProviderKind::from_provider(&format!("{} {} {}", provider_id, provider.name, endpoint));
+
+    match kind {
+        ProviderKind::Anthropic => client
+            .post(format!("{}/messages", endpoint))
+            .header("x-api-key", api_key.unwrap_or_default())
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .json(&serde_json::json!({
+                "model": model,
+                "max_tokens": 1,
+                "messages": [{ "role": "user", "content": "ping" }]
+            })),
+        ProviderKind::OpenAiCompatible => {
+            // OpenAI's o-series and gpt-5 models reject max_tokens; other compatible servers may not know the newer name
+            let limit_field = if provider_id == "openai" || endpoint.contains("api.openai.com") {
+                "max_completion_tokens"
+            } else {
+                "max_tokens"
+            };
+            let request = client
+                .post(format!("{}/chat/completions", endpoint))
+                .json(&serde_json::json!({
+                    "model": model,
+                    limit_field: 1,
+                    "messages": [{ "role": "user", "content": "ping" }]
+                }));
+            match api_key {
+                Some(key) => request.bearer_auth(key),
+                None => request,
+            }
+        }
+    }
+}
+
+fn load_provider_settings(app: &AppHandle) -> Result<BTreeMap<String, ProviderSettings>, String> {
+    let providers = get_setting::<BTreeMap<String, serde_json::Value>>(app, "providers")?.unwrap_or_default();
+
+    let mut settings = BTreeMap::new();
+    for (provider_id, value) in providers {
+        match serde_json::from_value(value) {
+            Ok(provider) => {
+                settings.insert(provider_id, provider);
+            }
+            Err(e) => eprintln!("Warning: Skipping provider {} with invalid settings: {}", provider_id, e),
+        }
+    }
+    Ok(settings)
+}
+
+// A rejected key is sometimes a badly set clock, so say so when that's the case
+async fn with_clock_skew_hint(message: String) -> String {
+    match measure_clock_skew().await.warning {
+        Some(warning) => format!("{} ({})", message, warning),
+        None => message,
+    }
+}
+
+// Listing models is authenticated but free, so it checks the key without spending quota on a completion
+pub async fn verify_api_key(
+    client: &reqwest::Client,
+    provider: &ProviderSettings,
+    provider_id: &str,
+    api_key: &str,
+) -> KeyValidation {
+    let endpoint = provider.endpoint.trim_end_matches('/');
+    let kind = ProviderKind::from_provider(&format!("{} {} {}", provider_id, provider.name, endpoint));
+    let request = match kind {
+        ProviderKind::Anthropic => client
+            .get(format!("{}/models", endpoint))
+            .header("x-api-key", api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION),
+        ProviderKind::OpenAiCompatible => client.get(format!("{}/models", endpoint)).bearer_auth(api_key),
+    };
+
+    let validation = |status, message| KeyValidation {
+        provider: provider_id.to_string(),
+        status,
+        message,
+    };
+
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(e) => return validation(KeyStatus::Unreachable, Some(format!("Could not reach {}: {}", provider.endpoint, e))),
+    };
+
+    let status = response.status();
+    if status.is_success() {
+        return validation(KeyStatus::Valid, None);
+    }
+
+    let message = format!("{}: {}", status, error_message(&response.text().await.unwrap_or_default()));
+    match classify_status(status) {
+        PingFailure::Auth => validation(KeyStatus::Invalid, Some(with_clock_skew_hint(message).await)),
+        // Rate limiting means the provider accepted the key
+        PingFailure::RateLimited => validation(KeyStatus::Valid, Some(message)),
+        _ => validation(KeyStatus::Unreachable, Some(message)),
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[tauri::command]
+pub async fn validate_all_keys(app: AppHandle, force: Option<bool>) -> Result<KeyValidationReport, String> {
+    let previous = LAST_KEY_VALIDATION
+        .lock()
+        .map_err(|e| format!("Failed to read key validation state: {}", e))?
+        .clone();
+    if let Some((checked_at, report)) = &previous {
+        if !force.unwrap_or(false) && checked_at.elapsed() < KEY_VALIDATION_INTERVAL {
+            return Ok(KeyValidationReport { cached: true, ..report.clone() });
+        }
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(KEY_CHECK_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let permits = Arc::new(Semaphore::new(KEY_VALIDATION_CONCURRENCY));
+
+    let mut checks = JoinSet::new();
+    for (provider_id, settings) in load_provider_settings(&app)? {
+        if settings.is_local {
+            continue;
+        }
+        let api_key = match get_api_key(&app, &provider_id) {
+            Ok(Some(api_key)) => api_key,
+            Ok(None) => continue,
+            Err(e) => {
+                eprintln!("Warning: {}", e);
+                continue;
+            }
+        };
+
+        let (client, permits) = (client.clone(), permits.clone());
+        checks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            verify_api_key(&client, &settings, &provider_id, &api_key).await
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(result) = checks.join_next().await {
+        match result {
+            Ok(validation) => results.push(validation),
+            Err(e) => eprintln!("Warning: API key check failed: {}", e),
+        }
+    }
+    results.sort_by(|a, b| a.provider.cmp(&b.provider));
+
+    // Only announce keys that just went bad so the UI doesn't re-alert every hour
+    let previous_status = |provider: &str| {
+        previous.as_ref().and_then(|(_, report)| {
+            report.results.iter().find(|validation| validation.provider == provider).map(|validation| validation.status)
+        })
+    };
+    for validation in &results {
+        if validation.status == KeyStatus::Invalid && previous_status(&validation.provider) != Some(KeyStatus::Invalid) {
+            if let Err(e) = app.emit("key-status-changed", validation) {
+                eprintln!("Warning: Failed to emit key status change: {}", e);
+            }
+        }
+    }
+
+    let report = KeyValidationReport {
+        results,
+        checked_at_ms: now_ms(),
+        cached: false,
+    };
+    if let Ok(mut last) = LAST_KEY_VALIDATION.lock() {
+        *last = Some((Instant::now(), report.clone()));
+    }
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn ping_chat_provider(app: AppHandle, provider: String, model: String) -> Result<PingResult, String> {
+    let providers = get_setting::<std::collections::BTreeMap<String, serde_json::Value>>(&app, "providers")?
+        .unwrap_or_default();
+    let settings: ProviderSettings = providers
+        .get(&provider)
+        .cloned()
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| format!("Invalid settings for provider {}: {}", provider, e))?
+        .ok_or_else(|| format!("Provider {} is not configured", provider))?;
+
+    let api_key = if settings.is_local {
+        None
+    } else {
+        Some(get_api_key(&app, &provider)?.ok_or_else(|| format!("No API key is stored for {}", provider))?)
+    };
+
+    let client = reqwest::Client::builder()
+        .timeout(PING_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let started = Instant::now();
+    let response = build_request(&client, &settings, &provider, api_key.as_deref(), &model).send().await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let response = match response {
+        Ok(response) => response,
+        Err(e) => {
+            return Ok(PingResult {
+                ok: false,
+                latency_ms,
+                model_echoed: None,
+                failure: Some(PingFailure::Network),
+                message: Some(format!("Could not reach {}: {}", settings.endpoint, e)),
+            });
+        }
+    };
+
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+
+    if !status.is_success() {
+        // Some OpenAI-compatible servers report an unknown model as 400 rather than 404, but keep the model_not_found code
+        let message = error_message(&body);
+        let failure = match classify_status(status) {
+            PingFailure::Other if error_code(&body).as_deref() == Some("model_not_found") => PingFailure::ModelNotFound,
+            failure => failure,
+        };
+        let mut message = format!("{}: {}", status, message);
+        if failure == PingFailure::Auth {
+            message = with_clock_skew_hint(message).await;
+        }
+        return Ok(PingResult {
+            ok: false,
+            latency_ms,
+            model_echoed: None,
+            failure: Some(failure),
+            message: Some(message),
+        });
+    }
+
+    let model_echoed = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|json| json.get("model").and_then(|m| m.as_str()).map(|m| m.to_string()));
+
+    Ok(PingResult {
+        ok: true,
+        latency_ms,
+        model_echoed,
+        failure: None,
+        message: None,
+    })
+}
@@ -0,0 +1,203 @@
+use std::fmt::Write;
+use tauri::AppHandle;
+
+use crate::gpu::GpuKind;
+use crate::models::ModelRef;
+use crate::ollama::{discover_local_models, LocalModel};
+use crate::system_info::{check_model_compatibility, get_system_resources, SystemResources};
+use crate::webview::read_webview_info;
+
+fn bytes_to_gb(bytes: u64) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0 * 1024.0)
+}
+
+// Pipes and newlines would break the Markdown table
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+#[cfg(target_os = "macos")]
+fn os_version() -> Option<String> {
+    command_output("sw_vers", &["-productVersion"]).map(|version| format!("macOS {}", version))
+}
+
+#[cfg(target_os = "linux")]
+fn os_version() -> Option<String> {
+    let os_release = std::fs::read_to_string("/etc/os-release").ok()?;
+    os_release
+        .lines()
+        .find_map(|line| line.strip_prefix("PRETTY_NAME="))
+        .map(|name| name.trim_matches('"').to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn os_version() -> Option<String> {
+    command_output("cmd", &["/C", "ver"])
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn os_version() -> Option<String> {
+    None
+}
+
+// Resolve a local model's size from its file, or from discovery when only the name is known
+fn local_model_size(model: &ModelRef, discovered: &[LocalModel]) -> Option<u64> {
+    if let ModelRef::Local { path: Some(path), .. } = model {
+        if let Ok(metadata) = std::fs::metadata(path) {
+            return Some(metadata.len());
+        }
+    }
+    discovered
+        .iter()
+        .find(|local| local.model_ref().matches(model))
+        .map(|local| local.size_bytes)
+}
+
+fn write_system_section(report: &mut String, app: &AppHandle, resources: &SystemResources) {
+    let os = os_version().unwrap_or_else(|| std::env::consts::OS.to_string());
+    let arch = if resources.running_under_rosetta {
+        format!("{} build on {} (Rosetta)", resources.binary_arch, resources.host_arch)
+    } else if resources.binary_arch != resources.host_arch {
+        format!("{} build on {}", resources.binary_arch, resources.host_arch)
+    } else {
+        resources.binary_arch.clone()
+    };
+
+    let _ = writeln!(report, "## System\n");
+    let _ = writeln!(report, "- App version: {}", app.package_info().version);
+    let _ = writeln!(report, "- OS: {}", os);
+    let _ = writeln!(report, "- Architecture: {}", arch);
+    let webview = read_webview_info();
+    let _ = writeln!(
+        report,
+        "- Webview: {} {}",
+        webview.kind,
+        webview.version.as_deref().unwrap_or("(version unknown)")
+    );
+    let _ = writeln!(report, "- CPU cores: {}", resources.cpu_cores);
+    let _ = writeln!(
+        report,
+        "- Memory: {:.1} GB total, {:.1} GB available",
+        resources.total_memory_gb, resources.available_memory_gb
+    );
+    let _ = writeln!(report, "- Free storage: {:.1} GB", resources.available_storage_gb);
+    let backends = resources.compute_backends.names();
+    let _ = writeln!(
+        report,
+        "- Compute backends: {}",
+        if backends.is_empty() { "none (CPU only)".to_string() } else { backends.join(", ") }
+    );
+
+    if resources.gpus.is_empty() {
+        let _ = writeln!(report, "- GPUs: none detected");
+    } else {
+        let _ = writeln!(report, "- GPUs:");
+        for gpu in &resources.gpus {
+            let kind = match gpu.kind {
+                GpuKind::Discrete => "discrete",
+                GpuKind::Integrated => "integrated",
+                GpuKind::Unknown => "unknown type",
+            };
+            let memory = match gpu.vram_gb {
+                Some(vram_gb) if gpu.shared_memory => format!("{:.1} GB shared", vram_gb),
+                Some(vram_gb) => format!("{:.1} GB VRAM", vram_gb),
+                None if gpu.shared_memory => "shared memory".to_string(),
+                None => "VRAM unknown".to_string(),
+            };
+            let _ = writeln!(report, "  - {} ({}, {}, {})", gpu.name, gpu.vendor, kind, memory);
+        }
+    }
+}
+
+fn write_models_section(report: &mut String, resources: &SystemResources, models: &[ModelRef], discovered: &[LocalModel]) {
+    let _ = writeln!(report, "\n## Models\n");
+    if models.is_empty() {
+        let _ = writeln!(report, "No models selected.");
+        return;
+    }
+
+    let _ = writeln!(report, "| Model | Size | Parameters | Memory needed | Compatible | Confidence | Notes |");
+    let _ = writeln!(report, "|---|---|---|---|---|---|---|");
+
+    for model in models {
+        let (name, size_bytes) = match model {
+            ModelRef::Cloud { provider, id } => {
+                let _ = writeln!(
+                    report,
+                    "| {} | - | - | - | Yes | - | Runs remotely on {} |",
+                    escape_cell(id),
+                    escape_cell(provider)
+                );
+                continue;
+            }
+            ModelRef::Local { name, .. } => (name, local_model_size(model, discovered)),
+        };
+
+        let Some(size_bytes) = size_bytes else {
+            let _ = writeln!(report, "| {} | unknown | - | - | - | - | Model file not found |", escape_cell(name));
+            continue;
+        };
+
+        let compatibility = check_model_compatibility(resources, size_bytes, name);
+        let parameters = compatibility
+            .parameter_count_billions
+            .map(|billions| format!("{}B", billions))
+            .unwrap_or_else(|| "-".to_string());
+        let notes = if compatibility.warnings.is_empty() {
+            "-".to_string()
+        } else {
+            compatibility.warnings.join("<br>")
+        };
+
+        let _ = writeln!(
+            report,
+            "| {} | {:.1} GB | {} | {:.1} GB | {} | {:.0}% | {} |",
+            escape_cell(name),
+            bytes_to_gb(size_bytes),
+            parameters,
+            compatibility.required_memory_gb,
+            if compatibility.is_compatible { "Yes" } else { "No" },
+            compatibility.confidence_level * 100.0,
+            escape_cell(&notes)
+        );
+    }
+}
+
+#[tauri::command]
+pub async fn export_compatibility_report(app: AppHandle, models: Vec<ModelRef>) -> Result<String, String> {
+    let resources = get_system_resources().await?;
+
+    // Only scan the model directories when some model needs its size looked up by name
+    let needs_discovery = models.iter().any(|model| match model {
+        ModelRef::Local { path: Some(path), .. } => !std::path::Path::new(path).exists(),
+        ModelRef::Local { path: None, .. } => true,
+        ModelRef::Cloud { .. } => false,
+    });
+    let discovered = if needs_discovery {
+        match discover_local_models().await {
+            Ok(result) => result.models,
+            Err(e) => {
+                eprintln!("Warning: Model discovery failed while building compatibility report: {}", e);
+                Vec::new()
+            }
+        }
+    } else {
+        Vec::new()
+    };
+
+    let mut report = String::from("# Open Chat compatibility report\n\n");
+    write_system_section(&mut report, &app, &resources);
+    write_models_section(&mut report, &resources, &models, &discovered);
+
+    Ok(report)
+}
@@ -0,0 +1,260 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Row, Sqlite};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::database::{ensure_column, get_database_pool};
+use crate::settings::{get_setting, set_setting};
+
+const RETENTION_POLICY_KEY: &str = "retentionPolicy";
+
+// Pruned conversations stay in the trash this long before they're deleted for good
+const TRASH_GRACE_DAYS: u32 = 30;
+
+// The first automatic run waits for startup to settle, then repeats daily
+const AUTO_PRUNE_INITIAL_DELAY: Duration = Duration::from_secs(60);
+const AUTO_PRUNE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+// Same format the frontend's new Date().toISOString() produces
+const SQL_NOW: &str = "strftime('%Y-%m-%dT%H:%M:%fZ', 'now')";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    pub max_conversations: Option<u32>,
+    pub max_age_days: Option<u32>,
+    // Favorited conversations are never pruned
+    pub pin_protected: bool,
+    // Off until the user has reviewed a preview and opted in
+    #[serde(default)]
+    pub auto_prune: bool,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        RetentionPolicy {
+            max_conversations: None,
+            max_age_days: None,
+            pin_protected: true,
+            auto_prune: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PruneReason {
+    TooOld,
+    OverLimit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrunedConversation {
+    pub id: i64,
+    pub title: String,
+    pub updated_at: Option<String>,
+    pub reason: PruneReason,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionReport {
+    // True when nothing was changed and the report only shows what would happen
+    pub preview: bool,
+    pub pruned: Vec<PrunedConversation>,
+    // Conversations whose trash grace period ran out and were (or would be) deleted permanently
+    pub purged: u64,
+}
+
+pub fn get_retention_policy(app: &AppHandle) -> Result<RetentionPolicy, String> {
+    Ok(get_setting(app, RETENTION_POLICY_KEY)?.unwrap_or_default())
+}
+
+// The frontend adds this column in its own migrations; make sure it exists if we run first
+async fn ensure_trash_column(pool: &Pool<Sqlite>) -> Result<(), String> {
+    ensure_column(pool, "conversations", "deleted_at", "DATETIME").await
+}
+
+async fn select_prunable(pool: &Pool<Sqlite>, policy: &RetentionPolicy) -> Result<Vec<PrunedConversation>, String> {
+    // Newest first, so everything past max_conversations is the oldest history
+    let rows = sqlx::query(
+        "SELECT id, title, updated_at, is_favorite FROM conversations \
+         WHERE deleted_at IS NULL ORDER BY updated_at DESC, id DESC",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load conversations: {}", e))?;
+
+    let cutoff = match policy.max_age_days {
+        Some(days) => Some(
+            sqlx::query_scalar::<_, String>(&format!(
+                "SELECT strftime('%Y-%m-%dT%H:%M:%fZ', 'now', '-{} days')",
+                days
+            ))
+            .fetch_one(pool)
+            .await
+            .map_err(|e| format!("Failed to compute retention cutoff: {}", e))?,
+        ),
+        None => None,
+    };
+
+    let mut prunable = Vec::new();
+    for (index, row) in rows.iter().enumerate() {
+        let is_favorite = row.try_get::<Option<bool>, _>("is_favorite").ok().flatten().unwrap_or(false);
+        if policy.pin_protected && is_favorite {
+            continue;
+        }
+
+        let updated_at: Option<String> = row.try_get("updated_at").ok().flatten();
+        let too_old = matches!((&cutoff, &updated_at), (Some(cutoff), Some(updated_at)) if updated_at < cutoff);
+        let over_limit = policy.max_conversations.is_some_and(|max| index >= max as usize);
+
+        let reason = if too_old {
+            PruneReason::TooOld
+        } else if over_limit {
+            PruneReason::OverLimit
+        } else {
+            continue;
+        };
+
+        prunable.push(PrunedConversation {
+            id: row.try_get("id").map_err(|e| format!("Failed to read conversation id: {}", e))?,
+            title: row.try_get::<Option<String>, _>("title").ok().flatten().unwrap_or_default(),
+            updated_at,
+            reason,
+        });
+    }
+    Ok(prunable)
+}
+
+fn expired_trash_filter() -> String {
+    format!(
+        "deleted_at IS NOT NULL AND deleted_at < strftime('%Y-%m-%dT%H:%M:%fZ', 'now', '-{} days')",
+        TRASH_GRACE_DAYS
+    )
+}
+
+async fn purge_expired_trash(pool: &Pool<Sqlite>, preview: bool) -> Result<u64, String> {
+    let filter = expired_trash_filter();
+    if preview {
+        let count = sqlx::query_scalar::<_, i64>(&format!("SELECT count(*) FROM conversations WHERE {}", filter))
+            .fetch_one(pool)
+            .await
+            .map_err(|e| format!("Failed to count expired trash: {}", e))?;
+        return Ok(count as u64);
+    }
+
+    let mut tx = pool.begin().await
+        .map_err(|e| format!("Failed to start purge transaction: {}", e))?;
+    sqlx::query(&format!(
+        "DELETE FROM messages WHERE conversation_id IN (SELECT id FROM conversations WHERE {})",
+        filter
+    ))
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to delete expired messages: {}", e))?;
+    let purged = sqlx::query(&format!("DELETE FROM conversations WHERE {}", filter))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to delete expired conversations: {}", e))?
+        .rows_affected();
+    tx.commit().await
+        .map_err(|e| format!("Failed to commit purge: {}", e))?;
+
+    Ok(purged)
+}
+
+pub async fn run_retention(app: &AppHandle, policy: &RetentionPolicy, preview: bool) -> Result<RetentionReport, String> {
+    let pool = get_database_pool(app).await?;
+    ensure_trash_column(&pool).await?;
+
+    let pruned = select_prunable(&pool, policy).await?;
+    if !preview && !pruned.is_empty() {
+        let mut tx = pool.begin().await
+            .map_err(|e| format!("Failed to start retention transaction: {}", e))?;
+        for conversation in &pruned {
+            sqlx::query(&format!("UPDATE conversations SET deleted_at = {} WHERE id = ?", SQL_NOW))
+                .bind(conversation.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to move conversation {} to the trash: {}", conversation.id, e))?;
+        }
+        tx.commit().await
+            .map_err(|e| format!("Failed to commit retention changes: {}", e))?;
+    }
+
+    let purged = purge_expired_trash(&pool, preview).await?;
+
+    if !preview && (!pruned.is_empty() || purged > 0) {
+        if let Err(e) = app.emit("conversations-pruned", pruned.len()) {
+            eprintln!("Warning: Failed to emit conversations-pruned: {}", e);
+        }
+    }
+
+    Ok(RetentionReport { preview, pruned, purged })
+}
+
+// Background loop that applies the saved policy once a day while auto-prune is enabled
+pub fn start_retention_schedule(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(AUTO_PRUNE_INITIAL_DELAY).await;
+        loop {
+            match get_retention_policy(&app) {
+                Ok(policy) if policy.auto_prune => {
+                    if let Err(e) = run_retention(&app, &policy, false).await {
+                        eprintln!("Warning: Automatic history pruning failed: {}", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Warning: Failed to read retention policy: {}", e),
+            }
+            tokio::time::sleep(AUTO_PRUNE_INTERVAL).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn set_retention_policy(
+    app: AppHandle,
+    max_conversations: Option<u32>,
+    max_age_days: Option<u32>,
+    pin_protected: bool,
+    auto_prune: Option<bool>,
+) -> Result<RetentionPolicy, String> {
+    if max_conversations == Some(0) {
+        return Err("max_conversations must be at least 1".to_string());
+    }
+    if max_age_days == Some(0) {
+        return Err("max_age_days must be at least 1".to_string());
+    }
+
+    let policy = RetentionPolicy {
+        max_conversations,
+        max_age_days,
+        pin_protected,
+        auto_prune: auto_prune.unwrap_or(false),
+    };
+    set_setting(&app, RETENTION_POLICY_KEY, &policy)?;
+    Ok(policy)
+}
+
+#[tauri::command]
+pub async fn apply_retention_policy(app: AppHandle, preview: Option<bool>) -> Result<RetentionReport, String> {
+    let policy = get_retention_policy(&app)?;
+    run_retention(&app, &policy, preview.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn restore_conversation(app: AppHandle, conversation_id: i64) -> Result<(), String> {
+    let pool = get_database_pool(&app).await?;
+    ensure_trash_column(&pool).await?;
+
+    let restored = sqlx::query("UPDATE conversations SET deleted_at = NULL WHERE id = ? AND deleted_at IS NOT NULL")
+        .bind(conversation_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to restore conversation: {}", e))?
+        .rows_affected();
+    if restored == 0 {
+        return Err(format!("Conversation {} is not in the trash", conversation_id));
+    }
+    Ok(())
+}
@@ -0,0 +1,267 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+use crate::settings::{get_setting, set_setting};
+
+// Must match the frontend's secure storage (src/utils/secureStorage.ts)
+pub const KEYRING_SERVICE: &str = "open-chat";
+pub const DEV_KEYS_FILE: &str = "keys.enc";
+const DEV_ENCRYPTION_KEY: &str = "open-chat-2024-secure-key-storage-v1-dev-only";
+
+// Env var names we know how to map onto the app's provider ids
+const KNOWN_ENV_KEYS: &[(&str, &str)] = &[
+    ("OPENAI_API_KEY", "openai"),
+    ("ANTHROPIC_API_KEY", "anthropic"),
+    ("GROQ_API_KEY", "groq"),
+    ("OPENROUTER_API_KEY", "openrouter"),
+    ("GEMINI_API_KEY", "google-ai"),
+    ("GOOGLE_API_KEY", "google-ai"),
+    ("XAI_API_KEY", "xai"),
+    ("TOGETHER_API_KEY", "together-ai"),
+    ("FIREWORKS_API_KEY", "fireworks-ai"),
+    ("DEEPINFRA_API_KEY", "deep-infra"),
+    ("CEREBRAS_API_KEY", "cerebras-cloud"),
+    ("COHERE_API_KEY", "cohere"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ImportKeysResult {
+    pub providers_set: Vec<String>,
+    pub unrecognized: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+fn provider_key(provider_id: &str) -> String {
+    format!("provider-{}", provider_id)
+}
+
+// The frontend keeps keys in a file instead of the keychain under `tauri dev`. It asks this
+// same function through uses_dev_key_storage, so both sides always pick the same store.
+fn uses_dev_key_file() -> bool {
+    tauri::is_dev()
+}
+
+fn dev_keys_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path().app_data_dir()
+        .map(|dir| dir.join(DEV_KEYS_FILE))
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
+
+// XOR over UTF-16 code units then base64, exactly as simpleEncrypt/simpleDecrypt do in the frontend
+fn dev_xor(units: impl Iterator<Item = u16>) -> Vec<u16> {
+    let key: Vec<u16> = DEV_ENCRYPTION_KEY.encode_utf16().collect();
+    units.enumerate().map(|(i, unit)| unit ^ key[i % key.len()]).collect()
+}
+
+fn dev_encrypt(text: &str) -> Result<String, String> {
+    let bytes = dev_xor(text.encode_utf16())
+        .into_iter()
+        .map(|unit| u8::try_from(unit).map_err(|_| "API keys must be plain ASCII".to_string()))
+        .collect::<Result<Vec<u8>, String>>()?;
+    Ok(BASE64.encode(bytes))
+}
+
+fn dev_decrypt(encrypted: &str) -> Result<String, String> {
+    let bytes = BASE64.decode(encrypted.trim())
+        .map_err(|e| format!("Failed to decode dev key file: {}", e))?;
+    String::from_utf16(&dev_xor(bytes.into_iter().map(u16::from)))
+        .map_err(|e| format!("Failed to decrypt dev key file: {}", e))
+}
+
+fn save_dev_keys(app: &AppHandle, keys: &BTreeMap<String, String>) -> Result<(), String> {
+    let path = dev_keys_path(app)?;
+    let json = serde_json::to_string(keys)
+        .map_err(|e| format!("Failed to serialize dev keys: {}", e))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    std::fs::write(&path, dev_encrypt(&json)?)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn load_dev_keys(app: &AppHandle) -> Result<BTreeMap<String, String>, String> {
+    let path = dev_keys_path(app)?;
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let encrypted = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&dev_decrypt(&encrypted)?)
+        .map_err(|e| format!("Dev key file is corrupt: {}", e))
+}
+
+#[cfg(desktop)]
+fn save_keyring_key(app: &AppHandle, provider_id: &str, api_key: &str) -> Result<(), String> {
+    use tauri_plugin_keyring::KeyringExt;
+
+    app.keyring()
+        .set_password(KEYRING_SERVICE, &provider_key(provider_id), api_key)
+        .map_err(|e| format!("Failed to save API key for {} to the keychain: {}", provider_id, e))
+}
+
+#[cfg(mobile)]
+fn save_keyring_key(_app: &AppHandle, provider_id: &str, _api_key: &str) -> Result<(), String> {
+    Err(format!("Saving the API key for {} is not supported on this platform", provider_id))
+}
+
+#[cfg(desktop)]
+fn load_keyring_key(app: &AppHandle, provider_id: &str) -> Result<Option<String>, String> {
+    use tauri_plugin_keyring::KeyringExt;
+
+    app.keyring()
+        .get_password(KEYRING_SERVICE, &provider_key(provider_id))
+        .map_err(|e| format!("Failed to read API key for {} from the keychain: {}", provider_id, e))
+}
+
+#[cfg(mobile)]
+fn load_keyring_key(_app: &AppHandle, _provider_id: &str) -> Result<Option<String>, String> {
+    Ok(None)
+}
+
+pub fn get_api_key(app: &AppHandle, provider_id: &str) -> Result<Option<String>, String> {
+    let key = if uses_dev_key_file() {
+        load_dev_keys(app)?.remove(&provider_key(provider_id))
+    } else {
+        load_keyring_key(app, provider_id)?
+    };
+    Ok(key.filter(|key| !key.trim().is_empty()))
+}
+
+// Save several keys at once so the dev key file is only rewritten once
+pub fn save_api_keys(app: &AppHandle, keys: &BTreeMap<String, String>) -> Vec<(String, Result<(), String>)> {
+    if uses_dev_key_file() {
+        let result = load_dev_keys(app).and_then(|mut stored| {
+            for (provider_id, api_key) in keys {
+                stored.insert(provider_key(provider_id), api_key.clone());
+            }
+            save_dev_keys(app, &stored)
+        });
+        return keys.keys().map(|provider_id| (provider_id.clone(), result.clone())).collect();
+    }
+
+    keys.iter()
+        .map(|(provider_id, api_key)| (provider_id.clone(), save_keyring_key(app, provider_id, api_key)))
+        .collect()
+}
+
+// Parse KEY=value lines, accepting `export`, quotes and trailing comments
+pub fn parse_env_file(contents: &str) -> Vec<(String, String)> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (name, value) = line.split_once('=')?;
+            let value = value.trim();
+
+            let value = if let Some(quoted) = value
+                .strip_prefix('"')
+                .and_then(|v| v.split_once('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|v| v.split_once('\'')))
+            {
+                quoted.0
+            } else {
+                value.split(" #").next().unwrap_or(value).trim()
+            };
+
+            Some((name.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+// Flag imported providers that already exist so the settings UI shows them as configured
+fn mark_providers_have_keys(app: &AppHandle, provider_ids: &[String]) -> Result<(), String> {
+    let Some(mut providers) = get_setting::<BTreeMap<String, serde_json::Value>>(app, "providers")? else {
+        return Ok(());
+    };
+
+    let mut changed = false;
+    for provider_id in provider_ids {
+        if let Some(provider) = providers.get_mut(provider_id).and_then(|p| p.as_object_mut()) {
+            provider.insert("hasApiKey".to_string(), serde_json::Value::Bool(true));
+            changed = true;
+        }
+    }
+
+    if changed {
+        set_setting(app, "providers", &providers)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn import_keys_from_env(app: AppHandle, path: String) -> Result<ImportKeysResult, String> {
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+    let mut result = ImportKeysResult::default();
+    let mut keys = BTreeMap::new();
+
+    for (name, value) in parse_env_file(&contents) {
+        let Some((_, provider_id)) = KNOWN_ENV_KEYS.iter().find(|(env_name, _)| *env_name == name) else {
+            result.unrecognized.push(name);
+            continue;
+        };
+        if value.is_empty() {
+            continue;
+        }
+        // When both GEMINI_API_KEY and GOOGLE_API_KEY are present, the first one wins
+        keys.entry(provider_id.to_string()).or_insert(value);
+    }
+
+    // Errors name the provider only; key values never reach logs or the result
+    for (provider_id, saved) in save_api_keys(&app, &keys) {
+        match saved {
+            Ok(()) => result.providers_set.push(provider_id),
+            Err(e) => result.errors.push(e),
+        }
+    }
+
+    if let Err(e) = mark_providers_have_keys(&app, &result.providers_set) {
+        eprintln!("Warning: Failed to update provider settings after key import: {}", e);
+    }
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn uses_dev_key_storage() -> bool {
+    uses_dev_key_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Provider ids offered in the settings UI, read from the presets in SettingsModal.tsx
+    fn frontend_provider_ids() -> Vec<String> {
+        let source = include_str!("../../src/components/Settings/SettingsModal.tsx");
+        let start = source.find("const providerPresets").expect("providerPresets not found in SettingsModal.tsx");
+        let presets = &source[start..];
+        let presets = &presets[..presets.find("\n  ]").expect("end of providerPresets not found")];
+        let pattern = regex::Regex::new(r"id: '([^']+)'").unwrap();
+        pattern.captures_iter(presets).map(|captures| captures[1].to_string()).collect()
+    }
+
+    #[test]
+    fn every_env_key_maps_to_a_frontend_provider() {
+        let provider_ids = frontend_provider_ids();
+        assert!(!provider_ids.is_empty());
+        for (env_name, provider_id) in KNOWN_ENV_KEYS {
+            assert!(
+                provider_ids.iter().any(|id| id == provider_id),
+                "{} maps to '{}', which is not a provider in the settings UI ({:?})",
+                env_name,
+                provider_id,
+                provider_ids
+            );
+        }
+    }
+}
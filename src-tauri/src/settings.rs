@@ -0,0 +1,38 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+// Same store file the frontend settings store uses, so both sides see the same values
+const SETTINGS_STORE: &str = "settings.json";
+
+pub fn get_setting<T: DeserializeOwned>(app: &AppHandle, key: &str) -> Result<Option<T>, String> {
+    let store = app.store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    match store.get(key) {
+        Some(value) => serde_json::from_value(value)
+            .map(Some)
+            .map_err(|e| format!("Invalid value for setting '{}': {}", key, e)),
+        None => Ok(None),
+    }
+}
+
+pub fn set_setting<T: Serialize>(app: &AppHandle, key: &str, value: &T) -> Result<(), String> {
+    let store = app.store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    let value = serde_json::to_value(value)
+        .map_err(|e| format!("Failed to serialize setting '{}': {}", key, e))?;
+    store.set(key, value);
+    store.save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+// Write any pending changes to disk (the store otherwise saves on its own schedule)
+pub fn flush_settings(app: &AppHandle) -> Result<(), String> {
+    let store = app.store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    store.save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
@@ -0,0 +1,39 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::AppHandle;
+
+use crate::ollama::stop_app_started_ollama;
+use crate::settings::{flush_settings, get_setting};
+
+// Whether quitting should also stop an Ollama server the app started (defaults to true)
+const STOP_OLLAMA_ON_QUIT_KEY: &str = "stopOllamaOnQuit";
+
+// Upper bound on how long quitting may wait for Ollama to exit
+const OLLAMA_STOP_TIMEOUT: Duration = Duration::from_secs(3);
+
+static SHUTDOWN_STARTED: AtomicBool = AtomicBool::new(false);
+
+// Called from RunEvent::Exit; safe to call more than once
+pub fn run_shutdown(app: &AppHandle) {
+    if SHUTDOWN_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    if let Err(e) = flush_settings(app) {
+        eprintln!("Warning: Failed to flush settings on shutdown: {}", e);
+    }
+
+    let stop_ollama = get_setting::<bool>(app, STOP_OLLAMA_ON_QUIT_KEY)
+        .unwrap_or_else(|e| {
+            eprintln!("Warning: {}", e);
+            None
+        })
+        .unwrap_or(true);
+    if stop_ollama {
+        stop_app_started_ollama(OLLAMA_STOP_TIMEOUT);
+    }
+
+    if let Err(e) = crate::unregister_all_shortcuts(app) {
+        eprintln!("Warning: Failed to unregister shortcuts on shutdown: {}", e);
+    }
+}
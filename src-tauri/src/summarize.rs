@@ -0,0 +1,215 @@
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+use tauri::AppHandle;
+
+use crate::ollama::{list_installed_tags, with_keep_alive, OLLAMA_BASE_URL};
+use crate::operations::{self, OperationKind};
+use crate::param_presets::{merge_preset_options, preset_options};
+
+// Roughly 6k tokens of input, which fits comfortably in the context window we request
+pub const MAX_INPUT_CHARS: usize = 24_000;
+const SUMMARY_CONTEXT_TOKENS: u32 = 8192;
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "been", "but", "by", "can", "for", "from", "has", "have", "he",
+    "her", "his", "i", "if", "in", "into", "is", "it", "its", "not", "of", "on", "or", "our", "she", "so",
+    "that", "the", "their", "them", "there", "these", "they", "this", "to", "was", "we", "were", "which",
+    "will", "with", "would", "you", "your",
+];
+
+#[derive(Debug, Deserialize)]
+struct OllamaGenerateResponse {
+    #[serde(default)]
+    response: String,
+    error: Option<String>,
+}
+
+pub fn truncate_chars(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((index, _)) => &text[..index],
+        None => text,
+    }
+}
+
+// Summaries are a cheap task, so default to the smallest installed model
+async fn pick_summary_model(client: &reqwest::Client) -> Result<String, String> {
+    list_installed_tags(client)
+        .await?
+        .into_iter()
+        // Embedding models can't generate text
+        .filter(|model| !model.name.to_lowercase().contains("embed"))
+        .min_by_key(|model| model.size)
+        .map(|model| model.name)
+        .ok_or_else(|| "No local models are installed".to_string())
+}
+
+// Run a single non-streaming completion on a local model, defaulting to the smallest one installed
+pub async fn complete_with_ollama(
+    app: &AppHandle,
+    prompt: &str,
+    max_tokens: usize,
+    model: Option<String>,
+    preset: &serde_json::Value,
+) -> Result<String, String> {
+    let options = merge_preset_options(serde_json::json!({
+        "temperature": 0.2,
+        "num_predict": max_tokens,
+        "num_ctx": SUMMARY_CONTEXT_TOKENS
+    }), preset);
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(120))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let model = match model {
+        Some(model) => model,
+        None => pick_summary_model(&client).await?,
+    };
+
+    let response: OllamaGenerateResponse = client
+        .post(format!("{}/api/generate", OLLAMA_BASE_URL))
+        .json(&with_keep_alive(app, serde_json::json!({
+            "model": model,
+            "prompt": prompt,
+            "stream": false,
+            "options": options
+        })))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid response from Ollama: {}", e))?;
+
+    if let Some(error) = response.error {
+        return Err(format!("Ollama failed to generate with {}: {}", model, error));
+    }
+
+    let output = response.response.trim().to_string();
+    if output.is_empty() {
+        return Err(format!("{} returned an empty response", model));
+    }
+    Ok(output)
+}
+
+pub async fn summarize_with_ollama(
+    app: &AppHandle,
+    text: &str,
+    max_tokens: usize,
+    model: Option<String>,
+    preset: &serde_json::Value,
+) -> Result<String, String> {
+    let prompt = format!(
+        "Summarize the following text in no more than {} words. Keep the key facts, names and numbers. \
+         Reply with the summary only.\n\n---\n{}",
+        (max_tokens * 3 / 4).max(10),
+        truncate_chars(text, MAX_INPUT_CHARS)
+    );
+
+    complete_with_ollama(app, &prompt, max_tokens, model, preset).await
+}
+
+fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        let at_boundary = matches!(c, '.' | '!' | '?' | '\n')
+            && chars.peek().map(|(_, next)| next.is_whitespace()).unwrap_or(true);
+        if at_boundary {
+            let end = index + c.len_utf8();
+            let sentence = text[start..end].trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence);
+            }
+            start = end;
+        }
+    }
+
+    let rest = text[start..].trim();
+    if !rest.is_empty() {
+        sentences.push(rest);
+    }
+    sentences
+}
+
+fn content_words(sentence: &str) -> Vec<String> {
+    sentence
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.len() > 1)
+        .map(|word| word.to_lowercase())
+        .filter(|word| !STOPWORDS.contains(&word.as_str()))
+        .collect()
+}
+
+// Pick the sentences whose words are most frequent across the whole text, keeping original order
+pub fn extractive_summary(text: &str, max_tokens: usize) -> String {
+    let sentences = split_sentences(text);
+    let sentence_words: Vec<Vec<String>> = sentences.iter().map(|s| content_words(s)).collect();
+
+    let mut frequencies: BTreeMap<&str, usize> = BTreeMap::new();
+    for word in sentence_words.iter().flatten() {
+        *frequencies.entry(word.as_str()).or_insert(0) += 1;
+    }
+    let max_frequency = frequencies.values().copied().max().unwrap_or(1) as f64;
+
+    let mut ranked: Vec<(usize, f64)> = sentence_words
+        .iter()
+        .enumerate()
+        .filter(|(_, words)| !words.is_empty())
+        .map(|(index, words)| {
+            let score: f64 = words.iter().map(|word| frequencies[word.as_str()] as f64 / max_frequency).sum();
+            // Normalize by length so long sentences don't win by default
+            (index, score / (words.len() as f64).sqrt())
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    // Budget in words, at ~0.75 words per token
+    let word_budget = (max_tokens * 3 / 4).max(1);
+    let mut chosen = BTreeSet::new();
+    let mut used_words = 0;
+    for (index, _) in ranked {
+        let length = sentences[index].split_whitespace().count();
+        if used_words + length > word_budget && !chosen.is_empty() {
+            continue;
+        }
+        chosen.insert(index);
+        used_words += length;
+        if used_words >= word_budget {
+            break;
+        }
+    }
+
+    chosen.into_iter().map(|index| sentences[index]).collect::<Vec<_>>().join(" ")
+}
+
+#[tauri::command]
+pub async fn tool_summarize_text(
+    app: AppHandle,
+    text: String,
+    max_tokens: usize,
+    model: Option<String>,
+    preset: Option<String>,
+) -> Result<String, String> {
+    if text.trim().is_empty() {
+        return Err("Nothing to summarize".to_string());
+    }
+    if max_tokens == 0 {
+        return Err("max_tokens must be greater than zero".to_string());
+    }
+    let preset = preset_options(&app, preset.as_deref())?;
+
+    operations::run(OperationKind::Summarization, "Summarizing text", |_| async {
+        match summarize_with_ollama(&app, &text, max_tokens, model, &preset).await {
+            Ok(summary) => Ok(summary),
+            Err(e) => {
+                eprintln!("Warning: Falling back to extractive summary: {}", e);
+                Ok(extractive_summary(&text, max_tokens))
+            }
+        }
+    })
+    .await
+}
@@ -1,4 +1,9 @@
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+use crate::cpu::detect_cpu_topology;
+use crate::gpu::{detect_gpus, primary_gpu, probe_compute_backends, ComputeBackends, GpuInfo, GpuKind};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemResources {
@@ -6,6 +11,16 @@ pub struct SystemResources {
     pub available_memory_gb: f64,
     pub available_storage_gb: f64,
     pub cpu_cores: usize,
+    pub physical_cpu_cores: usize,
+    // Threads to give CPU inference: performance cores minus one for the OS
+    pub recommended_thread_count: usize,
+    pub binary_arch: String,
+    pub host_arch: String,
+    pub running_under_rosetta: bool,
+    pub gpus: Vec<GpuInfo>,
+    // VRAM of the GPU inference will run on (the discrete one when there are several)
+    pub primary_gpu_vram_gb: Option<f64>,
+    pub compute_backends: ComputeBackends,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +31,8 @@ pub struct ModelCompatibility {
     pub available_memory_gb: f64,
     pub memory_sufficient: bool,
     pub storage_sufficient: bool,
+    pub gpu_vram_gb: Option<f64>,
+    pub parameter_count_billions: Option<f64>,
     pub warnings: Vec<String>,
 }
 
@@ -42,12 +59,49 @@ pub async fn get_system_resources() -> Result<SystemResources, String> {
     
     // Get CPU core count
     let cpu_cores = get_cpu_cores();
+    let cpu_topology = detect_cpu_topology();
+
+    // Compare the architecture we were compiled for against the machine we're running on
+    let binary_arch = std::env::consts::ARCH.to_string();
+    let host_arch = get_host_arch();
+    let running_under_rosetta = is_running_under_rosetta();
+
+    // GPU enumeration shells out to vendor tools, so keep it off the async runtime
+    // Missing GPU details shouldn't hide the memory and storage numbers, so degrade to no GPUs
+    let gpus = match tokio::time::timeout(timeout_duration, tokio::task::spawn_blocking(detect_gpus)).await {
+        Ok(Ok(gpus)) => gpus,
+        Ok(Err(e)) => {
+            eprintln!("Warning: GPU detection failed: {}", e);
+            Vec::new()
+        }
+        Err(_) => {
+            eprintln!("Warning: GPU query timed out");
+            Vec::new()
+        }
+    };
+
+    // Each backend tool has its own deadline; if the probe as a whole fails, report no acceleration
+    let compute_backends = tokio::task::spawn_blocking(probe_compute_backends)
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("Warning: Compute backend detection failed: {}", e);
+            ComputeBackends::default()
+        });
+    let primary_gpu_vram_gb = primary_gpu(&gpus).and_then(|gpu| gpu.vram_gb);
 
     Ok(SystemResources {
         total_memory_gb,
         available_memory_gb,
         available_storage_gb,
         cpu_cores,
+        physical_cpu_cores: cpu_topology.physical_cores,
+        recommended_thread_count: cpu_topology.recommended_thread_count,
+        binary_arch,
+        host_arch,
+        running_under_rosetta,
+        gpus,
+        primary_gpu_vram_gb,
+        compute_backends,
     })
 }
 
@@ -56,6 +110,15 @@ pub async fn validate_model_compatibility(
     model_name: &str,
 ) -> Result<ModelCompatibility, String> {
     let system_resources = get_system_resources().await?;
+    Ok(check_model_compatibility(&system_resources, model_size_bytes, model_name))
+}
+
+// Split out so callers checking several models only query the system once
+pub fn check_model_compatibility(
+    system_resources: &SystemResources,
+    model_size_bytes: u64,
+    model_name: &str,
+) -> ModelCompatibility {
     let model_size_gb = bytes_to_gb(model_size_bytes);
     
     // Estimate required RAM based on model size and type
@@ -111,41 +174,146 @@ pub async fn validate_model_compatibility(
         warnings.push("CPU has fewer than 4 cores. Model inference may be slow.".to_string());
     }
 
+    if system_resources.running_under_rosetta {
+        warnings.push(format!(
+            "Open Chat is running under Rosetta ({} build on {} hardware). Install the native Apple Silicon build for much faster local inference.",
+            system_resources.binary_arch, system_resources.host_arch
+        ));
+    }
+
+    if let Some(vram_gb) = system_resources.primary_gpu_vram_gb {
+        if required_memory_gb > vram_gb {
+            warnings.push(format!(
+                "Model requires {:.1}GB but the discrete GPU has {:.1}GB of VRAM. Part of the model will run on the CPU.",
+                required_memory_gb, vram_gb
+            ));
+        }
+    }
+
+    if !system_resources.compute_backends.has_gpu_acceleration() {
+        warnings.push("No GPU compute backend (Metal, CUDA or ROCm) was found, so the model will run on the CPU and respond much more slowly.".to_string());
+    }
+
+    // Apple Silicon's unified memory is fast enough that the shared memory warning doesn't apply
+    let has_discrete_gpu = system_resources.gpus.iter().any(|gpu| gpu.kind == GpuKind::Discrete);
+    let only_shared_igpu = !has_discrete_gpu
+        && system_resources
+            .gpus
+            .iter()
+            .any(|gpu| gpu.kind == GpuKind::Integrated && gpu.shared_memory && gpu.vendor != "Apple");
+    if only_shared_igpu {
+        warnings.push("Only an integrated GPU with shared memory is available. Local inference will be noticeably slower than on a discrete GPU.".to_string());
+    }
+
     let is_compatible = memory_sufficient && storage_sufficient;
 
-    Ok(ModelCompatibility {
+    ModelCompatibility {
         is_compatible,
         confidence_level,
         required_memory_gb,
         available_memory_gb: usable_memory_gb,
         memory_sufficient,
         storage_sufficient,
+        gpu_vram_gb: system_resources.primary_gpu_vram_gb,
+        parameter_count_billions: parse_parameter_count(model_name).map(|params| params.total_billions),
         warnings,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParameterCount {
+    pub total_billions: f64,
+    // Mixture-of-experts models only run a subset of their weights per token
+    pub active_billions: f64,
+}
+
+// Matches sizes like "8b", "0.5b", "135m", "8x7b" (MoE) and "30b-a3b" (MoE with active params)
+static PARAMETER_COUNT_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|[^a-z0-9.])(?:(\d+)x)?(\d+(?:\.\d+)?)([bm])(?:-a(\d+(?:\.\d+)?)b)?(?:$|[^a-z0-9])").unwrap()
+});
+
+pub fn parse_parameter_count(model_name: &str) -> Option<ParameterCount> {
+    let model_name_lower = model_name.to_lowercase();
+    let captures = PARAMETER_COUNT_PATTERN.captures(&model_name_lower)?;
+
+    let size: f64 = captures.get(2)?.as_str().parse().ok()?;
+    let size_billions = match captures.get(3)?.as_str() {
+        "m" => size / 1000.0,
+        _ => size,
+    };
+    if size_billions <= 0.0 {
+        return None;
+    }
+
+    if let Some(experts) = captures.get(1).and_then(|m| m.as_str().parse::<f64>().ok()) {
+        // Experts share attention layers, so "8x7b" is ~47B total rather than 56B;
+        // two experts are routed per token in the common Mixtral-style setup
+        return Some(ParameterCount {
+            total_billions: experts * size_billions * 0.83,
+            active_billions: 2.0_f64.min(experts) * size_billions * 0.83,
+        });
+    }
+
+    let active_billions = captures
+        .get(4)
+        .and_then(|m| m.as_str().parse::<f64>().ok())
+        .unwrap_or(size_billions);
+
+    Some(ParameterCount {
+        total_billions: size_billions,
+        active_billions,
     })
 }
 
-fn estimate_model_memory_requirements(model_size_bytes: u64, model_name: &str) -> f64 {
+// Approximate bytes per weight for the quantization named in the model tag
+fn bytes_per_parameter_from_name(model_name_lower: &str) -> Option<f64> {
+    // First match wins, so tags that contain another tag ("iq4" has "q4", "bf16" has "f16") come first
+    let quantizations = [
+        ("f32", 4.0),
+        ("bf16", 2.0),
+        ("fp16", 2.0),
+        ("f16", 2.0),
+        ("iq4", 0.53),
+        ("q8", 1.06),
+        ("q6", 0.82),
+        ("q5", 0.69),
+        ("q4", 0.56),
+        ("q3", 0.43),
+        ("q2", 0.33),
+    ];
+
+    quantizations
+        .iter()
+        .find(|(tag, _)| model_name_lower.contains(tag))
+        .map(|(_, bytes)| *bytes)
+}
+
+pub fn estimate_model_memory_requirements(model_size_bytes: u64, model_name: &str) -> f64 {
     let model_size_gb = bytes_to_gb(model_size_bytes);
     let model_name_lower = model_name.to_lowercase();
-    
-    // Base multiplier for loading the model into memory
-    let mut memory_multiplier = 1.2; // Base overhead for model loading
-    
-    // Adjust based on model characteristics
-    if model_name_lower.contains("7b") || model_name_lower.contains("7-b") {
-        memory_multiplier = 1.5; // ~8GB for 7B models
-    } else if model_name_lower.contains("13b") || model_name_lower.contains("13-b") {
-        memory_multiplier = 1.6; // ~16GB for 13B models
-    } else if model_name_lower.contains("70b") || model_name_lower.contains("70-b") {
-        memory_multiplier = 1.8; // ~80GB for 70B models
-    } else if model_name_lower.contains("vision") || model_name_lower.contains("llava") {
-        memory_multiplier = 1.7; // Vision models need more memory
-    } else if model_name_lower.contains("code") || model_name_lower.contains("coder") {
-        memory_multiplier = 1.4; // Code models are usually more efficient
+
+    let weights_gb = match parse_parameter_count(model_name) {
+        Some(params) => {
+            // Prefer the real bits-per-weight from the file; otherwise trust the tag, defaulting to Q4
+            let bytes_per_parameter = if model_size_bytes > 0 {
+                (model_size_bytes as f64 / (params.total_billions * 1e9)).clamp(0.25, 4.0)
+            } else {
+                bytes_per_parameter_from_name(&model_name_lower).unwrap_or(0.56)
+            };
+            // All experts of an MoE model must be resident, not just the active ones
+            params.total_billions * 1e9 * bytes_per_parameter / (1024.0 * 1024.0 * 1024.0)
+        }
+        None => model_size_gb,
+    };
+
+    // KV cache and runtime buffers scale with model size, plus a fixed runtime overhead
+    let mut estimated_memory = weights_gb * 1.2 + 0.5;
+
+    if model_name_lower.contains("vision") || model_name_lower.contains("llava") {
+        estimated_memory += 1.0; // Vision projector and image embeddings
     }
-    
+
     // For very small models, set a minimum requirement
-    let estimated_memory = model_size_gb * memory_multiplier;
     if estimated_memory < 2.0 {
         2.0 // Minimum 2GB for any model
     } else {
@@ -189,6 +357,73 @@ fn get_cpu_cores() -> usize {
         .unwrap_or(1)
 }
 
+// Normalize the various names platforms use for the same architecture
+fn normalize_arch(arch: &str) -> String {
+    match arch.trim().to_lowercase().as_str() {
+        "arm64" | "aarch64" => "aarch64".to_string(),
+        "x86_64" | "amd64" | "x64" => "x86_64".to_string(),
+        "x86" | "i386" | "i686" => "x86".to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn is_running_under_rosetta() -> bool {
+    use std::process::Command;
+    
+    // sysctl.proc_translated is 1 for translated processes, 0 for native, and missing on Intel Macs
+    Command::new("sysctl")
+        .args(["-n", "sysctl.proc_translated"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim() == "1")
+        .unwrap_or(false)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn is_running_under_rosetta() -> bool {
+    false
+}
+
+#[cfg(target_os = "macos")]
+fn get_host_arch() -> String {
+    use std::process::Command;
+    
+    // uname reports x86_64 for translated processes, so ask the hardware directly
+    let is_arm64 = Command::new("sysctl")
+        .args(["-n", "hw.optional.arm64"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim() == "1")
+        .unwrap_or(false);
+    
+    if is_arm64 {
+        "aarch64".to_string()
+    } else {
+        normalize_arch(std::env::consts::ARCH)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn get_host_arch() -> String {
+    // PROCESSOR_ARCHITEW6432 is only set for emulated (WOW64) processes and holds the native architecture
+    std::env::var("PROCESSOR_ARCHITEW6432")
+        .or_else(|_| std::env::var("PROCESSOR_ARCHITECTURE"))
+        .map(|arch| normalize_arch(&arch))
+        .unwrap_or_else(|_| normalize_arch(std::env::consts::ARCH))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn get_host_arch() -> String {
+    use std::process::Command;
+    
+    Command::new("uname")
+        .arg("-m")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| normalize_arch(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_else(|| normalize_arch(std::env::consts::ARCH))
+}
+
 #[cfg(target_os = "windows")]
 async fn get_total_memory() -> Result<u64, String> {
     use std::process::Command;
@@ -278,35 +513,66 @@ async fn get_available_memory() -> Result<u64, String> {
         .map_err(|e| format!("Failed to get memory info: {}", e))?;
     
     let output_str = String::from_utf8_lossy(&output.stdout);
-    let mut free_pages = 0u64;
+    parse_vm_stat_available(&output_str)
+        .ok_or_else(|| "Could not parse available memory information".to_string())
+}
+
+// Assumed compression ratio while the compressor holds nothing to measure it from
+#[cfg(any(target_os = "macos", test))]
+const DEFAULT_COMPRESSION_RATIO: f64 = 2.0;
+
+// macOS keeps little memory truly free and reclaims inactive pages on demand. File-backed inactive
+// pages are simply dropped, but anonymous ones have to go through the compressor, which only gives
+// back what compression saves, so they are weighted by the compressor's current ratio. Purgeable
+// pages sit in the active and inactive queues; only those that can't all be inactive add anything.
+#[cfg(any(target_os = "macos", test))]
+fn parse_vm_stat_available(output: &str) -> Option<u64> {
     let mut page_size = 4096u64; // Default page size
-    
-    // Parse page size from the first line
-    for line in output_str.lines() {
-        if line.contains("page size of") {
-            if let Some(size_str) = line.split("page size of ").nth(1) {
-                if let Some(size_part) = size_str.split(" bytes").next() {
-                    if let Ok(size) = size_part.parse::<u64>() {
-                        page_size = size;
-                        break;
-                    }
-                }
+    let mut counts = std::collections::HashMap::new();
+
+    for line in output.lines() {
+        // The first line reads "Mach Virtual Memory Statistics: (page size of 16384 bytes)"
+        if let Some(size_str) = line.split("page size of ").nth(1) {
+            if let Some(size) = size_str.split(" bytes").next().and_then(|size| size.parse::<u64>().ok()) {
+                page_size = size;
             }
+            continue;
         }
-    }
-    
-    // Count free pages
-    for line in output_str.lines() {
-        if line.starts_with("Pages free:") {
-            if let Some(pages_str) = line.split(':').nth(1) {
-                if let Ok(pages) = pages_str.trim().replace('.', "").parse::<u64>() {
-                    free_pages += pages;
-                }
-            }
+
+        let Some((label, pages_str)) = line.split_once(':') else {
+            continue;
+        };
+        if let Ok(pages) = pages_str.trim().trim_end_matches('.').parse::<u64>() {
+            counts.insert(label.trim(), pages);
         }
     }
-    
-    Ok(free_pages * page_size)
+
+    let count = |label: &str| counts.get(label).copied().unwrap_or(0);
+    // vm_stat already subtracts speculative pages from "Pages free", so both are added
+    let free = *counts.get("Pages free")?;
+    let inactive = count("Pages inactive");
+
+    let file_backed = count("File-backed pages");
+    let anonymous = count("Anonymous pages");
+    let anonymous_share = if file_backed + anonymous > 0 {
+        anonymous as f64 / (file_backed + anonymous) as f64
+    } else {
+        0.0
+    };
+    let stored = count("Pages stored in compressor");
+    let occupied = count("Pages occupied by compressor");
+    let compression_ratio = if stored > 0 && occupied > 0 {
+        stored as f64 / occupied as f64
+    } else {
+        DEFAULT_COMPRESSION_RATIO
+    };
+    let anonymous_inactive = inactive as f64 * anonymous_share;
+    let reclaimable_inactive = inactive as f64 - anonymous_inactive / compression_ratio.max(1.0);
+
+    let purgeable_beyond_inactive = count("Pages purgeable").saturating_sub(inactive);
+    let available_pages =
+        free + count("Pages speculative") + reclaimable_inactive.round() as u64 + purgeable_beyond_inactive;
+    Some(available_pages * page_size)
 }
 
 #[cfg(target_os = "linux")]
@@ -494,4 +760,69 @@ pub async fn validate_model_system_compatibility(
     model_name: String,
 ) -> Result<ModelCompatibility, String> {
     validate_model_compatibility(model_size_bytes, &model_name).await
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Captured from an M1 MacBook Air with 16 GB under moderate load
+    const VM_STAT_SAMPLE: &str = "Mach Virtual Memory Statistics: (page size of 16384 bytes)
+Pages free:                                9821.
+Pages active:                            298110.
+Pages inactive:                          290412.
+Pages speculative:                         3560.
+Pages throttled:                              0.
+Pages wired down:                         134270.
+Pages purgeable:                          12043.
+\"Translation faults\":                 1094328731.
+Pages copy-on-write:                    36071423.
+Pages zero filled:                     536790834.
+Pages reactivated:                      63870152.
+Pages purged:                            9328105.
+File-backed pages:                       172448.
+Anonymous pages:                         419634.
+Pages stored in compressor:              651620.
+Pages occupied by compressor:            238014.
+Decompressions:                         61298510.
+Compressions:                           79816520.
+Pageins:                                21953604.
+Pageouts:                                 386190.
+Swapins:                                 1286014.
+Swapouts:                                1715301.
+";
+
+    #[test]
+    fn vm_stat_available_weighs_anonymous_inactive_pages_by_the_compression_ratio() {
+        let available = parse_vm_stat_available(VM_STAT_SAMPLE).unwrap();
+
+        // Free and speculative are counted whole and purgeable pages all fit in the inactive queue
+        let ratio = 651620.0 / 238014.0;
+        let anonymous_inactive = 290412.0 * 419634.0 / (172448.0 + 419634.0);
+        let inactive: f64 = 290412.0 - anonymous_inactive / ratio;
+        let expected_pages = 9821 + 3560 + inactive.round() as u64;
+        assert_eq!(available, expected_pages * 16384);
+
+        // More than free + speculative, less than also counting every inactive page in full
+        assert!(available > (9821 + 3560) * 16384);
+        assert!(available < (9821 + 3560 + 290412) * 16384);
+    }
+
+    #[test]
+    fn vm_stat_counts_purgeable_pages_only_beyond_the_inactive_queue() {
+        let output = "Mach Virtual Memory Statistics: (page size of 4096 bytes)
+Pages free:                                1000.
+Pages inactive:                             200.
+Pages speculative:                          100.
+Pages purgeable:                            500.
+File-backed pages:                          300.
+Anonymous pages:                              0.
+";
+        assert_eq!(parse_vm_stat_available(output), Some((1000 + 100 + 200 + 300) * 4096));
+    }
+
+    #[test]
+    fn vm_stat_without_free_pages_is_unparsable() {
+        assert_eq!(parse_vm_stat_available("Pages inactive: 200."), None);
+    }
+}
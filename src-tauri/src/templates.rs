@@ -0,0 +1,202 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::LazyLock;
+use tauri::AppHandle;
+
+use crate::database::get_database_pool;
+
+// Matches {{name}} placeholders, allowing whitespace inside the braces
+static PLACEHOLDER_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{\{\s*([A-Za-z0-9_]+)\s*\}\}").unwrap()
+});
+
+// Same format the frontend's new Date().toISOString() produces
+const SQL_NOW: &str = "strftime('%Y-%m-%dT%H:%M:%fZ', 'now')";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationTemplate {
+    pub id: i64,
+    pub name: String,
+    pub system_prompt: Option<String>,
+    pub first_message: Option<String>,
+    pub settings: Option<serde_json::Value>,
+    // Placeholder names that must be supplied when instantiating
+    pub variables: Vec<String>,
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateConversation {
+    pub conversation_id: i64,
+    // Filled-in first message for the UI to place in the composer
+    pub first_message: Option<String>,
+}
+
+async fn ensure_templates_table(pool: &Pool<Sqlite>) -> Result<(), String> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS conversation_templates (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            system_prompt TEXT,
+            first_message TEXT,
+            settings TEXT,
+            created_at DATETIME,
+            updated_at DATETIME
+        )",
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to create templates table: {}", e))?;
+
+    Ok(())
+}
+
+pub fn template_variables(texts: &[Option<&str>]) -> Vec<String> {
+    let names: BTreeSet<String> = texts
+        .iter()
+        .flatten()
+        .flat_map(|text| PLACEHOLDER_PATTERN.captures_iter(text).map(|captures| captures[1].to_string()))
+        .collect();
+    names.into_iter().collect()
+}
+
+pub fn fill_placeholders(text: &str, params: &BTreeMap<String, String>) -> Result<String, String> {
+    let missing: BTreeSet<&str> = PLACEHOLDER_PATTERN
+        .captures_iter(text)
+        .filter_map(|captures| captures.get(1))
+        .map(|name| name.as_str())
+        .filter(|name| !params.contains_key(*name))
+        .collect();
+
+    if !missing.is_empty() {
+        return Err(format!(
+            "Missing template values for: {}",
+            missing.into_iter().collect::<Vec<_>>().join(", ")
+        ));
+    }
+
+    Ok(PLACEHOLDER_PATTERN
+        .replace_all(text, |captures: &regex::Captures| params[&captures[1]].clone())
+        .to_string())
+}
+
+fn template_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<ConversationTemplate, String> {
+    let system_prompt: Option<String> = row.try_get("system_prompt")
+        .map_err(|e| format!("Failed to read template system prompt: {}", e))?;
+    let first_message: Option<String> = row.try_get("first_message")
+        .map_err(|e| format!("Failed to read template first message: {}", e))?;
+    let settings: Option<String> = row.try_get("settings")
+        .map_err(|e| format!("Failed to read template settings: {}", e))?;
+
+    Ok(ConversationTemplate {
+        id: row.try_get("id").map_err(|e| format!("Failed to read template id: {}", e))?,
+        name: row.try_get("name").map_err(|e| format!("Failed to read template name: {}", e))?,
+        variables: template_variables(&[system_prompt.as_deref(), first_message.as_deref()]),
+        settings: settings.and_then(|settings| serde_json::from_str(&settings).ok()),
+        system_prompt,
+        first_message,
+        created_at: row.try_get("created_at").unwrap_or(None),
+    })
+}
+
+#[tauri::command]
+pub async fn save_template(
+    app: AppHandle,
+    name: String,
+    system_prompt: Option<String>,
+    first_message: Option<String>,
+    settings: Option<serde_json::Value>,
+) -> Result<i64, String> {
+    if name.trim().is_empty() {
+        return Err("Template name cannot be empty".to_string());
+    }
+
+    let pool = get_database_pool(&app).await?;
+    ensure_templates_table(&pool).await?;
+
+    let settings = settings
+        .map(|settings| serde_json::to_string(&settings))
+        .transpose()
+        .map_err(|e| format!("Failed to serialize template settings: {}", e))?;
+
+    let result = sqlx::query(&format!(
+        "INSERT INTO conversation_templates (name, system_prompt, first_message, settings, created_at, updated_at) \
+         VALUES (?, ?, ?, ?, {now}, {now})",
+        now = SQL_NOW
+    ))
+    .bind(name.trim())
+    .bind(system_prompt)
+    .bind(first_message)
+    .bind(settings)
+    .execute(&pool)
+    .await
+    .map_err(|e| format!("Failed to save template: {}", e))?;
+
+    Ok(result.last_insert_rowid())
+}
+
+#[tauri::command]
+pub async fn list_templates(app: AppHandle) -> Result<Vec<ConversationTemplate>, String> {
+    let pool = get_database_pool(&app).await?;
+    ensure_templates_table(&pool).await?;
+
+    let rows = sqlx::query("SELECT * FROM conversation_templates ORDER BY name COLLATE NOCASE ASC")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| format!("Failed to load templates: {}", e))?;
+
+    rows.iter().map(template_from_row).collect()
+}
+
+#[tauri::command]
+pub async fn new_conversation_from_template(
+    app: AppHandle,
+    template_id: i64,
+    provider: String,
+    model: String,
+    params: Option<BTreeMap<String, String>>,
+) -> Result<TemplateConversation, String> {
+    let pool = get_database_pool(&app).await?;
+    ensure_templates_table(&pool).await?;
+
+    let row = sqlx::query("SELECT * FROM conversation_templates WHERE id = ?")
+        .bind(template_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| format!("Failed to load template {}: {}", template_id, e))?
+        .ok_or_else(|| format!("Template {} not found", template_id))?;
+    let template = template_from_row(&row)?;
+
+    let params = params.unwrap_or_default();
+    let system_prompt = template.system_prompt
+        .as_deref()
+        .map(|prompt| fill_placeholders(prompt, &params))
+        .transpose()?;
+    let first_message = template.first_message
+        .as_deref()
+        .map(|message| fill_placeholders(message, &params))
+        .transpose()?;
+    let title = fill_placeholders(&template.name, &params).unwrap_or(template.name.clone());
+    let settings = template.settings.map(|settings| settings.to_string());
+
+    let result = sqlx::query(&format!(
+        "INSERT INTO conversations (title, provider, model, system_prompt, settings, created_at, updated_at) \
+         VALUES (?, ?, ?, ?, ?, {now}, {now})",
+        now = SQL_NOW
+    ))
+    .bind(title)
+    .bind(provider)
+    .bind(model)
+    .bind(system_prompt)
+    .bind(settings)
+    .execute(&pool)
+    .await
+    .map_err(|e| format!("Failed to create conversation from template: {}", e))?;
+
+    Ok(TemplateConversation {
+        conversation_id: result.last_insert_rowid(),
+        first_message,
+    })
+}
@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ThrottleState {
+    Throttling,
+    Nominal,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThermalStatus {
+    pub cpu_temperature_c: Option<f64>,
+    pub gpu_temperature_c: Option<f64>,
+    pub throttling: ThrottleState,
+    // Set when throttling means benchmark numbers will read low
+    pub warning: Option<String>,
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    match Command::new(program).args(args).output() {
+        Ok(output) if output.status.success() => Some(String::from_utf8_lossy(&output.stdout).to_string()),
+        _ => None,
+    }
+}
+
+// Combine two readings, where any "throttling" wins and "nominal" beats "unknown"
+fn merge_throttle_state(a: ThrottleState, b: ThrottleState) -> ThrottleState {
+    match (a, b) {
+        (ThrottleState::Throttling, _) | (_, ThrottleState::Throttling) => ThrottleState::Throttling,
+        (ThrottleState::Nominal, _) | (_, ThrottleState::Nominal) => ThrottleState::Nominal,
+        _ => ThrottleState::Unknown,
+    }
+}
+
+// nvidia-smi reports temperature and thermal slowdown without elevated privileges on every platform
+fn read_nvidia_thermal() -> (Option<f64>, ThrottleState) {
+    let Some(output) = command_output(
+        "nvidia-smi",
+        &[
+            "--query-gpu=temperature.gpu,clocks_throttle_reasons.hw_thermal_slowdown,clocks_throttle_reasons.sw_thermal_slowdown",
+            "--format=csv,noheader,nounits",
+        ],
+    ) else {
+        return (None, ThrottleState::Unknown);
+    };
+
+    // One line per GPU, e.g. "64, Not Active, Not Active"; report the hottest
+    let mut temperature: Option<f64> = None;
+    let mut state = ThrottleState::Unknown;
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if let Some(temp) = fields.first().and_then(|t| t.parse::<f64>().ok()) {
+            temperature = Some(temperature.map_or(temp, |current| current.max(temp)));
+        }
+        let slowdown = fields.iter().skip(1).any(|field| field.eq_ignore_ascii_case("active"));
+        let line_state = if fields.len() >= 3 {
+            if slowdown { ThrottleState::Throttling } else { ThrottleState::Nominal }
+        } else {
+            ThrottleState::Unknown
+        };
+        state = merge_throttle_state(state, line_state);
+    }
+
+    (temperature, state)
+}
+
+#[cfg(target_os = "macos")]
+fn read_platform_thermal() -> (Option<f64>, ThrottleState) {
+    // Temperatures need powermetrics (root) or private SMC keys, but pmset reports the
+    // thermal speed limit unprivileged: "CPU_Speed_Limit = 100" means no throttling
+    let Some(output) = command_output("pmset", &["-g", "therm"]) else {
+        return (None, ThrottleState::Unknown);
+    };
+
+    let speed_limit = output.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        if key.trim() == "CPU_Speed_Limit" {
+            value.trim().parse::<u32>().ok()
+        } else {
+            None
+        }
+    });
+
+    let state = match speed_limit {
+        Some(limit) if limit < 100 => ThrottleState::Throttling,
+        Some(_) => ThrottleState::Nominal,
+        // Apple Silicon omits the speed limit and only prints a warning level when throttled
+        None if output.contains("thermal warning level") && !output.contains("No thermal warning level") => ThrottleState::Throttling,
+        None => ThrottleState::Unknown,
+    };
+
+    (None, state)
+}
+
+#[cfg(target_os = "linux")]
+fn read_millidegrees(path: &std::path::Path) -> Option<f64> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .map(|millidegrees| millidegrees / 1000.0)
+}
+
+#[cfg(target_os = "linux")]
+fn read_platform_thermal() -> (Option<f64>, ThrottleState) {
+    // Thermal zones carry both a temperature and the passive trip point where the kernel starts throttling
+    const CPU_ZONE_TYPES: &[&str] = &["x86_pkg_temp", "cpu-thermal", "cpu_thermal", "soc_thermal", "acpitz", "tcpu"];
+
+    let Ok(zones) = std::fs::read_dir("/sys/class/thermal") else {
+        return (None, ThrottleState::Unknown);
+    };
+
+    let mut temperature: Option<f64> = None;
+    let mut state = ThrottleState::Unknown;
+
+    for zone in zones.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+        let is_zone = zone
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.starts_with("thermal_zone"))
+            .unwrap_or(false);
+        if !is_zone {
+            continue;
+        }
+
+        let zone_type = std::fs::read_to_string(zone.join("type")).unwrap_or_default().trim().to_lowercase();
+        if !CPU_ZONE_TYPES.iter().any(|cpu_type| zone_type.contains(cpu_type)) {
+            continue;
+        }
+
+        let Some(temp) = read_millidegrees(&zone.join("temp")) else {
+            continue;
+        };
+        temperature = Some(temperature.map_or(temp, |current| current.max(temp)));
+
+        // trip_point_N_type / trip_point_N_temp pairs; "passive" is where throttling begins
+        for index in 0..16 {
+            let trip_type = std::fs::read_to_string(zone.join(format!("trip_point_{}_type", index)));
+            let Ok(trip_type) = trip_type else {
+                break;
+            };
+            if trip_type.trim() != "passive" {
+                continue;
+            }
+            if let Some(trip_temp) = read_millidegrees(&zone.join(format!("trip_point_{}_temp", index))) {
+                let zone_state = if temp >= trip_temp { ThrottleState::Throttling } else { ThrottleState::Nominal };
+                state = merge_throttle_state(state, zone_state);
+            }
+        }
+    }
+
+    (temperature, state)
+}
+
+#[cfg(target_os = "windows")]
+fn read_platform_thermal() -> (Option<f64>, ThrottleState) {
+    // ACPI thermal zones report tenths of a kelvin; many machines require admin or expose none at all
+    let script = "Get-CimInstance -Namespace root/wmi -ClassName MSAcpi_ThermalZoneTemperature -ErrorAction Stop | \
+        ForEach-Object { $_.CurrentTemperature }";
+
+    let temperature = command_output("powershell", &["-NoProfile", "-Command", script]).and_then(|output| {
+        output
+            .lines()
+            .filter_map(|line| line.trim().parse::<f64>().ok())
+            .map(|tenths_kelvin| tenths_kelvin / 10.0 - 273.15)
+            .fold(None, |hottest: Option<f64>, temp| Some(hottest.map_or(temp, |h| h.max(temp))))
+    });
+
+    (temperature, ThrottleState::Unknown)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn read_platform_thermal() -> (Option<f64>, ThrottleState) {
+    (None, ThrottleState::Unknown)
+}
+
+pub fn read_thermal_status() -> ThermalStatus {
+    let (cpu_temperature_c, cpu_state) = read_platform_thermal();
+    let (gpu_temperature_c, gpu_state) = read_nvidia_thermal();
+    let throttling = merge_throttle_state(cpu_state, gpu_state);
+
+    let warning = if throttling == ThrottleState::Throttling {
+        Some("The system is thermally throttling. Measured tokens/sec may be lower than usual.".to_string())
+    } else {
+        None
+    };
+
+    ThermalStatus {
+        cpu_temperature_c,
+        gpu_temperature_c,
+        throttling,
+        warning,
+    }
+}
+
+#[tauri::command]
+pub async fn thermal_status() -> Result<ThermalStatus, String> {
+    tokio::task::spawn_blocking(read_thermal_status)
+        .await
+        .map_err(|e| format!("Failed to read thermal status: {}", e))
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TtsVoice {
+    pub id: String,
+    pub name: String,
+    pub language: Option<String>,
+    pub gender: Option<String>,
+}
+
+// Run a TTS listing command, treating a missing engine as "no voices" rather than an error
+fn run_voice_listing(program: &str, args: &[&str]) -> Option<String> {
+    match Command::new(program).args(args).output() {
+        Ok(output) if output.status.success() => Some(String::from_utf8_lossy(&output.stdout).to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn normalize_gender(gender: &str) -> Option<String> {
+    match gender.trim().to_lowercase().as_str() {
+        "m" | "male" => Some("male".to_string()),
+        "f" | "female" => Some("female".to_string()),
+        "neutral" => Some("neutral".to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn list_platform_voices() -> Vec<TtsVoice> {
+    let Some(output) = run_voice_listing("say", &["-v", "?"]) else {
+        return Vec::new();
+    };
+
+    // Lines look like: "Eddy (English (US)) en_US    # Hello! My name is Eddy."
+    output
+        .lines()
+        .filter_map(|line| {
+            let description = line.split('#').next()?.trim();
+            let (name, language) = description.rsplit_once(char::is_whitespace)?;
+            let name = name.trim();
+            if name.is_empty() {
+                return None;
+            }
+
+            Some(TtsVoice {
+                id: name.to_string(),
+                name: name.to_string(),
+                language: Some(language.trim().replace('_', "-")),
+                gender: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn list_platform_voices() -> Vec<TtsVoice> {
+    let script = "Add-Type -AssemblyName System.Speech; \
+        (New-Object System.Speech.Synthesis.SpeechSynthesizer).GetInstalledVoices() | \
+        ForEach-Object { $v = $_.VoiceInfo; '{0}|{1}|{2}|{3}' -f $v.Id, $v.Name, $v.Culture.Name, $v.Gender }";
+
+    let Some(output) = run_voice_listing("powershell", &["-NoProfile", "-Command", script]) else {
+        return Vec::new();
+    };
+
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.trim().split('|').collect();
+            if fields.len() < 4 || fields[0].is_empty() {
+                return None;
+            }
+
+            Some(TtsVoice {
+                id: fields[0].to_string(),
+                name: fields[1].to_string(),
+                language: Some(fields[2].to_string()).filter(|l| !l.is_empty()),
+                gender: normalize_gender(fields[3]),
+            })
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn list_platform_voices() -> Vec<TtsVoice> {
+    // Prefer speech-dispatcher, which fronts whichever synthesizer is installed
+    if let Some(output) = run_voice_listing("spd-say", &["-L"]) {
+        // Columns are NAME, LANGUAGE, VARIANT separated by runs of spaces; skip the header
+        let voices: Vec<TtsVoice> = output
+            .lines()
+            .skip(1)
+            .filter_map(|line| {
+                let fields: Vec<&str> = line
+                    .split("  ")
+                    .map(str::trim)
+                    .filter(|field| !field.is_empty())
+                    .collect();
+                let name = fields.first()?;
+
+                Some(TtsVoice {
+                    id: name.to_string(),
+                    name: name.to_string(),
+                    language: fields.get(1).map(|l| l.to_string()),
+                    gender: None,
+                })
+            })
+            .collect();
+
+        if !voices.is_empty() {
+            return voices;
+        }
+    }
+
+    // Fall back to espeak-ng/espeak, whose listing includes a gender column
+    // e.g. " 5  af              --/M      Afrikaans          gmw/af"
+    let output = run_voice_listing("espeak-ng", &["--voices"])
+        .or_else(|| run_voice_listing("espeak", &["--voices"]));
+
+    let Some(output) = output else {
+        return Vec::new();
+    };
+
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 5 {
+                return None;
+            }
+
+            let gender = fields[2].split('/').nth(1).and_then(normalize_gender);
+
+            Some(TtsVoice {
+                id: fields[4].to_string(),
+                name: fields[3].replace('_', " "),
+                language: Some(fields[1].to_string()),
+                gender,
+            })
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn list_tts_voices() -> Result<Vec<TtsVoice>, String> {
+    tokio::task::spawn_blocking(list_platform_voices)
+        .await
+        .map_err(|e| format!("Failed to list TTS voices: {}", e))
+}
@@ -0,0 +1,252 @@
+use base64::Engine;
+use minisign_verify::{PublicKey, Signature};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BundleVerificationStatus {
+    Valid,
+    // The bundle is intact but the signature doesn't match it
+    BadSignature,
+    // The signature was made with a different key than the one checked against
+    WrongKey,
+    MalformedSignature,
+    // The file isn't a readable update bundle, e.g. truncated or the wrong file
+    MalformedBundle,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateBundleVerification {
+    pub path: String,
+    pub valid: bool,
+    pub status: BundleVerificationStatus,
+    pub message: Option<String>,
+    pub format: Option<String>,
+    pub version: Option<String>,
+    pub size_bytes: u64,
+    // Signed metadata from the signature, typically the signing timestamp and file name
+    pub trusted_comment: Option<String>,
+}
+
+struct BundleInfo {
+    format: &'static str,
+    version: Option<String>,
+}
+
+// The updater stores keys and signatures as base64 of the minisign text format
+fn decode_minisign_text(encoded: &str, what: &str) -> Result<String, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| format!("The {} is not valid base64: {}", what, e))?;
+    String::from_utf8(bytes).map_err(|_| format!("The {} does not decode to text", what))
+}
+
+fn embedded_public_key(app: &AppHandle) -> Result<String, String> {
+    app.config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|updater| updater.get("pubkey"))
+        .and_then(|pubkey| pubkey.as_str())
+        .map(|pubkey| pubkey.to_string())
+        .ok_or_else(|| "No updater public key is configured for this app".to_string())
+}
+
+fn version_from_file_name(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_string_lossy().to_string();
+    let pattern = regex::Regex::new(r"_v?(\d+\.\d+\.\d+(?:-[0-9A-Za-z.]+)?)_").ok()?;
+    pattern.captures(&name).map(|captures| captures[1].to_string())
+}
+
+fn plist_version(plist: &str) -> Option<String> {
+    let pattern = regex::Regex::new(r"<key>CFBundleShortVersionString</key>\s*<string>([^<]+)</string>").ok()?;
+    pattern.captures(plist).map(|captures| captures[1].trim().to_string())
+}
+
+// Reading every entry catches truncated or corrupted archives, not just a bad header
+fn inspect_app_archive(path: &Path) -> Result<Option<String>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open bundle: {}", e))?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(BufReader::new(file)));
+    let entries = archive.entries().map_err(|e| format!("Not a gzipped tar archive: {}", e))?;
+
+    let mut entry_count = 0;
+    let mut version = None;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Archive is corrupt or truncated: {}", e))?;
+        entry_count += 1;
+
+        let is_info_plist = entry
+            .path()
+            .map(|entry_path| entry_path.ends_with("Contents/Info.plist"))
+            .unwrap_or(false);
+        let mut contents = Vec::new();
+        entry
+            .read_to_end(&mut contents)
+            .map_err(|e| format!("Archive is corrupt or truncated: {}", e))?;
+        if is_info_plist && version.is_none() {
+            version = plist_version(&String::from_utf8_lossy(&contents));
+        }
+    }
+
+    if entry_count == 0 {
+        return Err("Archive is empty".to_string());
+    }
+    Ok(version)
+}
+
+fn inspect_bundle(path: &Path) -> Result<BundleInfo, String> {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    if name.ends_with(".tar.gz") {
+        let version = inspect_app_archive(path)?.or_else(|| version_from_file_name(path));
+        return Ok(BundleInfo { format: "tar.gz", version });
+    }
+
+    // (format, extension, magic bytes) for the single-file installers the updater can apply
+    let formats: [(&str, &str, &[u8]); 6] = [
+        ("AppImage", ".appimage", b"\x7fELF"),
+        ("MSI", ".msi", b"\xd0\xcf\x11\xe0\xa1\xb1\x1a\xe1"),
+        ("NSIS", ".exe", b"MZ"),
+        ("zip", ".zip", b"PK\x03\x04"),
+        ("deb", ".deb", b"!<arch>\n"),
+        ("RPM", ".rpm", b"\xed\xab\xee\xdb"),
+    ];
+    let (format, _, magic) = formats
+        .iter()
+        .find(|(_, extension, _)| name.ends_with(extension))
+        .ok_or_else(|| "Unrecognized update bundle type; expected .app.tar.gz, .AppImage, .msi, .exe, .zip, .deb or .rpm".to_string())?;
+
+    let mut header = vec![0u8; magic.len()];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .map_err(|e| format!("Failed to read bundle header: {}", e))?;
+    if header != *magic {
+        return Err(format!("File does not have a valid {} header", format));
+    }
+
+    Ok(BundleInfo { format, version: version_from_file_name(path) })
+}
+
+fn verify_signature(path: &Path, public_key: &PublicKey, signature: &Signature) -> Result<(), minisign_verify::Error> {
+    // Stream prehashed signatures so large bundles aren't read into memory; legacy ones need the whole file
+    match public_key.verify_stream(signature) {
+        Ok(mut verifier) => {
+            let mut file = BufReader::new(File::open(path)?);
+            let mut buffer = vec![0u8; 64 * 1024];
+            loop {
+                let read = file.read(&mut buffer)?;
+                if read == 0 {
+                    break;
+                }
+                verifier.update(&buffer[..read]);
+            }
+            verifier.finalize()
+        }
+        Err(minisign_verify::Error::UnsupportedLegacyMode) => {
+            let data = std::fs::read(path)?;
+            public_key.verify(&data, signature, true)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub fn verify_bundle(path: &Path, signature: &str, public_key: &str) -> Result<UpdateBundleVerification, String> {
+    let size_bytes = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+        .len();
+
+    let mut result = UpdateBundleVerification {
+        path: path.to_string_lossy().to_string(),
+        valid: false,
+        status: BundleVerificationStatus::MalformedSignature,
+        message: None,
+        format: None,
+        version: None,
+        size_bytes,
+        trusted_comment: None,
+    };
+
+    // A signature can be passed inline or as the path to the .sig file published next to the bundle
+    let signature = match std::fs::read_to_string(signature.trim()) {
+        Ok(contents) => contents,
+        Err(_) => signature.to_string(),
+    };
+    let keys = decode_minisign_text(&signature, "signature")
+        .and_then(|signature| Signature::decode(&signature).map_err(|e| format!("Invalid signature: {}", e)))
+        .and_then(|signature| {
+            let public_key = decode_minisign_text(public_key, "public key")?;
+            let public_key = PublicKey::decode(&public_key).map_err(|e| format!("Invalid public key: {}", e))?;
+            Ok((signature, public_key))
+        });
+    let (signature, public_key) = match keys {
+        Ok(keys) => keys,
+        Err(message) => {
+            result.message = Some(message);
+            return Ok(result);
+        }
+    };
+    result.trusted_comment = Some(signature.trusted_comment().to_string());
+
+    let signature_check = verify_signature(path, &public_key, &signature);
+
+    // A malformed bundle is reported first: a truncated download fails both checks, and the bundle is the actual problem
+    match inspect_bundle(path) {
+        Ok(info) => {
+            result.format = Some(info.format.to_string());
+            result.version = info.version;
+        }
+        Err(message) => {
+            result.status = BundleVerificationStatus::MalformedBundle;
+            result.message = Some(match signature_check {
+                Ok(()) => format!("{} (the signature matches, so the bundle was published this way)", message),
+                Err(_) => message,
+            });
+            return Ok(result);
+        }
+    }
+
+    match signature_check {
+        Ok(()) => {
+            result.valid = true;
+            result.status = BundleVerificationStatus::Valid;
+        }
+        Err(minisign_verify::Error::UnexpectedKeyId) => {
+            result.status = BundleVerificationStatus::WrongKey;
+            result.message = Some("The bundle was signed with a different key than the one provided".to_string());
+        }
+        Err(minisign_verify::Error::IoError(e)) => {
+            return Err(format!("Failed to read {}: {}", path.display(), e));
+        }
+        Err(e) => {
+            result.status = BundleVerificationStatus::BadSignature;
+            result.message = Some(format!("The signature does not match this bundle: {}", e));
+        }
+    }
+
+    Ok(result)
+}
+
+// Check a bundle downloaded out-of-band the same way the updater would, without installing it
+#[tauri::command]
+pub async fn verify_update_bundle(
+    app: AppHandle,
+    path: String,
+    signature: String,
+    pubkey: Option<String>,
+) -> Result<UpdateBundleVerification, String> {
+    let public_key = match pubkey {
+        Some(pubkey) if !pubkey.trim().is_empty() => pubkey,
+        _ => embedded_public_key(&app)?,
+    };
+
+    tokio::task::spawn_blocking(move || verify_bundle(Path::new(&path), &signature, &public_key))
+        .await
+        .map_err(|e| format!("Update bundle verification failed: {}", e))?
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebviewInfo {
+    // "WebView2" on Windows, "WKWebView" on macOS, "WebKitGTK" on Linux
+    pub kind: String,
+    // None when the runtime couldn't report it, e.g. WebView2 isn't installed
+    pub version: Option<String>,
+}
+
+fn webview_kind() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "WebView2"
+    } else if cfg!(any(target_os = "macos", target_os = "ios")) {
+        "WKWebView"
+    } else if cfg!(target_os = "android") {
+        "Android System WebView"
+    } else {
+        "WebKitGTK"
+    }
+}
+
+pub fn read_webview_info() -> WebviewInfo {
+    let version = match tauri::webview_version() {
+        Ok(version) => Some(version),
+        Err(e) => {
+            eprintln!("Warning: Failed to read webview version: {}", e);
+            None
+        }
+    };
+    WebviewInfo {
+        kind: webview_kind().to_string(),
+        version,
+    }
+}
+
+#[tauri::command]
+pub async fn get_webview_info() -> Result<WebviewInfo, String> {
+    tokio::task::spawn_blocking(read_webview_info)
+        .await
+        .map_err(|e| format!("Failed to read webview info: {}", e))
+}
@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+
+use crate::database::{get_database_file_path, get_database_pool};
+use crate::secrets::DEV_KEYS_FILE;
+
+// Callers must pass this exact string, so a stray invoke can't erase everything
+const WIPE_CONFIRMATION_TOKEN: &str = "WIPE ALL DATA";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct WipeSummary {
+    pub conversations_deleted: u64,
+    pub messages_deleted: u64,
+    pub database_bytes_before: u64,
+    pub database_bytes_after: u64,
+    pub api_keys_removed: Vec<String>,
+    pub files_removed: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+async fn wipe_database(pool: &Pool<Sqlite>, summary: &mut WipeSummary) -> Result<(), String> {
+    let mut tx = pool.begin().await
+        .map_err(|e| format!("Failed to start wipe transaction: {}", e))?;
+
+    summary.messages_deleted = sqlx::query("DELETE FROM messages")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to delete messages: {}", e))?
+        .rows_affected();
+
+    summary.conversations_deleted = sqlx::query("DELETE FROM conversations")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to delete conversations: {}", e))?
+        .rows_affected();
+
+    // Restart AUTOINCREMENT ids so nothing hints at how much history existed
+    if let Err(e) = sqlx::query("DELETE FROM sqlite_sequence WHERE name IN ('messages', 'conversations')")
+        .execute(&mut *tx)
+        .await
+    {
+        summary.warnings.push(format!("Failed to reset id counters: {}", e));
+    }
+
+    tx.commit().await
+        .map_err(|e| format!("Failed to commit wipe: {}", e))?;
+
+    // DELETE only marks pages free; VACUUM rebuilds the file so the old contents are gone from disk
+    sqlx::query("VACUUM")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to compact database: {}", e))?;
+
+    // Fold the WAL back in and truncate it, since it can still hold copies of deleted pages
+    if let Err(e) = sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)").execute(pool).await {
+        summary.warnings.push(format!("Failed to truncate the write-ahead log: {}", e));
+    }
+
+    Ok(())
+}
+
+// Overwrite a file's contents before unlinking it so the bytes don't linger in free blocks
+fn overwrite_and_remove(path: &std::path::Path) -> Result<(), String> {
+    let length = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+        .len();
+    std::fs::write(path, vec![0u8; length as usize])
+        .map_err(|e| format!("Failed to overwrite {}: {}", path.display(), e))?;
+    std::fs::remove_file(path)
+        .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))
+}
+
+#[cfg(desktop)]
+fn wipe_keyring_entries(app: &AppHandle, summary: &mut WipeSummary) {
+    use crate::secrets::KEYRING_SERVICE;
+    use crate::settings::get_setting;
+    use std::collections::BTreeMap;
+    use tauri_plugin_keyring::KeyringExt;
+
+    let providers = get_setting::<BTreeMap<String, serde_json::Value>>(app, "providers")
+        .unwrap_or_else(|e| {
+            summary.warnings.push(e);
+            None
+        })
+        .unwrap_or_default();
+
+    for provider_id in providers.keys() {
+        let user = format!("provider-{}", provider_id);
+        match app.keyring().get_password(KEYRING_SERVICE, &user) {
+            Ok(Some(existing)) => {
+                // Overwrite first: some backends keep the old secret around after a plain delete
+                let filler = "0".repeat(existing.len().max(1));
+                if let Err(e) = app.keyring().set_password(KEYRING_SERVICE, &user, &filler) {
+                    summary.warnings.push(format!("Failed to overwrite key for {}: {}", provider_id, e));
+                }
+                match app.keyring().delete_password(KEYRING_SERVICE, &user) {
+                    Ok(()) => summary.api_keys_removed.push(provider_id.clone()),
+                    Err(e) => summary.warnings.push(format!("Failed to delete key for {}: {}", provider_id, e)),
+                }
+            }
+            Ok(None) => {}
+            Err(e) => summary.warnings.push(format!("Failed to read key for {}: {}", provider_id, e)),
+        }
+    }
+}
+
+#[cfg(mobile)]
+fn wipe_keyring_entries(_app: &AppHandle, summary: &mut WipeSummary) {
+    summary.warnings.push("Removing stored API keys is not supported on this platform".to_string());
+}
+
+fn wipe_dev_keys_file(app: &AppHandle, summary: &mut WipeSummary) {
+    let path = match app.path().app_data_dir() {
+        Ok(dir) => dir.join(DEV_KEYS_FILE),
+        Err(e) => {
+            summary.warnings.push(format!("Failed to resolve app data directory: {}", e));
+            return;
+        }
+    };
+
+    if path.exists() {
+        match overwrite_and_remove(&path) {
+            Ok(()) => summary.files_removed.push(path.to_string_lossy().to_string()),
+            Err(e) => summary.warnings.push(e),
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn wipe_all_data(
+    app: AppHandle,
+    confirmation: String,
+    include_api_keys: bool,
+) -> Result<WipeSummary, String> {
+    if confirmation != WIPE_CONFIRMATION_TOKEN {
+        return Err(format!("Confirmation must be exactly '{}'", WIPE_CONFIRMATION_TOKEN));
+    }
+
+    let mut summary = WipeSummary::default();
+    let database_path = get_database_file_path(&app)?;
+    summary.database_bytes_before = std::fs::metadata(&database_path).map(|m| m.len()).unwrap_or(0);
+
+    let pool = get_database_pool(&app).await?;
+    wipe_database(&pool, &mut summary).await?;
+    summary.database_bytes_after = std::fs::metadata(&database_path).map(|m| m.len()).unwrap_or(0);
+
+    if include_api_keys {
+        wipe_keyring_entries(&app, &mut summary);
+        wipe_dev_keys_file(&app, &mut summary);
+    }
+
+    Ok(summary)
+}
@@ -17,10 +17,49 @@ import { telemetryService } from './services/telemetryService'
 import { ollamaService } from './services/ollamaService'
 import { check } from '@tauri-apps/plugin-updater'
 import { relaunch } from '@tauri-apps/plugin-process'
+import { ask, message } from '@tauri-apps/plugin-dialog'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 import { getCurrentWindow } from '@tauri-apps/api/window'
 import { open } from '@tauri-apps/plugin-shell'
 import { settings, SETTINGS_KEYS } from './shared/settingsStore'
 
+interface DatabaseIntegrity {
+  ok: boolean
+  problems: string[]
+}
+
+interface DatabaseRepair {
+  backup_path: string
+  recovered_rows: number
+  warnings: string[]
+}
+
+// Offer to repair a damaged database before the stores try to load it
+async function checkDatabaseOnStartup() {
+  try {
+    const integrity = await invoke<DatabaseIntegrity>('check_database_integrity', { quick: true })
+    if (integrity.ok) return
+
+    console.error('Database integrity check failed:', integrity.problems)
+    const repair = await ask(
+      'Your chat history database appears to be damaged. Open Chat can try to recover as much as possible into a new database. The damaged file will be kept as a backup.',
+      { title: 'Database problem', kind: 'warning', okLabel: 'Repair', cancelLabel: 'Not now' }
+    )
+    if (!repair) return
+
+    const result = await invoke<DatabaseRepair>('repair_database')
+    await message(
+      `Recovered ${result.recovered_rows} rows. The damaged database was saved to ${result.backup_path}. Open Chat will now restart.`,
+      { title: 'Database repaired', kind: 'info' }
+    )
+    await relaunch()
+  } catch (error) {
+    console.error('Database repair failed:', error)
+    await message(`Open Chat couldn't repair the database: ${error}`, { title: 'Repair failed', kind: 'error' })
+  }
+}
+
 function App() {
   // Check if we're in mini window mode
   const isMiniWindow = new URLSearchParams(window.location.search).get('window') === 'mini'
@@ -80,6 +119,10 @@ function App() {
         console.warn('Telemetry initialization failed:', error)
       })
       
+      if (!isMiniWindow) {
+        await checkDatabaseOnStartup()
+      }
+
       // Initialize store
       await initializeAppStore()
       
@@ -94,6 +137,13 @@ function App() {
         console.warn('Ollama auto-start error:', error)
       })
       
+      // Refresh the sidebar when the retention policy prunes history in the background
+      listen('conversations-pruned', () => {
+        useAppStore.getState().loadConversations()
+      }).catch(error => {
+        console.warn('Failed to listen for pruned conversations:', error)
+      })
+
       // Set up sync listeners
       await messageSync.setupListeners(
         (conversationId) => {
@@ -774,6 +774,7 @@ const ChatView = forwardRef<ChatViewHandle, ChatViewProps>(function ChatView({ c
             stop: conversationSettings.stop.length > 0 ? conversationSettings.stop : undefined,
             n: conversationSettings.n,
             seed: conversationSettings.seed,
+            numThread: conversationSettings.num_thread,
           }),
           reasoningEffort
         }
@@ -11,6 +11,7 @@ export interface ConversationSettings {
   stop: string[]
   n: number
   seed?: number
+  num_thread?: number
 }
 
 export const defaultSettings: ConversationSettings = {
@@ -21,7 +22,8 @@ export const defaultSettings: ConversationSettings = {
   presence_penalty: 0.0,
   stop: [],
   n: 1,
-  seed: undefined
+  seed: undefined,
+  num_thread: undefined
 }
 
 interface ConversationSettingsModalProps {
@@ -254,6 +256,24 @@ export default function ConversationSettingsModal({
                   </p>
                 </div>
 
+                {/* CPU Threads */}
+                <div className="space-y-2">
+                  <div className="flex items-center justify-between">
+                    <label className="text-sm font-medium text-foreground">CPU Threads</label>
+                    <input
+                      type="number"
+                      min="1"
+                      value={formSettings.num_thread || ''}
+                      onChange={(e) => updateSetting('num_thread', e.target.value ? parseInt(e.target.value) : undefined)}
+                      placeholder="Auto"
+                      className="w-24 px-2 py-1 text-sm elegant-input-container rounded-lg text-right"
+                    />
+                  </div>
+                  <p className="text-xs text-muted-foreground">
+                    Threads Ollama uses for CPU inference. Best set to your performance core count minus one.
+                  </p>
+                </div>
+
               </div>
             </div>
 
@@ -5,6 +5,7 @@ import { useSettings } from '../../hooks/useSettings'
 import { open } from '@tauri-apps/plugin-dialog'
 import { readFile } from '@tauri-apps/plugin-fs'
 import { getCurrentWebview } from '@tauri-apps/api/webview'
+import { invoke } from '@tauri-apps/api/core'
 
 interface FileAttachment {
   path: string
@@ -156,6 +157,36 @@ const MessageInput = forwardRef<MessageInputHandle, MessageInputProps>(
       }
     }, [message])
 
+    // Restore this window's draft, e.g. after the mini window was closed and recreated
+    const draftLoadedRef = useRef(false)
+    useEffect(() => {
+      const windowLabel = getCurrentWebview().label
+      invoke<{ text: string } | null>('get_draft', { windowLabel })
+        .then(draft => {
+          if (draft?.text) {
+            setMessage(current => current || draft.text)
+          }
+        })
+        .catch(error => console.error('Failed to load draft:', error))
+        .finally(() => {
+          draftLoadedRef.current = true
+        })
+    }, [])
+
+    // Auto-save the draft on a debounce. An empty field is skipped rather than saved, so briefly
+    // clearing it doesn't lose the draft; sending is what clears it
+    useEffect(() => {
+      if (!draftLoadedRef.current || !message.trim()) return
+
+      const timeout = setTimeout(() => {
+        const windowLabel = getCurrentWebview().label
+        invoke('save_draft', { windowLabel, text: message })
+          .catch(error => console.error('Failed to save draft:', error))
+      }, 500)
+
+      return () => clearTimeout(timeout)
+    }, [message])
+
     // Tauri drag and drop event listener
     useEffect(() => {
       let unlisten: (() => void) | undefined
@@ -380,6 +411,8 @@ const MessageInput = forwardRef<MessageInputHandle, MessageInputProps>(
         )
         setMessage('')
         setAttachments([])
+        invoke('clear_draft', { windowLabel: getCurrentWebview().label })
+          .catch(error => console.error('Failed to clear draft:', error))
       }
     }
 
@@ -1,3 +1,4 @@
+import { invoke } from '@tauri-apps/api/core'
 import { type CreateMessageInput, messageStore } from '../shared/messageStore'
 import { tokenService } from './tokenService'
 
@@ -29,6 +30,7 @@ export interface ModelConfig {
   stop?: string[]
   n?: number
   seed?: number
+  numThread?: number
 }
 
 export interface SendMessageOptions {
@@ -63,6 +65,7 @@ class ChatService {
       stop?: string[]
       n?: number
       seed?: number
+      numThread?: number
     }
   ): Promise<ModelConfig[]> {
     return Promise.all(
@@ -368,6 +371,7 @@ class ChatService {
       ...(modelConfig.n !== undefined && { n: modelConfig.n }),
       ...(modelConfig.seed !== undefined && { seed: modelConfig.seed }),
       ...(modelConfig.reasoningEffort !== undefined && modelConfig.reasoningEffort !== 'none' && { reasoning_effort: modelConfig.reasoningEffort }),
+      ...(isOllama && modelConfig.numThread !== undefined && { options: { num_thread: modelConfig.numThread } }),
     }
 
     // Build headers
@@ -419,6 +423,17 @@ class ChatService {
       } catch (e) {
         // Ignore errors reading response body
       }
+      // A key that works elsewhere can be rejected here because the system clock is off
+      if (response.status === 401 || response.status === 403) {
+        try {
+          const skew = await invoke<{ warning: string | null }>('check_clock_skew')
+          if (skew.warning) {
+            errorText = `${errorText}\n\n${skew.warning}`
+          }
+        } catch {
+          // The diagnostic is best-effort
+        }
+      }
       throw new Error(errorText)
     }
 
@@ -230,9 +230,13 @@ export class OllamaService {
           return { success: false, message: 'Ollama stop signal sent but service still accessible' };
         }
       } catch (invokeError) {
+        // Tauri commands reject with the error string itself
+        const reason = typeof invokeError === 'string'
+          ? invokeError
+          : invokeError instanceof Error ? invokeError.message : 'Unknown error';
         return { 
           success: false, 
-          message: `Failed to stop Ollama: ${invokeError instanceof Error ? invokeError.message : 'Unknown error'}` 
+          message: `Failed to stop Ollama: ${reason}` 
         };
       }
     } catch (error) {
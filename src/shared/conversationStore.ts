@@ -67,8 +67,26 @@ class ConversationDatabase {
         }
       }
       
+      // Migration 2: Add deleted_at column for the retention trash. schema_version is shared
+      // with the message store, which can already have moved it past 2, so check the table itself.
+      const columns = await this.db.select(`PRAGMA table_info(conversations)`) as { name: string }[]
+      if (!columns.some(column => column.name === 'deleted_at')) {
+        try {
+          await this.db.execute(`ALTER TABLE conversations ADD COLUMN deleted_at DATETIME`)
+          console.log('Migration 2: Added deleted_at column to conversations table')
+        } catch (err) {
+          const errorMessage = (err as Error).message
+          if (errorMessage.includes('duplicate column name')) {
+            // The backend may have added it in the meantime
+            console.log('Migration 2: deleted_at column already exists')
+          } else {
+            console.error('Migration 2 failed:', errorMessage)
+          }
+        }
+      }
+
       // Future migrations can be added here with version checks
-      // if (currentVersion < 2) { ... }
+      // if (currentVersion < 3) { ... }
       
     } catch (err) {
       console.error('Failed to run migrations:', err)
@@ -99,7 +117,8 @@ class ConversationDatabase {
   async getConversations() {
     const db = await this.init()
     const conversations = await db.select(
-      'SELECT * FROM conversations ORDER BY updated_at DESC'
+      // Conversations pruned by the retention policy stay in the trash until purged
+      'SELECT * FROM conversations WHERE deleted_at IS NULL ORDER BY updated_at DESC'
     ) as any[]
     
     // Convert SQLite integers to booleans for is_favorite
@@ -219,6 +238,7 @@ export interface Conversation {
   system_prompt: string | null
   is_favorite: boolean
   settings: string | null // JSON string for conversation-specific settings
+  summary?: string | null // Written by the summarize_conversation command
   created_at: string
   updated_at: string
 }
\ No newline at end of file
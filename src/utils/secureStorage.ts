@@ -1,11 +1,19 @@
 import { getPassword, setPassword, deletePassword } from "tauri-plugin-keyring-api"
 import { BaseDirectory, readTextFile, writeTextFile, exists } from '@tauri-apps/plugin-fs'
+import { invoke } from '@tauri-apps/api/core'
 
 const SERVICE_NAME = "open-chat"
 const KEYS_FILE = "keys.enc"
 
-// Check if we're in development mode
-const isDev = import.meta.env.DEV || import.meta.env.MODE === 'development'
+// Ask the backend which store to use so both sides agree (it imports keys into the same store)
+let devModePromise: Promise<boolean> | null = null
+
+function usesDevStorage(): Promise<boolean> {
+  if (!devModePromise) {
+    devModePromise = invoke<boolean>('uses_dev_key_storage').catch(() => import.meta.env.DEV)
+  }
+  return devModePromise
+}
 
 // Simple XOR encryption for dev mode storage
 const ENCRYPTION_KEY = "open-chat-2024-secure-key-storage-v1-dev-only"
@@ -39,8 +47,8 @@ function simpleDecrypt(encrypted: string): string {
 // In-memory cache for API keys (used in both dev and prod for performance)
 const keyCache = new Map<string, string>()
 let devKeysData: Record<string, string> = {}
-let devInitialized = false
 
+// Always re-read the file, since the backend can add keys to it (e.g. importing from .env)
 async function loadDevKeys(): Promise<void> {
   try {
     if (await exists(KEYS_FILE, { baseDir: BaseDirectory.AppData })) {
@@ -70,13 +78,6 @@ async function saveDevKeys(): Promise<void> {
   }
 }
 
-async function ensureDevInitialized() {
-  if (!devInitialized) {
-    await loadDevKeys()
-    devInitialized = true
-  }
-}
-
 /**
  * Save an API key - uses encrypted file in dev, keychain in prod
  */
@@ -84,9 +85,9 @@ export async function saveApiKey(providerId: string, apiKey: string): Promise<vo
   const key = `provider-${providerId}`
   
   try {
-    if (isDev) {
+    if (await usesDevStorage()) {
       // Development: Use encrypted file storage
-      await ensureDevInitialized()
+      await loadDevKeys()
       devKeysData[key] = apiKey
       keyCache.set(key, apiKey)
       await saveDevKeys()
@@ -115,9 +116,9 @@ export async function getApiKey(providerId: string): Promise<string | null> {
   }
   
   try {
-    if (isDev) {
-      // Development: Use encrypted file storage
-      await ensureDevInitialized()
+    if (await usesDevStorage()) {
+      // Development: Use encrypted file storage; a cache miss may be a key the backend just imported
+      await loadDevKeys()
       const value = devKeysData[key] || null
       if (value) {
         keyCache.set(key, value)
@@ -146,9 +147,9 @@ export async function deleteApiKey(providerId: string): Promise<void> {
   try {
     keyCache.delete(key) // Clear cache in both modes
     
-    if (isDev) {
+    if (await usesDevStorage()) {
       // Development: Use encrypted file storage
-      await ensureDevInitialized()
+      await loadDevKeys()
       delete devKeysData[key]
       await saveDevKeys()
       console.log(`[DEV MODE] Deleted API key for ${providerId} from encrypted file`)
@@ -175,9 +176,9 @@ export async function hasApiKey(providerId: string): Promise<boolean> {
   }
   
   try {
-    if (isDev) {
+    if (await usesDevStorage()) {
       // Development: Use encrypted file storage
-      await ensureDevInitialized()
+      await loadDevKeys()
       return key in devKeysData
     } else {
       // Production: Use system keychain